@@ -116,4 +116,41 @@ teardown_file() {
   exec_graphql 'account-with-balance' "$variables"
   balance=$(graphql_output '.data.account.balance.settled.normalBalance.units')
   [[ $balance == "9.53" ]] || exit 1
+
+  # filter entries by effective date
+  variables=$(
+    jq -n \
+    --arg transaction_id "$(random_uuid)" \
+    --arg account_id "$liability_account_id" \
+    --arg depositTemplateId "$deposit_template_id" \
+    '{
+      "input": {
+        "transactionId": $transaction_id,
+        "txTemplateCode": ("DEPOSIT-" + $depositTemplateId),
+        "params": {
+          "account": $account_id,
+          "amount": "1.00",
+          "effective": "2023-01-10"
+        }
+      }
+    }'
+  )
+  exec_graphql 'transaction-post' "$variables"
+
+  variables=$(jq -n --arg accountId "$liability_account_id" '{ "accountId": $accountId, "first": 10 }')
+  exec_graphql 'account-entries' "$variables"
+  [[ $(graphql_output '.data.account.entries.nodes | length') == "2" ]] || exit 1
+
+  variables=$(jq -n \
+    --arg accountId "$liability_account_id" \
+    '{
+      "accountId": $accountId,
+      "first": 10,
+      "filter": { "effectiveFrom": "2023-01-01" }
+    }'
+  )
+  exec_graphql 'account-entries' "$variables"
+  [[ $(graphql_output '.data.account.entries.nodes | length') == "1" ]] || exit 1
+  effective=$(graphql_output '.data.account.entries.nodes[0].transaction.effective')
+  [[ $effective == "2023-01-10" ]] || exit 1
 }
@@ -0,0 +1,84 @@
+# Subscribes via the graphql-transport-ws protocol and records the data
+# of every received event as JSON lines.
+# usage: subscription-client.py <url> <query-file> <variables> <log>
+import base64
+import json
+import os
+import socket
+import struct
+import sys
+import urllib.parse
+
+URL = urllib.parse.urlparse(sys.argv[1])
+QUERY = open(sys.argv[2]).read()
+VARIABLES = json.loads(sys.argv[3])
+LOG = sys.argv[4]
+
+
+def send(sock, message):
+    payload = json.dumps(message).encode()
+    header = bytes([0x81])
+    if len(payload) < 126:
+        header += bytes([0x80 | len(payload)])
+    elif len(payload) < 65536:
+        header += bytes([0x80 | 126]) + struct.pack("!H", len(payload))
+    else:
+        header += bytes([0x80 | 127]) + struct.pack("!Q", len(payload))
+    mask = os.urandom(4)
+    masked = bytes(b ^ mask[i % 4] for i, b in enumerate(payload))
+    sock.sendall(header + mask + masked)
+
+
+def read_exact(sock, n):
+    data = b""
+    while len(data) < n:
+        chunk = sock.recv(n - len(data))
+        if not chunk:
+            raise EOFError
+        data += chunk
+    return data
+
+
+def receive(sock):
+    first, second = read_exact(sock, 2)
+    length = second & 0x7F
+    if length == 126:
+        (length,) = struct.unpack("!H", read_exact(sock, 2))
+    elif length == 127:
+        (length,) = struct.unpack("!Q", read_exact(sock, 8))
+    payload = read_exact(sock, length)
+    if first & 0x0F == 0x8:
+        raise EOFError
+    return json.loads(payload) if first & 0x0F == 0x1 else None
+
+
+sock = socket.create_connection((URL.hostname, URL.port))
+key = base64.b64encode(os.urandom(16)).decode()
+sock.sendall(
+    (
+        f"GET {URL.path} HTTP/1.1\r\n"
+        f"Host: {URL.netloc}\r\n"
+        "Upgrade: websocket\r\n"
+        "Connection: Upgrade\r\n"
+        f"Sec-WebSocket-Key: {key}\r\n"
+        "Sec-WebSocket-Version: 13\r\n"
+        "Sec-WebSocket-Protocol: graphql-transport-ws\r\n\r\n"
+    ).encode()
+)
+response = b""
+while b"\r\n\r\n" not in response:
+    response += read_exact(sock, 1)
+if b" 101 " not in response.split(b"\r\n")[0]:
+    sys.exit(f"upgrade failed: {response.decode()}")
+
+send(sock, {"type": "connection_init"})
+send(sock, {"id": "1", "type": "subscribe", "payload": {"query": QUERY, "variables": VARIABLES}})
+while True:
+    message = receive(sock)
+    if message is None:
+        continue
+    if message["type"] == "ping":
+        send(sock, {"type": "pong"})
+    elif message["type"] in ("next", "error"):
+        with open(LOG, "a") as f:
+            f.write(json.dumps(message["payload"]) + "\n")
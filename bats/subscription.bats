@@ -0,0 +1,115 @@
+#!/usr/bin/env bats
+
+load "helpers"
+
+WS_ENDPOINT="ws://localhost:2252/graphql/ws"
+EVENTS_LOG="${CALA_HOME}/subscription-events"
+
+setup_file() {
+  start_server
+}
+
+teardown_file() {
+  stop_server
+  stop_subscription
+}
+
+subscribe() {
+  rm -f "${EVENTS_LOG}"
+  background python3 "${REPO_ROOT}/bats/subscription-client.py" \
+    "${WS_ENDPOINT}" "$(gql_file 'transaction-created-subscription')" "$1" "${EVENTS_LOG}" \
+    > "${CALA_HOME}/subscription-pid"
+  sleep 1
+}
+
+stop_subscription() {
+  if [[ -f "${CALA_HOME}/subscription-pid" ]]; then
+    kill $(cat "${CALA_HOME}/subscription-pid") || true
+    rm -f "${CALA_HOME}/subscription-pid"
+  fi
+}
+
+received() {
+  jq -s -r '.[].data.transactionCreated.transaction.transactionId' "${EVENTS_LOG}"
+}
+
+wait_for_transaction() {
+  [[ -f "${EVENTS_LOG}" ]] && received | grep -q "$1"
+}
+
+post_deposit() {
+  variables=$(
+    jq -n \
+    --arg transaction_id "$1" \
+    --arg account_id "$2" \
+    --arg depositTemplateId "$3" \
+    '{
+      "input": {
+        "transactionId": $transaction_id,
+        "txTemplateCode": ("DEPOSIT-" + $depositTemplateId),
+        "params": {
+          "account": $account_id,
+          "amount": "9.53",
+          "effective": "2022-09-21"
+        }
+      }
+    }'
+  )
+  exec_graphql 'transaction-post' "$variables"
+  [[ $(graphql_output '.data.transactionPost.transaction.transactionId') == "$1" ]] || exit 1
+}
+
+@test "subscription: receive created transactions and resume after a sequence" {
+  journal_id=$(random_uuid)
+  variables=$(jq -n --arg journalId "$journal_id" '{ input: { journalId: $journalId, name: "Subscriptions" } }')
+  exec_graphql 'journal-create' "$variables"
+  [[ $(graphql_output '.data.journalCreate.journal.journalId') == "$journal_id" ]] || exit 1
+
+  liability_account_id=$(random_uuid)
+  variables=$(
+    jq -n \
+    --arg accountId "$liability_account_id" \
+    '{ input: { accountId: $accountId, name: "Liability", code: ("LIABILITY-" + $accountId), normalBalanceType: "CREDIT" } }'
+  )
+  exec_graphql 'account-create' "$variables"
+  asset_account_id=$(random_uuid)
+  variables=$(
+    jq -n \
+    --arg accountId "$asset_account_id" \
+    '{ input: { accountId: $accountId, name: "Assets", code: ("ASSET-" + $accountId), normalBalanceType: "DEBIT" } }'
+  )
+  exec_graphql 'account-create' "$variables"
+
+  deposit_template_id=$(random_uuid)
+  withdrawal_template_id=$(random_uuid)
+  variables=$(jq -n \
+  --arg depositTemplateId "$deposit_template_id" \
+  --arg withdrawalTemplateId "$withdrawal_template_id" \
+  --arg assetAccountId "$asset_account_id" \
+  --arg journalId "$journal_id" \
+  '{
+    "depositTemplateId": $depositTemplateId,
+    "depositTemplateCode": ("DEPOSIT-" + $depositTemplateId),
+    "withdrawalTemplateId": $withdrawalTemplateId,
+    "withdrawalTemplateCode": ("WITHDRAWAL-" + $withdrawalTemplateId),
+    "assetAccountId": ("uuid(\u0027" + $assetAccountId + "\u0027)"),
+    "journalId": ("uuid(\u0027" + $journalId + "\u0027)")
+  }')
+  exec_graphql 'tx-template-create' "$variables"
+
+  subscribe "$(jq -n -c --arg journalId "$journal_id" '{ journalId: $journalId }')"
+  first_transaction_id=$(random_uuid)
+  post_deposit "$first_transaction_id" "$liability_account_id" "$deposit_template_id"
+  retry 20 1 wait_for_transaction "$first_transaction_id"
+  [[ $(jq -s -r '.[0].data.transactionCreated.transaction.journalId' "${EVENTS_LOG}") == "$journal_id" ]] || exit 1
+  sequence=$(jq -s -r '.[0].data.transactionCreated.sequence' "${EVENTS_LOG}")
+  stop_subscription
+
+  second_transaction_id=$(random_uuid)
+  post_deposit "$second_transaction_id" "$liability_account_id" "$deposit_template_id"
+
+  subscribe "$(jq -n -c --arg journalId "$journal_id" --argjson startAfter "$sequence" '{ journalId: $journalId, startAfter: $startAfter }')"
+  retry 20 1 wait_for_transaction "$second_transaction_id"
+  [[ $(received | head -n 1) == "$second_transaction_id" ]] || exit 1
+  [[ $(received | grep -c "$first_transaction_id") == "0" ]] || exit 1
+}
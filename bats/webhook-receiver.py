@@ -0,0 +1,26 @@
+# Records webhook deliveries as JSON lines and answers 500
+# while a file named <log>.fail exists.
+import http.server
+import json
+import os
+import sys
+
+PORT = int(sys.argv[1])
+LOG = sys.argv[2]
+
+
+class Handler(http.server.BaseHTTPRequestHandler):
+    def do_POST(self):
+        body = self.rfile.read(int(self.headers["Content-Length"]))
+        status = 500 if os.path.exists(LOG + ".fail") else 200
+        headers = {k.lower(): v for k, v in self.headers.items()}
+        with open(LOG, "a") as f:
+            f.write(json.dumps({"status": status, "headers": headers, "body": body.decode()}) + "\n")
+        self.send_response(status)
+        self.end_headers()
+
+    def log_message(self, *args):
+        pass
+
+
+http.server.HTTPServer(("127.0.0.1", PORT), Handler).serve_forever()
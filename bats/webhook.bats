@@ -0,0 +1,109 @@
+#!/usr/bin/env bats
+
+load "helpers"
+
+RECEIVER_PORT=2260
+RECEIVER_LOG="${CALA_HOME}/webhook-deliveries"
+
+setup_file() {
+  start_server
+  rm -f "${RECEIVER_LOG}" "${RECEIVER_LOG}.fail"
+  background python3 "${REPO_ROOT}/bats/webhook-receiver.py" "${RECEIVER_PORT}" "${RECEIVER_LOG}" > "${CALA_HOME}/webhook-receiver-pid"
+  sleep 1
+}
+
+teardown_file() {
+  stop_server
+  kill $(cat "${CALA_HOME}/webhook-receiver-pid") || true
+}
+
+create_journal() {
+  variables=$(jq -n --arg journalId "$(random_uuid)" '{ input: { journalId: $journalId, name: "Webhook" } }')
+  exec_graphql 'journal-create' "$variables"
+  graphql_output '.data.journalCreate.journal.journalId'
+}
+
+delivered() {
+  jq -s --arg journalId "$1" --arg status "$2" \
+    '[.[] | select(.status == ($status | tonumber) and (.body | fromjson | .payload.journal.id) == $journalId)] | length' \
+    "${RECEIVER_LOG}"
+}
+
+wait_for_delivery() {
+  [[ $(delivered "$1" "$2") -gt 0 ]] || return 1
+}
+
+@test "webhook: deliver signed events" {
+  variables=$(
+    jq -n \
+      --arg name "webhook-$(random_uuid)" \
+      --arg url "http://127.0.0.1:${RECEIVER_PORT}/hook" \
+    '{
+      input: {
+        name: $name,
+        url: $url,
+        secret: "webhook-secret",
+        payloadTypes: ["journal_created"]
+      }
+    }'
+  )
+  exec_graphql 'cala-outbox-webhook-create' "$variables"
+  webhook_id=$(graphql_output '.data.calaOutboxWebhookCreate.webhook.webhookId')
+  [[ "$webhook_id" != "null" ]] || exit 1
+  echo "$webhook_id" > "${CALA_HOME}/webhook-id"
+
+  journal_id=$(create_journal)
+  retry 20 1 wait_for_delivery "$journal_id" 200
+
+  delivery=$(jq -s -c --arg journalId "$journal_id" \
+    '[.[] | select((.body | fromjson | .payload.journal.id) == $journalId)][0]' "${RECEIVER_LOG}")
+  [[ $(echo "$delivery" | jq -r '.headers["x-cala-event-type"]') == "journal_created" ]] || exit 1
+  timestamp=$(echo "$delivery" | jq -r '.headers["x-cala-timestamp"]')
+  body=$(echo "$delivery" | jq -j '.body')
+  expected="sha256=$(printf '%s.%s' "$timestamp" "$body" | openssl dgst -sha256 -hmac "webhook-secret" | sed 's/^.* //')"
+  [[ $(echo "$delivery" | jq -r '.headers["x-cala-signature"]') == "$expected" ]] || exit 1
+}
+
+@test "webhook: dead-letter and redeliver failed events" {
+  webhook_id=$(cat "${CALA_HOME}/webhook-id")
+  touch "${RECEIVER_LOG}.fail"
+  journal_id=$(create_journal)
+
+  for i in {1..90}; do
+    exec_graphql 'cala-outbox-webhook-dead-letters' "{\"webhookId\": \"$webhook_id\"}"
+    dead_letter_id=$(graphql_output '.data.calaOutboxWebhookDeadLetters[0].deadLetterId')
+    [[ "$dead_letter_id" != "null" ]] && break
+    sleep 1
+  done
+  [[ "$dead_letter_id" != "null" ]] || exit 1
+  [[ $(graphql_output '.data.calaOutboxWebhookDeadLetters[0].attempts') == "5" ]] || exit 1
+  [[ $(delivered "$journal_id" 200) == "0" ]] || exit 1
+
+  rm "${RECEIVER_LOG}.fail"
+  exec_graphql 'cala-outbox-webhook-dead-letter-redeliver' "{\"input\": {\"deadLetterId\": \"$dead_letter_id\"}}"
+  [[ $(graphql_output '.data.calaOutboxWebhookDeadLetterRedeliver.deadLetter.redeliveredAt') != "null" ]] || exit 1
+  [[ $(delivered "$journal_id" 200) == "1" ]] || exit 1
+}
+
+@test "webhook: reject targets that aren't allowed" {
+  for url in "http://169.254.169.254/latest/meta-data" "http://localhost:${RECEIVER_PORT}/hook" "http://10.0.0.1/hook"; do
+    variables=$(
+      jq -n \
+        --arg name "webhook-$(random_uuid)" \
+        --arg url "$url" \
+      '{ input: { name: $name, url: $url, secret: "webhook-secret" } }'
+    )
+    exec_graphql 'cala-outbox-webhook-create' "$variables"
+    [[ $(graphql_output '.errors[0].message') =~ "HostNotAllowed" ]] || exit 1
+  done
+}
+
+@test "webhook: delete stops deliveries" {
+  webhook_id=$(cat "${CALA_HOME}/webhook-id")
+  exec_graphql 'cala-outbox-webhook-delete' "{\"input\": {\"webhookId\": \"$webhook_id\"}}"
+  [[ $(graphql_output '.data.calaOutboxWebhookDelete.webhook.webhookId') == "$webhook_id" ]] || exit 1
+
+  journal_id=$(create_journal)
+  sleep 3
+  [[ $(delivered "$journal_id" 200) == "0" ]] || exit 1
+}
@@ -0,0 +1,166 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use uuid::Uuid;
+
+use cala_cel_interpreter::{CelContext, CelExpression, CelMap, CelValue};
+
+const CONSTANTS: [&str; 5] = ["SETTLED", "PENDING", "ENCUMBRANCE", "DEBIT", "CREDIT"];
+
+/// The expressions of a transfer with an optional fee, as evaluated per posting.
+const TRANSFER_TEMPLATE: [&str; 20] = [
+    "params.journal_id",
+    "params.effective",
+    "params.correlation_id",
+    "{'reference': params.reference, 'fee': params.fee}",
+    "'TRANSFER_DR'",
+    "params.sender",
+    "SETTLED",
+    "DEBIT",
+    "params.amount",
+    "params.currency",
+    "'TRANSFER_CR'",
+    "params.recipient",
+    "SETTLED",
+    "CREDIT",
+    "params.amount",
+    "params.currency",
+    "params.fee > decimal('0')",
+    "params.fee > decimal('0') ? params.fee : decimal('0')",
+    "decimal.round(params.amount * decimal('0.015'), 2)",
+    "uuid('00000000-0000-0000-0000-000000000001')",
+];
+
+/// Conditions and limits of velocity controls, evaluated per entry.
+const VELOCITY_CONTROLS: [&str; 6] = [
+    "context.vars.account.metadata.tier.startsWith('prem') && size(context.vars.entry.layer) > 0",
+    "context.vars.entry.units > decimal('1000') || context.vars.entry.direction.contains('DE')",
+    "has(context.vars.transaction.metadata.exempt) ? false : true",
+    "decimal('10000') * decimal('0.5')",
+    "date('2024-01-01').addMonths(1)",
+    "context.vars.account.code.startsWith('ACC-')",
+];
+
+fn template_context() -> CelContext {
+    let mut ctx = CelContext::new();
+    for constant in CONSTANTS {
+        ctx.add_variable(constant, constant);
+    }
+    let mut params = CelMap::new();
+    params.insert("journal_id", Uuid::new_v4());
+    params.insert("sender", Uuid::new_v4());
+    params.insert("recipient", Uuid::new_v4());
+    params.insert("amount", CelValue::Decimal("1290.50".parse().unwrap()));
+    params.insert("fee", CelValue::Decimal("2.5".parse().unwrap()));
+    params.insert("currency", "USD");
+    params.insert("reference", "invoice-42");
+    params.insert("correlation_id", "corr-42");
+    params.insert(
+        "effective",
+        chrono::NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+    );
+    ctx.add_variable("params", params);
+    ctx
+}
+
+fn velocity_context() -> CelContext {
+    let mut ctx = CelContext::new();
+    for constant in CONSTANTS {
+        ctx.add_variable(constant, constant);
+    }
+    let mut account_meta = CelMap::new();
+    account_meta.insert("tier", "premium");
+    let mut account = CelMap::new();
+    account.insert("code", "ACC-001");
+    account.insert("metadata", account_meta);
+    let mut entry = CelMap::new();
+    entry.insert("layer", "SETTLED");
+    entry.insert("direction", "DEBIT");
+    entry.insert("units", CelValue::Decimal("1290.50".parse().unwrap()));
+    let mut transaction = CelMap::new();
+    transaction.insert("metadata", CelMap::new());
+    let mut vars = CelMap::new();
+    vars.insert("account", account);
+    vars.insert("entry", entry);
+    vars.insert("transaction", transaction);
+    let mut context = CelMap::new();
+    context.insert("vars", vars);
+    ctx.add_variable("context", context);
+    ctx
+}
+
+fn bench_expressions(c: &mut Criterion, name: &str, sources: &[&str], ctx: &CelContext) {
+    let exprs: Vec<CelExpression> = sources.iter().map(|s| s.parse().unwrap()).collect();
+    let mut group = c.benchmark_group(name);
+    group.bench_function("interpreted", |b| {
+        b.iter(|| {
+            for expr in exprs.iter() {
+                black_box(expr.interpret(ctx).unwrap());
+            }
+        })
+    });
+    group.bench_function("compiled", |b| {
+        b.iter(|| {
+            for expr in exprs.iter() {
+                black_box(expr.evaluate(ctx).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+/// Includes parsing and compiling, as done when an expression isn't cached.
+/// Compare against the `compiled` benchmark of the cached expressions.
+fn bench_end_to_end(c: &mut Criterion, name: &str, sources: &[&str], ctx: &CelContext) {
+    let mut group = c.benchmark_group(format!("{name}_end_to_end"));
+    group.bench_function("parse_and_compile", |b| {
+        b.iter(|| {
+            for source in sources.iter() {
+                black_box(black_box(source).parse::<CelExpression>().unwrap());
+            }
+        })
+    });
+    group.bench_function("parse_compile_and_evaluate", |b| {
+        b.iter(|| {
+            for source in sources.iter() {
+                let expr: CelExpression = black_box(source).parse().unwrap();
+                black_box(expr.evaluate(ctx).unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+fn transfer_template(c: &mut Criterion) {
+    bench_expressions(
+        c,
+        "transfer_template",
+        &TRANSFER_TEMPLATE,
+        &template_context(),
+    );
+}
+
+fn velocity_controls(c: &mut Criterion) {
+    bench_expressions(
+        c,
+        "velocity_controls",
+        &VELOCITY_CONTROLS,
+        &velocity_context(),
+    );
+}
+
+fn end_to_end(c: &mut Criterion) {
+    bench_end_to_end(
+        c,
+        "transfer_template",
+        &TRANSFER_TEMPLATE,
+        &template_context(),
+    );
+    bench_end_to_end(
+        c,
+        "velocity_controls",
+        &VELOCITY_CONTROLS,
+        &velocity_context(),
+    );
+}
+
+criterion_group!(benches, transfer_template, velocity_controls, end_to_end);
+criterion_main!(benches);
@@ -0,0 +1,213 @@
+//! Evaluates CEL expressions offline, eg. to try out the `units`, `effective`
+//! or `condition` expressions of a tx template before creating it.
+//! The ledger constants (`SETTLED`, `DEBIT`, ...) are predefined.
+//!
+//! ```text
+//! cala-cel --context '{"amount": "10"}' "decimal(amount) * decimal('1.1')"
+//! cala-cel --var "amount=decimal('10')" --ast "amount > decimal('5')"
+//! cala-cel --context @params.json     # starts a REPL
+//! ```
+use anyhow::Context;
+use clap::Parser;
+use std::io::{BufRead, IsTerminal, Write};
+
+use cala_cel_interpreter::{CelContext, CelError, CelExpression, CelType, CelValue};
+
+/// The constants cala-ledger predefines for tx template and velocity expressions.
+const LEDGER_CONSTANTS: [&str; 5] = ["SETTLED", "PENDING", "ENCUMBRANCE", "DEBIT", "CREDIT"];
+
+#[derive(Parser)]
+#[clap(version, long_about = None)]
+/// Parse and evaluate CEL expressions. Starts a REPL if no expression is given.
+struct Cli {
+    /// JSON object whose top level keys become variables (`@FILE` to read from a file)
+    #[clap(short, long, value_name = "JSON")]
+    context: Option<String>,
+    /// Bind NAME to the result of a CEL expression (evaluated in order)
+    #[clap(short, long = "var", value_name = "NAME=EXPR")]
+    vars: Vec<String>,
+    /// Print the syntax tree of the expression
+    #[clap(long)]
+    ast: bool,
+    expression: Option<String>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let mut ctx = ledger_context();
+    if let Some(context) = cli.context {
+        load_context(&mut ctx, &context)?;
+    }
+    for var in cli.vars {
+        bind(&mut ctx, &var)?;
+    }
+
+    match cli.expression {
+        Some(expression) => {
+            let expr = parse(&expression)?;
+            if cli.ast {
+                println!("{:#?}", expr.ast());
+            }
+            let value = expr.evaluate(&ctx).map_err(report)?;
+            println!("{}", describe(&value));
+        }
+        None => repl(ctx, cli.ast)?,
+    }
+    Ok(())
+}
+
+fn ledger_context() -> CelContext {
+    let mut ctx = CelContext::new();
+    for constant in LEDGER_CONSTANTS {
+        ctx.add_variable(constant, constant);
+    }
+    ctx
+}
+
+fn load_context(ctx: &mut CelContext, context: &str) -> anyhow::Result<()> {
+    let json = match context.strip_prefix('@') {
+        Some(path) => std::fs::read_to_string(path).context(format!("Reading '{path}'"))?,
+        None => context.to_string(),
+    };
+    let serde_json::Value::Object(vars) = serde_json::from_str(&json).context("Parsing context")?
+    else {
+        anyhow::bail!("Context must be a JSON object");
+    };
+    for (name, value) in vars {
+        ctx.add_variable(name, CelValue::from(value));
+    }
+    Ok(())
+}
+
+fn bind(ctx: &mut CelContext, var: &str) -> anyhow::Result<()> {
+    let Some((name, expression)) = var.split_once('=') else {
+        anyhow::bail!("Expected NAME=EXPR but got '{var}'");
+    };
+    let value = parse(expression.trim())?.evaluate(ctx).map_err(report)?;
+    ctx.add_variable(name.trim().to_string(), value);
+    Ok(())
+}
+
+fn repl(mut ctx: CelContext, mut ast: bool) -> anyhow::Result<()> {
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        eprintln!("Commands: ':let NAME = EXPR', ':ast' (toggle syntax tree), ':quit'");
+    }
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("cel> ");
+            std::io::stdout().flush()?;
+        }
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let line = line.trim();
+        let res = match line {
+            "" => continue,
+            ":quit" | ":q" => break,
+            ":ast" => {
+                ast = !ast;
+                continue;
+            }
+            _ => match line.strip_prefix(":let ") {
+                Some(var) => bind(&mut ctx, var),
+                None => parse(line).and_then(|expr| {
+                    if ast {
+                        println!("{:#?}", expr.ast());
+                    }
+                    let value = expr.evaluate(&ctx).map_err(report)?;
+                    println!("{}", describe(&value));
+                    Ok(())
+                }),
+            },
+        };
+        if let Err(e) = res {
+            eprintln!("{e}");
+        }
+    }
+    Ok(())
+}
+
+fn parse(expression: &str) -> anyhow::Result<CelExpression> {
+    expression.parse::<CelExpression>().map_err(report)
+}
+
+fn describe(value: &CelValue) -> String {
+    format!("{value} : {:?}", CelType::from(value))
+}
+
+fn report(e: CelError) -> anyhow::Error {
+    match (e.location(), e.sub_expression()) {
+        (Some(location), Some(sub)) => {
+            anyhow::anyhow!("{} (at {location} in '{sub}')", e.root_cause())
+        }
+        (Some(location), None) => anyhow::anyhow!("{} (at {location})", e.root_cause()),
+        _ => anyhow::anyhow!("{e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_context_and_vars() -> anyhow::Result<()> {
+        let mut ctx = CelContext::new();
+        load_context(&mut ctx, r#"{"params": {"rate": 1.5, "code": "ABC"}}"#)?;
+        bind(&mut ctx, "amount = decimal('10')")?;
+        bind(&mut ctx, "total=amount + decimal('2')")?;
+
+        assert_eq!(
+            describe(&parse("total")?.evaluate(&ctx)?),
+            "decimal('12') : Decimal"
+        );
+        assert_eq!(
+            describe(&parse("params.rate")?.evaluate(&ctx)?),
+            "1.5 : Double"
+        );
+        assert_eq!(
+            describe(&parse("params.code")?.evaluate(&ctx)?),
+            "\"ABC\" : String"
+        );
+        assert!(load_context(&mut ctx, "[1]").is_err());
+        assert!(bind(&mut ctx, "amount").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn predefines_ledger_constants() -> anyhow::Result<()> {
+        let ctx = ledger_context();
+        assert_eq!(
+            describe(&parse("SETTLED")?.evaluate(&ctx)?),
+            "\"SETTLED\" : String"
+        );
+        assert_eq!(
+            describe(&parse("DEBIT")?.evaluate(&ctx)?),
+            "\"DEBIT\" : String"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn describes_values() -> anyhow::Result<()> {
+        let ctx = CelContext::new();
+        let describe_expr = |e: &str| -> anyhow::Result<String> {
+            Ok(describe(&parse(e)?.evaluate(&ctx).map_err(report)?))
+        };
+        assert_eq!(
+            describe_expr("date('2024-01-31').addMonths(1)")?,
+            "date('2024-02-29') : Date"
+        );
+        assert_eq!(
+            describe_expr("duration('1h30m')")?,
+            "duration('5400s') : Duration"
+        );
+        assert_eq!(describe_expr("size('abc') > 2")?, "true : Bool");
+        let err = describe_expr("1 +\n unknown").unwrap_err();
+        assert!(err.to_string().contains("line 2, column 2"));
+        Ok(())
+    }
+}
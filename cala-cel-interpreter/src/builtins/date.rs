@@ -0,0 +1,71 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+use crate::{cel_type::*, error::*, value::*};
+
+pub fn add_days(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let date = assert_date(target)?;
+    let days = assert_int(args.first())?;
+    let res = if days >= 0 {
+        date.checked_add_days(Days::new(days as u64))
+    } else {
+        date.checked_sub_days(Days::new(days.unsigned_abs()))
+    };
+    res.map(CelValue::Date)
+        .ok_or_else(|| CelError::DateError(format!("{date} + {days} days is out of range")))
+}
+
+/// Adds calendar months, clamping to the last day of the resulting month
+/// (eg. `2024-01-31` + 1 month is `2024-02-29`).
+pub fn add_months(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let date = assert_date(target)?;
+    let months = assert_int(args.first())?;
+    let magnitude = u32::try_from(months.unsigned_abs())
+        .map_err(|_| CelError::DateError(format!("{months} months is out of range")))?;
+    let res = if months >= 0 {
+        date.checked_add_months(Months::new(magnitude))
+    } else {
+        date.checked_sub_months(Months::new(magnitude))
+    };
+    res.map(CelValue::Date)
+        .ok_or_else(|| CelError::DateError(format!("{date} + {months} months is out of range")))
+}
+
+pub fn start_of_month(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let date = assert_date(target)?;
+    Ok(CelValue::Date(first_of_month(date)))
+}
+
+pub fn end_of_month(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let date = assert_date(target)?;
+    let end = first_of_month(date)
+        .checked_add_months(Months::new(1))
+        .and_then(|d| d.pred_opt())
+        .ok_or_else(|| CelError::DateError(format!("end of month for {date} is out of range")))?;
+    Ok(CelValue::Date(end))
+}
+
+/// Day of the week with Sunday as `0` as in the CEL spec.
+pub fn day_of_week(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let date = assert_date(target)?;
+    Ok(CelValue::Int(date.weekday().num_days_from_sunday() as i64))
+}
+
+fn first_of_month(date: &NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("first day of month always exists")
+}
+
+fn assert_date(target: &CelValue) -> Result<&NaiveDate, CelError> {
+    if let CelValue::Date(d) = target {
+        Ok(d)
+    } else {
+        Err(CelError::BadType(CelType::Date, CelType::from(target)))
+    }
+}
+
+fn assert_int(arg: Option<&CelValue>) -> Result<i64, CelError> {
+    match arg {
+        Some(CelValue::Int(i)) => Ok(*i),
+        Some(v) => Err(CelError::BadType(CelType::Int, CelType::from(v))),
+        None => Err(CelError::MissingArgument),
+    }
+}
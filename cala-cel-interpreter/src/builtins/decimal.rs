@@ -1,4 +1,6 @@
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use std::sync::Arc;
 
 use crate::{cel_type::*, error::*, value::*};
 
@@ -21,3 +23,108 @@ pub fn add(args: Vec<CelValue>) -> Result<CelValue, CelError> {
     let b: &Decimal = assert_arg(args.get(1))?;
     Ok(CelValue::Decimal(a + b))
 }
+
+/// Rounds to `scale` decimal places. The optional mode defaults to `HALF_EVEN`.
+pub fn round(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let d: &Decimal = assert_arg(args.first())?;
+    let scale = assert_scale(args.get(1))?;
+    let strategy = match args.get(2) {
+        Some(mode) => rounding_strategy(mode)?,
+        None => RoundingStrategy::MidpointNearestEven,
+    };
+    Ok(CelValue::Decimal(d.round_dp_with_strategy(scale, strategy)))
+}
+
+pub fn floor(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let d: &Decimal = assert_arg(args.first())?;
+    Ok(CelValue::Decimal(d.floor()))
+}
+
+pub fn ceil(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let d: &Decimal = assert_arg(args.first())?;
+    Ok(CelValue::Decimal(d.ceil()))
+}
+
+pub fn truncate(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let d: &Decimal = assert_arg(args.first())?;
+    let scale = match args.get(1) {
+        Some(scale) => assert_scale(Some(scale))?,
+        None => 0,
+    };
+    Ok(CelValue::Decimal(d.trunc_with_scale(scale)))
+}
+
+pub fn abs(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let d: &Decimal = assert_arg(args.first())?;
+    Ok(CelValue::Decimal(d.abs()))
+}
+
+pub fn neg(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let d: &Decimal = assert_arg(args.first())?;
+    Ok(CelValue::Decimal(-d))
+}
+
+pub fn min(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let a: &Decimal = assert_arg(args.first())?;
+    let b: &Decimal = assert_arg(args.get(1))?;
+    Ok(CelValue::Decimal(*a.min(b)))
+}
+
+pub fn max(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let a: &Decimal = assert_arg(args.first())?;
+    let b: &Decimal = assert_arg(args.get(1))?;
+    Ok(CelValue::Decimal(*a.max(b)))
+}
+
+/// Returns `-1`, `0` or `1` depending on whether `a` is less than, equal to or greater than `b`.
+pub fn cmp(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let a: &Decimal = assert_arg(args.first())?;
+    let b: &Decimal = assert_arg(args.get(1))?;
+    Ok(CelValue::Int(a.cmp(b) as i64))
+}
+
+/// Divides `a` by `b` and rounds the quotient to `scale` decimal places.
+/// The optional mode defaults to `HALF_EVEN`.
+pub fn div(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let a: &Decimal = assert_arg(args.first())?;
+    let b: &Decimal = assert_arg(args.get(1))?;
+    let scale = assert_scale(args.get(2))?;
+    let strategy = match args.get(3) {
+        Some(mode) => rounding_strategy(mode)?,
+        None => RoundingStrategy::MidpointNearestEven,
+    };
+    let quotient = a
+        .checked_div(*b)
+        .ok_or_else(|| CelError::DecimalError(format!("Cannot divide {a} by {b}")))?;
+    Ok(CelValue::Decimal(
+        quotient.round_dp_with_strategy(scale, strategy),
+    ))
+}
+
+fn assert_scale(arg: Option<&CelValue>) -> Result<u32, CelError> {
+    match arg {
+        Some(CelValue::Int(i)) if (0..=Decimal::MAX_SCALE as i64).contains(i) => Ok(*i as u32),
+        Some(CelValue::Int(i)) => Err(CelError::DecimalError(format!(
+            "scale {i} must be between 0 and {}",
+            Decimal::MAX_SCALE
+        ))),
+        Some(v) => Err(CelError::BadType(CelType::Int, CelType::from(v))),
+        None => Err(CelError::MissingArgument),
+    }
+}
+
+fn rounding_strategy(mode: &CelValue) -> Result<RoundingStrategy, CelError> {
+    let mode: Arc<String> = mode.try_into()?;
+    match mode.as_str() {
+        "HALF_EVEN" => Ok(RoundingStrategy::MidpointNearestEven),
+        "HALF_UP" => Ok(RoundingStrategy::MidpointAwayFromZero),
+        "HALF_DOWN" => Ok(RoundingStrategy::MidpointTowardZero),
+        "UP" => Ok(RoundingStrategy::AwayFromZero),
+        "DOWN" => Ok(RoundingStrategy::ToZero),
+        "CEILING" => Ok(RoundingStrategy::ToPositiveInfinity),
+        "FLOOR" => Ok(RoundingStrategy::ToNegativeInfinity),
+        m => Err(CelError::DecimalError(format!(
+            "unknown rounding mode '{m}'"
+        ))),
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::Duration;
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::{cel_type::*, error::*, value::*};
+
+pub fn cast(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    match args.first() {
+        Some(CelValue::String(s)) => Ok(CelValue::Duration(parse(s)?)),
+        Some(CelValue::Duration(d)) => Ok(CelValue::Duration(*d)),
+        Some(v) => Err(CelError::BadType(CelType::Duration, CelType::from(v))),
+        None => Err(CelError::MissingArgument),
+    }
+}
+
+pub fn get_hours(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    Ok(CelValue::Int(assert_duration(target)?.num_hours()))
+}
+
+pub fn get_minutes(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    Ok(CelValue::Int(assert_duration(target)?.num_minutes()))
+}
+
+pub fn get_seconds(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    Ok(CelValue::Int(assert_duration(target)?.num_seconds()))
+}
+
+pub fn get_milliseconds(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    Ok(CelValue::Int(assert_duration(target)?.num_milliseconds()))
+}
+
+fn assert_duration(target: &CelValue) -> Result<&Duration, CelError> {
+    if let CelValue::Duration(d) = target {
+        Ok(d)
+    } else {
+        Err(CelError::BadType(CelType::Duration, CelType::from(target)))
+    }
+}
+
+/// Parses the CEL duration format - a sequence of decimal numbers with unit
+/// suffixes (`h`, `m`, `s`, `ms`, `us`, `ns`), optionally preceded by a sign.
+/// Eg. `1h30m`, `-1.5h` or `3600s`.
+fn parse(source: &str) -> Result<Duration, CelError> {
+    let err = || CelError::DurationError(format!("invalid duration '{source}'"));
+    let (negative, mut rest) = match source.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, source.strip_prefix('+').unwrap_or(source)),
+    };
+    if rest.is_empty() {
+        return Err(err());
+    }
+    let mut nanos: i128 = 0;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(err)?;
+        let unit_len = rest[number_len..]
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len() - number_len);
+        let number: Decimal = rest[..number_len].parse().map_err(|_| err())?;
+        let unit_nanos: i64 = match &rest[number_len..number_len + unit_len] {
+            "h" => 3_600_000_000_000,
+            "m" => 60_000_000_000,
+            "s" => 1_000_000_000,
+            "ms" => 1_000_000,
+            "us" => 1_000,
+            "ns" => 1,
+            _ => return Err(err()),
+        };
+        let part = number
+            .checked_mul(Decimal::from(unit_nanos))
+            .and_then(|n| n.round().to_i128())
+            .ok_or_else(err)?;
+        nanos = nanos.checked_add(part).ok_or_else(err)?;
+        rest = &rest[number_len + unit_len..];
+    }
+    let nanos = i64::try_from(nanos).map_err(|_| err())?;
+    Ok(Duration::nanoseconds(if negative { -nanos } else { nanos }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration() {
+        assert_eq!(parse("1h30m").unwrap(), Duration::minutes(90));
+        assert_eq!(parse("-1.5h").unwrap(), Duration::minutes(-90));
+        assert_eq!(parse("3600s").unwrap(), Duration::hours(1));
+        assert_eq!(parse("250ms").unwrap(), Duration::milliseconds(250));
+        assert_eq!(
+            parse("1.000000001s").unwrap(),
+            Duration::nanoseconds(1_000_000_001)
+        );
+        assert_eq!(
+            parse("9223372036854775807ns").unwrap(),
+            Duration::nanoseconds(i64::MAX)
+        );
+        assert_eq!(
+            parse("-2562047h47m16.854775807s").unwrap(),
+            Duration::nanoseconds(-i64::MAX)
+        );
+        assert!(parse("9223372036854775808ns").is_err());
+        assert!(parse("1.2.3s").is_err());
+        assert!(parse("").is_err());
+        assert!(parse("10").is_err());
+        assert!(parse("1d").is_err());
+    }
+}
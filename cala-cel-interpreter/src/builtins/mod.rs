@@ -1,4 +1,7 @@
+pub(crate) mod date;
 pub(crate) mod decimal;
+pub(crate) mod duration;
+pub(crate) mod string;
 pub(crate) mod timestamp;
 
 use chrono::{NaiveDate, Utc};
@@ -6,7 +9,7 @@ use chrono::{NaiveDate, Utc};
 use std::sync::Arc;
 
 use super::value::*;
-use crate::error::*;
+use crate::{cel_type::*, error::*};
 
 pub(crate) fn date(args: Vec<CelValue>) -> Result<CelValue, CelError> {
     if args.is_empty() {
@@ -25,6 +28,33 @@ pub(crate) fn uuid(args: Vec<CelValue>) -> Result<CelValue, CelError> {
     ))
 }
 
+pub(crate) fn size(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    match args.first() {
+        Some(v) => size_of(v),
+        None => Err(CelError::MissingArgument),
+    }
+}
+
+pub(crate) fn size_member(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    size_of(target)
+}
+
+fn size_of(value: &CelValue) -> Result<CelValue, CelError> {
+    let size = match value {
+        CelValue::List(list) => list.len(),
+        CelValue::Map(map) => map.len(),
+        CelValue::String(s) => s.chars().count(),
+        CelValue::Bytes(b) => b.len(),
+        v => {
+            return Err(CelError::NoMatchingOverload(format!(
+                "Cannot apply 'size' to {:?}",
+                CelType::from(v)
+            )))
+        }
+    };
+    Ok(CelValue::Int(size as i64))
+}
+
 fn assert_arg<'a, T: TryFrom<&'a CelValue, Error = CelError>>(
     arg: Option<&'a CelValue>,
 ) -> Result<T, CelError> {
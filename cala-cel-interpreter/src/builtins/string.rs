@@ -0,0 +1,136 @@
+use regex::Regex;
+
+use std::sync::Arc;
+
+use crate::{cel_type::*, error::*, value::*};
+
+use super::assert_arg;
+
+pub fn cast(args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = match args.first() {
+        Some(CelValue::String(s)) => return Ok(CelValue::String(s.clone())),
+        Some(CelValue::Int(i)) => i.to_string(),
+        Some(CelValue::UInt(u)) => u.to_string(),
+        Some(CelValue::Double(d)) => d.to_string(),
+        Some(CelValue::Decimal(d)) => d.to_string(),
+        Some(CelValue::Bool(b)) => b.to_string(),
+        Some(CelValue::Uuid(u)) => u.to_string(),
+        Some(CelValue::Date(d)) => d.format("%Y-%m-%d").to_string(),
+        Some(CelValue::Timestamp(ts)) => ts.to_rfc3339(),
+        Some(CelValue::Bytes(b)) => String::from_utf8(b.to_vec())
+            .map_err(|e| CelError::Unexpected(format!("invalid utf-8 bytes: {e}")))?,
+        Some(v) => {
+            return Err(CelError::NoMatchingOverload(format!(
+                "Cannot apply 'string' to {:?}",
+                CelType::from(v)
+            )))
+        }
+        None => return Err(CelError::MissingArgument),
+    };
+    Ok(CelValue::String(s.into()))
+}
+
+pub fn contains(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = assert_string(target)?;
+    let sub: Arc<String> = assert_arg(args.first())?;
+    Ok(CelValue::Bool(s.contains(sub.as_str())))
+}
+
+pub fn starts_with(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = assert_string(target)?;
+    let prefix: Arc<String> = assert_arg(args.first())?;
+    Ok(CelValue::Bool(s.starts_with(prefix.as_str())))
+}
+
+pub fn ends_with(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = assert_string(target)?;
+    let suffix: Arc<String> = assert_arg(args.first())?;
+    Ok(CelValue::Bool(s.ends_with(suffix.as_str())))
+}
+
+pub fn matches(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = assert_string(target)?;
+    let pattern: Arc<String> = assert_arg(args.first())?;
+    let re = Regex::new(&pattern).map_err(|e| CelError::RegexError(e.to_string()))?;
+    Ok(CelValue::Bool(re.is_match(s)))
+}
+
+pub fn lower_ascii(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = assert_string(target)?;
+    Ok(CelValue::String(s.to_ascii_lowercase().into()))
+}
+
+pub fn upper_ascii(target: &CelValue, _args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = assert_string(target)?;
+    Ok(CelValue::String(s.to_ascii_uppercase().into()))
+}
+
+pub fn split(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = assert_string(target)?;
+    let separator: Arc<String> = assert_arg(args.first())?;
+    Ok(s.split(separator.as_str())
+        .map(|part| CelValue::String(part.to_string().into()))
+        .collect::<CelArray>()
+        .into())
+}
+
+pub fn substring(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let s = assert_string(target)?;
+    let len = s.chars().count();
+    let start = assert_index(args.first(), len)?;
+    let end = match args.get(1) {
+        Some(arg) => assert_index(Some(arg), len)?,
+        None => len,
+    };
+    if start > end {
+        return Err(CelError::IndexOutOfRange(format!(
+            "substring start {start} is greater than end {end}"
+        )));
+    }
+    Ok(CelValue::String(
+        s.chars()
+            .skip(start)
+            .take(end - start)
+            .collect::<String>()
+            .into(),
+    ))
+}
+
+pub fn join(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let list = if let CelValue::List(list) = target {
+        list
+    } else {
+        return Err(CelError::BadType(CelType::List, CelType::from(target)));
+    };
+    let separator: Arc<String> = match args.first() {
+        Some(arg) => Arc::<String>::try_from(arg)?,
+        None => Arc::new(String::new()),
+    };
+    let parts = list
+        .iter()
+        .map(|elem| Arc::<String>::try_from(elem).map(|s| s.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(CelValue::String(parts.join(separator.as_str()).into()))
+}
+
+fn assert_string(target: &CelValue) -> Result<&str, CelError> {
+    if let CelValue::String(s) = target {
+        Ok(s.as_str())
+    } else {
+        Err(CelError::BadType(CelType::String, CelType::from(target)))
+    }
+}
+
+fn assert_index(arg: Option<&CelValue>, len: usize) -> Result<usize, CelError> {
+    let idx = match arg {
+        Some(CelValue::Int(i)) => *i,
+        Some(v) => return Err(CelError::BadType(CelType::Int, CelType::from(v))),
+        None => return Err(CelError::MissingArgument),
+    };
+    if idx < 0 || idx as usize > len {
+        return Err(CelError::IndexOutOfRange(format!(
+            "index {idx} out of range for string of length {len}"
+        )));
+    }
+    Ok(idx as usize)
+}
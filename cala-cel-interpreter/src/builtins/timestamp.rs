@@ -1,3 +1,6 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use chrono_tz::Tz;
+
 use crate::{cel_type::*, error::*, value::*};
 
 use super::assert_arg;
@@ -21,3 +24,35 @@ pub fn format(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelErr
         Err(CelError::BadType(CelType::Timestamp, CelType::from(target)))
     }
 }
+
+/// Converts the timestamp to a calendar date in the given timezone.
+/// The timezone is an IANA name (eg. `Europe/Berlin`) or a fixed UTC offset
+/// (eg. `+02:00`) and defaults to `UTC`.
+pub fn date(target: &CelValue, args: Vec<CelValue>) -> Result<CelValue, CelError> {
+    let ts = if let CelValue::Timestamp(ts) = target {
+        ts
+    } else {
+        return Err(CelError::BadType(CelType::Timestamp, CelType::from(target)));
+    };
+    let date = match args.first() {
+        Some(arg) => {
+            let tz: std::sync::Arc<String> = assert_arg(Some(arg))?;
+            local_date(ts, &tz)?
+        }
+        None => ts.date_naive(),
+    };
+    Ok(CelValue::Date(date))
+}
+
+fn local_date(ts: &DateTime<Utc>, tz: &str) -> Result<NaiveDate, CelError> {
+    if tz == "Z" {
+        return Ok(ts.date_naive());
+    }
+    if let Ok(offset) = tz.parse::<FixedOffset>() {
+        return Ok(ts.with_timezone(&offset).date_naive());
+    }
+    let tz: Tz = tz
+        .parse()
+        .map_err(|e| CelError::TimestampError(format!("invalid timezone '{tz}': {e}")))?;
+    Ok(ts.with_timezone(&tz).date_naive())
+}
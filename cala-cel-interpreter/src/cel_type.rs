@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CelType {
     // Builtins
     Map,
@@ -16,6 +16,7 @@ pub enum CelType {
     Timestamp,
     Uuid,
     Decimal,
+    Duration,
 }
 
 impl CelType {
@@ -34,6 +35,28 @@ impl CelType {
             CelType::Timestamp => "timestamp",
             CelType::Uuid => "uuid",
             CelType::Decimal => "decimal",
+            CelType::Duration => "duration",
         }
     }
+
+    pub(crate) fn from_package_name(name: &str) -> Option<Self> {
+        [
+            CelType::Map,
+            CelType::List,
+            CelType::Int,
+            CelType::UInt,
+            CelType::Double,
+            CelType::String,
+            CelType::Bytes,
+            CelType::Bool,
+            CelType::Null,
+            CelType::Date,
+            CelType::Timestamp,
+            CelType::Uuid,
+            CelType::Decimal,
+            CelType::Duration,
+        ]
+        .into_iter()
+        .find(|t| t.package_name() == name)
+    }
 }
@@ -0,0 +1,26 @@
+use lazy_static::lazy_static;
+
+use std::collections::HashMap;
+
+use crate::builtins;
+
+use super::*;
+
+lazy_static! {
+    pub static ref CEL_PACKAGE: Arc<CelPackage> = {
+        let mut idents = HashMap::new();
+        idents.insert(
+            SELF_PACKAGE_NAME,
+            ContextItem::Function(Arc::new(builtins::date)),
+        );
+
+        let mut member_fns: HashMap<_, CelMemberFunction> = HashMap::new();
+        member_fns.insert("addDays", Arc::new(builtins::date::add_days));
+        member_fns.insert("addMonths", Arc::new(builtins::date::add_months));
+        member_fns.insert("startOfMonth", Arc::new(builtins::date::start_of_month));
+        member_fns.insert("endOfMonth", Arc::new(builtins::date::end_of_month));
+        member_fns.insert("dayOfWeek", Arc::new(builtins::date::day_of_week));
+
+        Arc::new(CelPackage::new(CelContext::from_idents(idents), member_fns))
+    };
+}
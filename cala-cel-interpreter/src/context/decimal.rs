@@ -7,17 +7,60 @@ use crate::builtins;
 use super::*;
 
 lazy_static! {
-    pub static ref CEL_PACKAGE: CelPackage = {
+    pub static ref CEL_PACKAGE: Arc<CelPackage> = {
         let mut idents = HashMap::new();
         idents.insert(
             SELF_PACKAGE_NAME,
-            ContextItem::Function(Box::new(builtins::decimal::cast)),
+            ContextItem::Function(Arc::new(builtins::decimal::cast)),
         );
         idents.insert(
             Cow::Borrowed("Add"),
-            ContextItem::Function(Box::new(builtins::decimal::add)),
+            ContextItem::Function(Arc::new(builtins::decimal::add)),
+        );
+        idents.insert(
+            Cow::Borrowed("round"),
+            ContextItem::Function(Arc::new(builtins::decimal::round)),
+        );
+        idents.insert(
+            Cow::Borrowed("floor"),
+            ContextItem::Function(Arc::new(builtins::decimal::floor)),
+        );
+        idents.insert(
+            Cow::Borrowed("ceil"),
+            ContextItem::Function(Arc::new(builtins::decimal::ceil)),
+        );
+        idents.insert(
+            Cow::Borrowed("truncate"),
+            ContextItem::Function(Arc::new(builtins::decimal::truncate)),
+        );
+        idents.insert(
+            Cow::Borrowed("abs"),
+            ContextItem::Function(Arc::new(builtins::decimal::abs)),
+        );
+        idents.insert(
+            Cow::Borrowed("neg"),
+            ContextItem::Function(Arc::new(builtins::decimal::neg)),
+        );
+        idents.insert(
+            Cow::Borrowed("min"),
+            ContextItem::Function(Arc::new(builtins::decimal::min)),
+        );
+        idents.insert(
+            Cow::Borrowed("max"),
+            ContextItem::Function(Arc::new(builtins::decimal::max)),
+        );
+        idents.insert(
+            Cow::Borrowed("cmp"),
+            ContextItem::Function(Arc::new(builtins::decimal::cmp)),
+        );
+        idents.insert(
+            Cow::Borrowed("div"),
+            ContextItem::Function(Arc::new(builtins::decimal::div)),
         );
 
-        CelPackage::new(CelContext { idents }, HashMap::new())
+        Arc::new(CelPackage::new(
+            CelContext::from_idents(idents),
+            HashMap::new(),
+        ))
     };
 }
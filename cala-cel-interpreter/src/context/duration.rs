@@ -0,0 +1,28 @@
+use lazy_static::lazy_static;
+
+use std::collections::HashMap;
+
+use crate::builtins;
+
+use super::*;
+
+lazy_static! {
+    pub static ref CEL_PACKAGE: Arc<CelPackage> = {
+        let mut idents = HashMap::new();
+        idents.insert(
+            SELF_PACKAGE_NAME,
+            ContextItem::Function(Arc::new(builtins::duration::cast)),
+        );
+
+        let mut member_fns: HashMap<_, CelMemberFunction> = HashMap::new();
+        member_fns.insert("getHours", Arc::new(builtins::duration::get_hours));
+        member_fns.insert("getMinutes", Arc::new(builtins::duration::get_minutes));
+        member_fns.insert("getSeconds", Arc::new(builtins::duration::get_seconds));
+        member_fns.insert(
+            "getMilliseconds",
+            Arc::new(builtins::duration::get_milliseconds),
+        );
+
+        Arc::new(CelPackage::new(CelContext::from_idents(idents), member_fns))
+    };
+}
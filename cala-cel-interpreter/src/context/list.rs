@@ -0,0 +1,17 @@
+use lazy_static::lazy_static;
+
+use std::collections::HashMap;
+
+use crate::builtins;
+
+use super::*;
+
+lazy_static! {
+    pub static ref CEL_PACKAGE: Arc<CelPackage> = {
+        let mut member_fns: HashMap<_, CelMemberFunction> = HashMap::new();
+        member_fns.insert("size", Arc::new(builtins::size_member));
+        member_fns.insert("join", Arc::new(builtins::string::join));
+
+        Arc::new(CelPackage::new(CelContext::empty(), member_fns))
+    };
+}
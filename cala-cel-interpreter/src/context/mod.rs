@@ -1,51 +1,188 @@
+mod date;
 mod decimal;
+mod duration;
+mod list;
+mod map;
 mod package;
+mod string;
 mod timestamp;
 
-use std::{borrow::Cow, collections::HashMap};
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
-use crate::{builtins, cel_type::CelType, error::*, value::*};
+use crate::{builtins, cel_type::CelType, error::*, extension::*, value::*};
 
-use package::CelPackage;
+pub use package::CelPackage;
 
 const SELF_PACKAGE_NAME: Cow<'static, str> = Cow::Borrowed("self");
 
-type CelFunction = Box<dyn Fn(Vec<CelValue>) -> Result<CelValue, CelError> + Sync>;
-pub(crate) type CelMemberFunction =
-    Box<dyn Fn(&CelValue, Vec<CelValue>) -> Result<CelValue, CelError> + Sync>;
+/// The functions and packages registered by [CelContext::new].
+const BUILTIN_IDENTS: [&str; 9] = [
+    "date",
+    "uuid",
+    "size",
+    "list",
+    "map",
+    "string",
+    "decimal",
+    "timestamp",
+    "duration",
+];
 
-#[derive(Debug)]
+type CelFunction = HostFunction;
+pub(crate) type CelMemberFunction = HostMemberFunction;
+
+#[derive(Debug, Clone)]
 pub struct CelContext {
     idents: HashMap<Cow<'static, str>, ContextItem>,
+    shadows_builtins: bool,
 }
 
 impl CelContext {
     pub fn add_variable(&mut self, name: impl Into<Cow<'static, str>>, value: impl Into<CelValue>) {
+        let name = name.into();
+        self.shadows_builtins |= BUILTIN_IDENTS.contains(&name.as_ref());
+        self.idents.insert(name, ContextItem::Value(value.into()));
+    }
+
+    /// Removes a variable previously added via [add_variable](Self::add_variable).
+    /// Functions and packages are left untouched.
+    pub fn remove_variable(&mut self, name: &str) {
+        if let Some(ContextItem::Value(_)) = self.idents.get(name) {
+            self.idents.remove(name);
+        }
+    }
+
+    /// Whether a variable has been added under the name of a builtin function or package.
+    /// Stays set after the variable is removed as the builtin doesn't come back.
+    pub(crate) fn shadows_builtins(&self) -> bool {
+        self.shadows_builtins
+    }
+
+    /// Whether `name` refers to a variable, function or package.
+    pub fn contains_ident(&self, name: &str) -> bool {
+        self.idents.contains_key(name)
+    }
+
+    /// Registers a host function callable as `name(args..)`.
+    /// Arguments and the result are checked against the signature.
+    pub fn add_function(
+        &mut self,
+        name: &'static str,
+        signature: CelSignature,
+        f: impl Fn(Vec<CelValue>) -> Result<CelValue, CelError> + Send + Sync + 'static,
+    ) -> Result<(), CelError> {
+        self.add_host_function(name, signature, Arc::new(f))
+    }
+
+    /// Registers a package of host functions. If a package of the same name exists
+    /// (eg. the builtin `date` package) the functions are added to it.
+    pub fn add_package(&mut self, name: &'static str, package: CelPackage) -> Result<(), CelError> {
+        validate_name(name)?;
+        if !package.member_signatures.is_empty() && CelType::from_package_name(name).is_none() {
+            return Err(CelError::RegistrationError(format!(
+                "'{name}' does not name a type - cannot register member functions"
+            )));
+        }
+        let merged = match self.idents.get(name) {
+            None => package,
+            Some(ContextItem::Package(existing)) => {
+                let mut merged = CelPackage::clone(existing);
+                merged.merge(package)?;
+                merged
+            }
+            Some(_) => {
+                return Err(CelError::RegistrationError(format!(
+                    "'{name}' already exists and is not a package"
+                )))
+            }
+        };
         self.idents
-            .insert(name.into(), ContextItem::Value(value.into()));
+            .insert(Cow::Borrowed(name), ContextItem::Package(Arc::new(merged)));
+        Ok(())
+    }
+
+    /// Registers all functions and packages of the extensions.
+    pub fn extend(&mut self, extensions: &CelExtensions) -> Result<(), CelError> {
+        for (name, signature, f) in extensions.functions.iter() {
+            self.add_host_function(name, signature.clone(), Arc::clone(f))?;
+        }
+        for (name, package) in extensions.packages.iter() {
+            self.add_package(name, package.clone())?;
+        }
+        Ok(())
+    }
+
+    fn add_host_function(
+        &mut self,
+        name: &'static str,
+        signature: CelSignature,
+        f: HostFunction,
+    ) -> Result<(), CelError> {
+        validate_name(name)?;
+        if self.idents.contains_key(name) {
+            return Err(CelError::RegistrationError(format!(
+                "'{name}' already exists"
+            )));
+        }
+        self.idents.insert(
+            Cow::Borrowed(name),
+            ContextItem::Function(signature.wrap(name, f)),
+        );
+        Ok(())
+    }
+
+    pub(crate) fn empty() -> Self {
+        Self::from_idents(HashMap::new())
+    }
+
+    fn from_idents(idents: HashMap<Cow<'static, str>, ContextItem>) -> Self {
+        Self {
+            idents,
+            shadows_builtins: false,
+        }
     }
 
     pub fn new() -> Self {
         let mut idents = HashMap::new();
         idents.insert(
             Cow::Borrowed("date"),
-            ContextItem::Function(Box::new(builtins::date)),
+            ContextItem::Package(Arc::clone(&date::CEL_PACKAGE)),
         );
         idents.insert(
             Cow::Borrowed("uuid"),
-            ContextItem::Function(Box::new(builtins::uuid)),
+            ContextItem::Function(Arc::new(builtins::uuid)),
+        );
+        idents.insert(
+            Cow::Borrowed("size"),
+            ContextItem::Function(Arc::new(builtins::size)),
+        );
+        idents.insert(
+            Cow::Borrowed("list"),
+            ContextItem::Package(Arc::clone(&list::CEL_PACKAGE)),
+        );
+        idents.insert(
+            Cow::Borrowed("map"),
+            ContextItem::Package(Arc::clone(&map::CEL_PACKAGE)),
+        );
+        idents.insert(
+            Cow::Borrowed("string"),
+            ContextItem::Package(Arc::clone(&string::CEL_PACKAGE)),
         );
         idents.insert(
             Cow::Borrowed("decimal"),
-            ContextItem::Package(&decimal::CEL_PACKAGE),
+            ContextItem::Package(Arc::clone(&decimal::CEL_PACKAGE)),
         );
 
         idents.insert(
             Cow::Borrowed("timestamp"),
-            ContextItem::Package(&timestamp::CEL_PACKAGE),
+            ContextItem::Package(Arc::clone(&timestamp::CEL_PACKAGE)),
+        );
+        idents.insert(
+            Cow::Borrowed("duration"),
+            ContextItem::Package(Arc::clone(&duration::CEL_PACKAGE)),
         );
 
-        Self { idents }
+        Self::from_idents(idents)
     }
 
     pub(crate) fn lookup_ident(&self, name: &str) -> Result<&ContextItem, CelError> {
@@ -75,10 +212,11 @@ impl Default for CelContext {
     }
 }
 
+#[derive(Clone)]
 pub(crate) enum ContextItem {
     Value(CelValue),
     Function(CelFunction),
-    Package(&'static CelPackage),
+    Package(Arc<CelPackage>),
 }
 
 impl std::fmt::Debug for ContextItem {
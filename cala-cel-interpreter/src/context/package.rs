@@ -1,21 +1,101 @@
 use super::*;
 
+/// A namespace of functions (`package.function(..)`). A package named after a
+/// type (eg. `date` or `string`) also provides the member functions of values
+/// of that type (`value.function(..)`).
+#[derive(Clone)]
 pub struct CelPackage {
     nested_ctx: CelContext,
     member_fns: HashMap<&'static str, CelMemberFunction>,
+    pub(crate) signatures: HashMap<&'static str, CelSignature>,
+    pub(crate) member_signatures: HashMap<&'static str, CelSignature>,
 }
 
 impl CelPackage {
-    pub fn new(
+    pub(crate) fn new(
         nested_ctx: CelContext,
         member_fns: HashMap<&'static str, CelMemberFunction>,
     ) -> Self {
         Self {
             nested_ctx,
             member_fns,
+            signatures: HashMap::new(),
+            member_signatures: HashMap::new(),
         }
     }
 
+    /// Registers `package.name(args..)`.
+    pub fn add_function(
+        &mut self,
+        name: &'static str,
+        signature: CelSignature,
+        f: impl Fn(Vec<CelValue>) -> Result<CelValue, CelError> + Send + Sync + 'static,
+    ) -> Result<(), CelError> {
+        self.add_host_function(name, signature, Arc::new(f))
+    }
+
+    /// Registers `value.name(args..)` for values whose type this package is named after.
+    /// The signature does not include the target value.
+    pub fn add_member_function(
+        &mut self,
+        name: &'static str,
+        signature: CelSignature,
+        f: impl Fn(&CelValue, Vec<CelValue>) -> Result<CelValue, CelError> + Send + Sync + 'static,
+    ) -> Result<(), CelError> {
+        validate_name(name)?;
+        if self.member_fns.contains_key(name) {
+            return Err(CelError::RegistrationError(format!(
+                "member function '{name}' already exists"
+            )));
+        }
+        self.member_fns
+            .insert(name, signature.wrap_member(name, Arc::new(f)));
+        self.member_signatures.insert(name, signature);
+        Ok(())
+    }
+
+    fn add_host_function(
+        &mut self,
+        name: &'static str,
+        signature: CelSignature,
+        f: HostFunction,
+    ) -> Result<(), CelError> {
+        validate_name(name)?;
+        if self.nested_ctx.idents.contains_key(name) {
+            return Err(CelError::RegistrationError(format!(
+                "function '{name}' already exists"
+            )));
+        }
+        self.nested_ctx.idents.insert(
+            Cow::Borrowed(name),
+            ContextItem::Function(signature.wrap(name, f)),
+        );
+        self.signatures.insert(name, signature);
+        Ok(())
+    }
+
+    pub(crate) fn merge(&mut self, other: CelPackage) -> Result<(), CelError> {
+        for (name, item) in other.nested_ctx.idents {
+            if self.nested_ctx.idents.contains_key(&name) {
+                return Err(CelError::RegistrationError(format!(
+                    "function '{name}' already exists"
+                )));
+            }
+            self.nested_ctx.idents.insert(name, item);
+        }
+        for (name, f) in other.member_fns {
+            if self.member_fns.contains_key(name) {
+                return Err(CelError::RegistrationError(format!(
+                    "member function '{name}' already exists"
+                )));
+            }
+            self.member_fns.insert(name, f);
+        }
+        self.signatures.extend(other.signatures);
+        self.member_signatures.extend(other.member_signatures);
+        Ok(())
+    }
+
     pub(crate) fn package_self(&self) -> Result<&ContextItem, CelError> {
         self.nested_ctx.lookup_ident(&SELF_PACKAGE_NAME)
     }
@@ -34,3 +114,9 @@ impl CelPackage {
             .ok_or_else(|| CelError::UnknownAttribute(CelType::from(value), name.to_string()))
     }
 }
+
+impl Default for CelPackage {
+    fn default() -> Self {
+        Self::new(CelContext::empty(), HashMap::new())
+    }
+}
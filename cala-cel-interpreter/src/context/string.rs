@@ -0,0 +1,30 @@
+use lazy_static::lazy_static;
+
+use std::collections::HashMap;
+
+use crate::builtins;
+
+use super::*;
+
+lazy_static! {
+    pub static ref CEL_PACKAGE: Arc<CelPackage> = {
+        let mut idents = HashMap::new();
+        idents.insert(
+            SELF_PACKAGE_NAME,
+            ContextItem::Function(Arc::new(builtins::string::cast)),
+        );
+
+        let mut member_fns: HashMap<_, CelMemberFunction> = HashMap::new();
+        member_fns.insert("size", Arc::new(builtins::size_member));
+        member_fns.insert("contains", Arc::new(builtins::string::contains));
+        member_fns.insert("startsWith", Arc::new(builtins::string::starts_with));
+        member_fns.insert("endsWith", Arc::new(builtins::string::ends_with));
+        member_fns.insert("matches", Arc::new(builtins::string::matches));
+        member_fns.insert("lowerAscii", Arc::new(builtins::string::lower_ascii));
+        member_fns.insert("upperAscii", Arc::new(builtins::string::upper_ascii));
+        member_fns.insert("split", Arc::new(builtins::string::split));
+        member_fns.insert("substring", Arc::new(builtins::string::substring));
+
+        Arc::new(CelPackage::new(CelContext::from_idents(idents), member_fns))
+    };
+}
@@ -7,16 +7,17 @@ use crate::builtins;
 use super::*;
 
 lazy_static! {
-    pub static ref CEL_PACKAGE: CelPackage = {
+    pub static ref CEL_PACKAGE: Arc<CelPackage> = {
         let mut idents = HashMap::new();
         idents.insert(
             SELF_PACKAGE_NAME,
-            ContextItem::Function(Box::new(builtins::timestamp::cast)),
+            ContextItem::Function(Arc::new(builtins::timestamp::cast)),
         );
 
         let mut member_fns: HashMap<_, CelMemberFunction> = HashMap::new();
-        member_fns.insert("format", Box::new(builtins::timestamp::format));
+        member_fns.insert("format", Arc::new(builtins::timestamp::format));
+        member_fns.insert("date", Arc::new(builtins::timestamp::date));
 
-        CelPackage::new(CelContext { idents }, member_fns)
+        Arc::new(CelPackage::new(CelContext::from_idents(idents), member_fns))
     };
 }
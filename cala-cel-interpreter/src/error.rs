@@ -1,3 +1,4 @@
+use cel_parser::{SourceLocation, Span};
 use chrono::ParseError;
 use thiserror::Error;
 
@@ -15,8 +16,8 @@ pub enum ResultCoercionError {
 
 #[derive(Error, Debug)]
 pub enum CelError {
-    #[error("CelError - CelParseError: {0}")]
-    CelParseError(String),
+    #[error("CelError - CelParseError at {1}: {0}")]
+    CelParseError(String, SourceLocation),
     #[error("CelError - BadType: expected {0:?} found {1:?}")]
     BadType(CelType, CelType),
     #[error("CelError - UnknownIdentifier: {0}")]
@@ -39,6 +40,16 @@ pub enum CelError {
     DecimalError(String),
     #[error("CelError - TimestampError: {0}")]
     TimestampError(String),
+    #[error("CelError - DateError: {0}")]
+    DateError(String),
+    #[error("CelError - DurationError: {0}")]
+    DurationError(String),
+    #[error("CelError - RegexError: {0}")]
+    RegexError(String),
+    #[error("CelError - IndexOutOfRange: {0}")]
+    IndexOutOfRange(String),
+    #[error("CelError - RegistrationError: {0}")]
+    RegistrationError(String),
     #[error("CelError - NoMatchingOverload: {0}")]
     NoMatchingOverload(String),
     #[error("CelError - Unexpected: {0}")]
@@ -47,6 +58,66 @@ pub enum CelError {
     #[error("CelError - {0}")]
     ResultCoercionError(#[from] ResultCoercionError),
 
-    #[error("Error evaluating cell expression '{0}' - {1}")]
-    EvaluationError(String, Box<Self>),
+    #[error("Error evaluating cell expression '{0}' at {1} - {2}")]
+    EvaluationError(String, SourceLocation, Box<Self>),
+    #[error("Type error in cel expression '{0}' at {1} - {2}")]
+    TypeError(String, SourceLocation, Box<Self>),
+    /// Raised by the sub-expression at the given span. Converted into an
+    /// [EvaluationError](Self::EvaluationError) or [TypeError](Self::TypeError)
+    /// once the source of the expression is known.
+    #[error("{1}")]
+    AtSpan(Span, Box<Self>),
+}
+
+impl CelError {
+    /// Line and column of the sub-expression (or token) that caused the error.
+    pub fn location(&self) -> Option<SourceLocation> {
+        match self {
+            CelError::CelParseError(_, location)
+            | CelError::EvaluationError(_, location, _)
+            | CelError::TypeError(_, location, _) => Some(*location),
+            _ => None,
+        }
+    }
+
+    /// Source text of the sub-expression that caused the error.
+    pub fn sub_expression(&self) -> Option<&str> {
+        match self {
+            CelError::EvaluationError(expr, _, _) | CelError::TypeError(expr, _, _) => Some(expr),
+            _ => None,
+        }
+    }
+
+    /// The underlying error without any location information.
+    pub fn root_cause(&self) -> &CelError {
+        match self {
+            CelError::EvaluationError(_, _, e)
+            | CelError::TypeError(_, _, e)
+            | CelError::AtSpan(_, e) => e.root_cause(),
+            e => e,
+        }
+    }
+
+    pub(crate) fn at_span(self, span: Span) -> Self {
+        match self {
+            e @ CelError::AtSpan(..) => e,
+            e => CelError::AtSpan(span, Box::new(e)),
+        }
+    }
+
+    pub(crate) fn locate(
+        self,
+        source: &str,
+        wrap: fn(String, SourceLocation, Box<CelError>) -> CelError,
+    ) -> Self {
+        let (span, e) = match self {
+            CelError::AtSpan(span, e) => (span, *e),
+            e => (Span::new(0, source.len()), e),
+        };
+        wrap(
+            span.slice(source).to_string(),
+            span.location(source),
+            Box::new(e),
+        )
+    }
 }
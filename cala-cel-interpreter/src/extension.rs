@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use crate::{cel_type::*, context::*, error::*, value::*};
+
+pub(crate) type HostFunction =
+    Arc<dyn Fn(Vec<CelValue>) -> Result<CelValue, CelError> + Send + Sync>;
+pub(crate) type HostMemberFunction =
+    Arc<dyn Fn(&CelValue, Vec<CelValue>) -> Result<CelValue, CelError> + Send + Sync>;
+
+/// Parameter and return types of a host-registered function.
+/// Arguments are checked against the signature before the function is invoked
+/// and expressions calling it are type checked against it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CelSignature {
+    pub params: Vec<CelType>,
+    pub returns: CelType,
+}
+
+impl CelSignature {
+    pub fn new(params: impl IntoIterator<Item = CelType>, returns: CelType) -> Self {
+        Self {
+            params: params.into_iter().collect(),
+            returns,
+        }
+    }
+
+    pub(crate) fn check_args(&self, name: &str, args: &[CelValue]) -> Result<(), CelError> {
+        if args.len() != self.params.len() {
+            return Err(CelError::NoMatchingOverload(format!(
+                "'{name}' expects {} arguments but got {}",
+                self.params.len(),
+                args.len()
+            )));
+        }
+        for (param, arg) in self.params.iter().zip(args) {
+            let found = CelType::from(arg);
+            if found != *param {
+                return Err(CelError::WrongArgumentType(found, *param));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_return(&self, res: CelValue) -> Result<CelValue, CelError> {
+        let found = CelType::from(&res);
+        if found != self.returns {
+            return Err(CelError::BadType(self.returns, found));
+        }
+        Ok(res)
+    }
+
+    pub(crate) fn wrap(&self, name: &'static str, f: HostFunction) -> HostFunction {
+        let signature = self.clone();
+        Arc::new(move |args| {
+            signature.check_args(name, &args)?;
+            signature.check_return(f(args)?)
+        })
+    }
+
+    pub(crate) fn wrap_member(
+        &self,
+        name: &'static str,
+        f: HostMemberFunction,
+    ) -> HostMemberFunction {
+        let signature = self.clone();
+        Arc::new(move |target, args| {
+            signature.check_args(name, &args)?;
+            signature.check_return(f(target, args)?)
+        })
+    }
+}
+
+/// Functions and packages provided by the host application.
+/// Apply them to both a [CelContext] and a [CelTypeContext](crate::CelTypeContext)
+/// so that expressions using them are evaluated and type checked consistently.
+#[derive(Clone, Default)]
+pub struct CelExtensions {
+    pub(crate) functions: Vec<(&'static str, CelSignature, HostFunction)>,
+    pub(crate) packages: Vec<(&'static str, CelPackage)>,
+}
+
+impl CelExtensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// See [CelContext::add_function].
+    pub fn add_function(
+        &mut self,
+        name: &'static str,
+        signature: CelSignature,
+        f: impl Fn(Vec<CelValue>) -> Result<CelValue, CelError> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.functions.push((name, signature, Arc::new(f)));
+        self
+    }
+
+    /// See [CelContext::add_package].
+    pub fn add_package(&mut self, name: &'static str, package: CelPackage) -> &mut Self {
+        self.packages.push((name, package));
+        self
+    }
+}
+
+impl std::fmt::Debug for CelExtensions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CelExtensions")
+            .field(
+                "functions",
+                &self
+                    .functions
+                    .iter()
+                    .map(|(name, ..)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "packages",
+                &self
+                    .packages
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+const RESERVED: [&str; 5] = ["true", "false", "null", "in", "has"];
+
+pub(crate) fn validate_name(name: &str) -> Result<(), CelError> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !RESERVED.contains(&name);
+    if valid {
+        Ok(())
+    } else {
+        Err(CelError::RegistrationError(format!(
+            "'{name}' is not a valid function name"
+        )))
+    }
+}
@@ -1,18 +1,26 @@
 use serde::{Deserialize, Serialize};
 
 use cel_parser::{
-    ast::{self, ArithmeticOp, Expression, RelationOp},
+    ast::{self, ArithmeticOp, Expression, ListMacro, RelationOp},
+    lalrpop_util::{lexer::Token, ParseError},
     parser::ExpressionParser,
+    SourceLocation, SyntaxError,
 };
 
-use crate::{cel_type::*, context::*, error::*, value::*};
+use std::sync::Arc;
 
+use crate::{cel_type::*, context::*, error::*, program::Program, value::*};
+
+/// A parsed CEL expression. It is compiled into a flat program once when parsed
+/// so holding on to the expression (eg. as part of a cached template) avoids
+/// re-doing that work on every evaluation.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(try_from = "String")]
 #[serde(into = "String")]
 pub struct CelExpression {
-    source: String,
-    expr: Expression,
+    pub(crate) source: String,
+    pub(crate) expr: Expression,
+    program: Arc<Program>,
 }
 
 impl CelExpression {
@@ -27,8 +35,30 @@ impl CelExpression {
         })?)
     }
 
+    /// The parsed syntax tree of the expression.
+    pub fn ast(&self) -> &Expression {
+        &self.expr
+    }
+
     pub fn evaluate(&self, ctx: &CelContext) -> Result<CelValue, CelError> {
-        match evaluate_expression(&self.expr, ctx)? {
+        Self::into_value(
+            self.program
+                .evaluate(ctx)
+                .map_err(|e| e.locate(&self.source, CelError::EvaluationError))?,
+        )
+    }
+
+    /// Evaluates the expression by walking the syntax tree instead of running the
+    /// compiled program. Kept as the reference implementation to compare against.
+    pub fn interpret(&self, ctx: &CelContext) -> Result<CelValue, CelError> {
+        Self::into_value(
+            evaluate_expression(&self.expr, ctx)
+                .map_err(|e| e.locate(&self.source, CelError::EvaluationError))?,
+        )
+    }
+
+    fn into_value(res: EvalType<'_>) -> Result<CelValue, CelError> {
+        match res {
             EvalType::Value(val) => Ok(val),
             EvalType::ContextItem(ContextItem::Value(val)) => Ok(val.clone()),
             _ => Err(CelError::Unexpected(
@@ -44,14 +74,14 @@ impl std::fmt::Display for CelExpression {
     }
 }
 
-enum EvalType<'a> {
+pub(crate) enum EvalType<'a> {
     Value(CelValue),
     ContextItem(&'a ContextItem),
-    MemberFn(&'a CelValue, &'a CelMemberFunction),
+    MemberFn(CelValue, &'a CelMemberFunction),
 }
 
 impl EvalType<'_> {
-    fn try_into_bool(self) -> Result<bool, CelError> {
+    pub(crate) fn try_into_bool(self) -> Result<bool, CelError> {
         if let EvalType::Value(val) = self {
             val.try_bool()
         } else {
@@ -61,7 +91,7 @@ impl EvalType<'_> {
         }
     }
 
-    fn try_into_key(self) -> Result<CelKey, CelError> {
+    pub(crate) fn try_into_key(self) -> Result<CelKey, CelError> {
         if let EvalType::Value(val) = self {
             match val {
                 CelValue::Int(i) => Ok(CelKey::Int(i)),
@@ -79,22 +109,24 @@ impl EvalType<'_> {
         }
     }
 
-    fn try_into_value(self) -> Result<CelValue, CelError> {
-        if let EvalType::Value(val) = self {
-            Ok(val)
-        } else {
-            Err(CelError::Unexpected("Couldn't unwrap value".to_string()))
+    pub(crate) fn try_into_value(self) -> Result<CelValue, CelError> {
+        match self {
+            EvalType::Value(val) => Ok(val),
+            EvalType::ContextItem(ContextItem::Value(val)) => Ok(val.clone()),
+            _ => Err(CelError::Unexpected("Couldn't unwrap value".to_string())),
         }
     }
 }
 
-fn evaluate_expression<'a>(
+pub(crate) fn evaluate_expression<'a>(
     expr: &Expression,
     ctx: &'a CelContext,
 ) -> Result<EvalType<'a>, CelError> {
-    match evaluate_expression_inner(expr, ctx) {
-        Ok(val) => Ok(val),
-        Err(e) => Err(CelError::EvaluationError(format!("{expr:?}"), Box::new(e))),
+    match expr {
+        Expression::Spanned(span, expr) => {
+            evaluate_expression(expr, ctx).map_err(|e| e.at_span(*span))
+        }
+        expr => evaluate_expression_inner(expr, ctx),
     }
 }
 
@@ -144,10 +176,60 @@ fn evaluate_expression_inner<'a>(
                 right.try_into_value()?,
             )?))
         }
+        Has(target, name) => Ok(EvalType::Value(evaluate_has(
+            evaluate_expression(target, ctx)?.try_into_value()?,
+            name,
+        )?)),
+        ListMacro(list_macro, target, var, expr) => Ok(EvalType::Value(evaluate_list_macro(
+            *list_macro,
+            evaluate_expression(target, ctx)?.try_into_value()?,
+            var,
+            |inner_ctx| evaluate_expression(expr, inner_ctx)?.try_into_value(),
+            ctx,
+        )?)),
         e => Err(CelError::Unexpected(format!("unimplemented {e:?}"))),
     }
 }
 
+pub(crate) fn evaluate_has(target: CelValue, name: &str) -> Result<CelValue, CelError> {
+    match target {
+        CelValue::Map(map) => Ok(CelValue::Bool(map.contains_key(name))),
+        v => Err(CelError::BadType(CelType::Map, CelType::from(&v))),
+    }
+}
+
+pub(crate) fn evaluate_list_macro(
+    list_macro: ListMacro,
+    target: CelValue,
+    var: &str,
+    body: impl Fn(&CelContext) -> Result<CelValue, CelError>,
+    ctx: &CelContext,
+) -> Result<CelValue, CelError> {
+    let elements: Vec<CelValue> = match target {
+        CelValue::List(list) => list.iter().cloned().collect(),
+        CelValue::Map(map) => map.keys().map(CelValue::from).collect(),
+        v => return Err(CelError::BadType(CelType::List, CelType::from(&v))),
+    };
+    let mut inner_ctx = ctx.clone();
+    let mut results = CelArray::new();
+    for element in elements {
+        inner_ctx.add_variable(var.to_string(), element.clone());
+        let res = body(&inner_ctx)?;
+        match list_macro {
+            ListMacro::All if !res.try_bool()? => return Ok(CelValue::Bool(false)),
+            ListMacro::Exists if res.try_bool()? => return Ok(CelValue::Bool(true)),
+            ListMacro::Map => results.push(res),
+            ListMacro::Filter if res.try_bool()? => results.push(element),
+            _ => (),
+        }
+    }
+    Ok(match list_macro {
+        ListMacro::All => CelValue::Bool(true),
+        ListMacro::Exists => CelValue::Bool(false),
+        ListMacro::Map | ListMacro::Filter => results.into(),
+    })
+}
+
 fn evaluate_member<'a>(
     target: EvalType<'a>,
     member: &ast::Member,
@@ -155,46 +237,67 @@ fn evaluate_member<'a>(
 ) -> Result<EvalType<'a>, CelError> {
     use ast::Member::*;
     match member {
-        Attribute(name) => match target {
-            EvalType::Value(CelValue::Map(map)) if map.contains_key(name) => {
-                Ok(EvalType::Value(map.get(name)))
-            }
-            EvalType::ContextItem(ContextItem::Value(CelValue::Map(map))) => {
-                Ok(EvalType::Value(map.get(name)))
-            }
-            EvalType::ContextItem(ContextItem::Package(p)) => {
-                Ok(EvalType::ContextItem(p.lookup(name)?))
-            }
-            EvalType::ContextItem(ContextItem::Value(v)) => {
-                Ok(EvalType::MemberFn(v, ctx.lookup_member_fn(v, name)?))
-            }
-            _ => Err(CelError::IllegalTarget),
-        },
-        FunctionCall(exprs) => match target {
-            EvalType::ContextItem(ContextItem::Function(f)) => {
-                let mut args = Vec::new();
-                for e in exprs {
-                    args.push(evaluate_expression(e, ctx)?.try_into_value()?)
-                }
-                Ok(EvalType::Value(f(args)?))
-            }
-            EvalType::ContextItem(ContextItem::Package(p)) => {
-                evaluate_member(EvalType::ContextItem(p.package_self()?), member, ctx)
+        Attribute(name) => evaluate_attribute(target, name, ctx),
+        FunctionCall(exprs) => {
+            let mut args = Vec::new();
+            for e in exprs {
+                args.push(evaluate_expression(e, ctx)?.try_into_value()?)
             }
-            EvalType::MemberFn(v, f) => {
-                let mut args = Vec::new();
-                for e in exprs {
-                    args.push(evaluate_expression(e, ctx)?.try_into_value()?)
-                }
-                Ok(EvalType::Value(f(v, args)?))
+            Ok(EvalType::Value(call_function(target, args)?))
+        }
+        Index(_) => Err(CelError::Unexpected("unimplemented index".to_string())),
+    }
+}
+
+pub(crate) fn evaluate_attribute<'a>(
+    target: EvalType<'a>,
+    name: &Arc<String>,
+    ctx: &'a CelContext,
+) -> Result<EvalType<'a>, CelError> {
+    let key = CelKey::from(name);
+    match target {
+        EvalType::Value(CelValue::Map(map)) if map.lookup(&key).is_some() => {
+            Ok(EvalType::Value(map.get(key)))
+        }
+        EvalType::ContextItem(ContextItem::Value(v @ CelValue::Map(map))) => {
+            match map.lookup(&key) {
+                Some(val) => Ok(EvalType::Value(val.clone())),
+                None => match ctx.lookup_member_fn(v, name) {
+                    Ok(f) => Ok(EvalType::MemberFn(v.clone(), f)),
+                    Err(_) => Ok(EvalType::Value(CelValue::Null)),
+                },
             }
-            _ => Err(CelError::IllegalTarget),
-        },
-        _ => unimplemented!(),
+        }
+        EvalType::ContextItem(ContextItem::Package(p)) => {
+            Ok(EvalType::ContextItem(p.lookup(name)?))
+        }
+        EvalType::ContextItem(ContextItem::Value(v)) => Ok(EvalType::MemberFn(
+            v.clone(),
+            ctx.lookup_member_fn(v, name)?,
+        )),
+        EvalType::Value(v) => {
+            let f = ctx.lookup_member_fn(&v, name)?;
+            Ok(EvalType::MemberFn(v, f))
+        }
+        _ => Err(CelError::IllegalTarget),
     }
 }
 
-fn evaluate_arithmetic(
+pub(crate) fn call_function(
+    target: EvalType<'_>,
+    args: Vec<CelValue>,
+) -> Result<CelValue, CelError> {
+    match target {
+        EvalType::ContextItem(ContextItem::Function(f)) => f(args),
+        EvalType::ContextItem(ContextItem::Package(p)) => {
+            call_function(EvalType::ContextItem(p.package_self()?), args)
+        }
+        EvalType::MemberFn(v, f) => f(&v, args),
+        _ => Err(CelError::IllegalTarget),
+    }
+}
+
+pub(crate) fn evaluate_arithmetic(
     op: ArithmeticOp,
     left: CelValue,
     right: CelValue,
@@ -217,6 +320,15 @@ fn evaluate_arithmetic(
             (Int(l), Int(r)) => Ok(Int(l + r)),
             (Double(l), Double(r)) => Ok(Double(l + r)),
             (Decimal(l), Decimal(r)) => Ok(Decimal(l + r)),
+            (String(l), String(r)) => Ok(String(format!("{l}{r}").into())),
+            (Timestamp(t), Duration(d)) | (Duration(d), Timestamp(t)) => t
+                .checked_add_signed(*d)
+                .map(Timestamp)
+                .ok_or_else(|| CelError::TimestampError(format!("{t} + {d} is out of range"))),
+            (Duration(l), Duration(r)) => l
+                .checked_add(r)
+                .map(Duration)
+                .ok_or_else(|| CelError::DurationError(format!("{l} + {r} is out of range"))),
             _ => Err(CelError::NoMatchingOverload(format!(
                 "Cannot apply '+' to {:?} and {:?}",
                 CelType::from(&left),
@@ -228,17 +340,27 @@ fn evaluate_arithmetic(
             (Int(l), Int(r)) => Ok(Int(l - r)),
             (Double(l), Double(r)) => Ok(Double(l - r)),
             (Decimal(l), Decimal(r)) => Ok(Decimal(l - r)),
+            (Timestamp(t), Duration(d)) => t
+                .checked_sub_signed(*d)
+                .map(Timestamp)
+                .ok_or_else(|| CelError::TimestampError(format!("{t} - {d} is out of range"))),
+            (Timestamp(l), Timestamp(r)) => Ok(Duration(l.signed_duration_since(r))),
+            (Duration(l), Duration(r)) => l
+                .checked_sub(r)
+                .map(Duration)
+                .ok_or_else(|| CelError::DurationError(format!("{l} - {r} is out of range"))),
+            (Date(l), Date(r)) => Ok(Int(l.signed_duration_since(*r).num_days())),
             _ => Err(CelError::NoMatchingOverload(format!(
                 "Cannot apply '-' to {:?} and {:?}",
                 CelType::from(&left),
                 CelType::from(&right)
             ))),
         },
-        _ => unimplemented!(),
+        op => Err(CelError::Unexpected(format!("unimplemented {op:?}"))),
     }
 }
 
-fn evaluate_relation(
+pub(crate) fn evaluate_relation(
     op: RelationOp,
     left: CelValue,
     right: CelValue,
@@ -250,6 +372,9 @@ fn evaluate_relation(
             (Int(l), Int(r)) => Ok(Bool(l < r)),
             (Double(l), Double(r)) => Ok(Bool(l < r)),
             (Decimal(l), Decimal(r)) => Ok(Bool(l < r)),
+            (Date(l), Date(r)) => Ok(Bool(l < r)),
+            (Timestamp(l), Timestamp(r)) => Ok(Bool(l < r)),
+            (Duration(l), Duration(r)) => Ok(Bool(l < r)),
             _ => Err(CelError::NoMatchingOverload(format!(
                 "Cannot apply '<' to {:?} and {:?}",
                 CelType::from(&left),
@@ -261,6 +386,9 @@ fn evaluate_relation(
             (Int(l), Int(r)) => Ok(Bool(l <= r)),
             (Double(l), Double(r)) => Ok(Bool(l <= r)),
             (Decimal(l), Decimal(r)) => Ok(Bool(l <= r)),
+            (Date(l), Date(r)) => Ok(Bool(l <= r)),
+            (Timestamp(l), Timestamp(r)) => Ok(Bool(l <= r)),
+            (Duration(l), Duration(r)) => Ok(Bool(l <= r)),
             _ => Err(CelError::NoMatchingOverload(format!(
                 "Cannot apply '<=' to {:?} and {:?}",
                 CelType::from(&left),
@@ -272,6 +400,9 @@ fn evaluate_relation(
             (Int(l), Int(r)) => Ok(Bool(l > r)),
             (Double(l), Double(r)) => Ok(Bool(l > r)),
             (Decimal(l), Decimal(r)) => Ok(Bool(l > r)),
+            (Date(l), Date(r)) => Ok(Bool(l > r)),
+            (Timestamp(l), Timestamp(r)) => Ok(Bool(l > r)),
+            (Duration(l), Duration(r)) => Ok(Bool(l > r)),
             _ => Err(CelError::NoMatchingOverload(format!(
                 "Cannot apply '>' to {:?} and {:?}",
                 CelType::from(&left),
@@ -283,6 +414,9 @@ fn evaluate_relation(
             (Int(l), Int(r)) => Ok(Bool(l >= r)),
             (Double(l), Double(r)) => Ok(Bool(l >= r)),
             (Decimal(l), Decimal(r)) => Ok(Bool(l >= r)),
+            (Date(l), Date(r)) => Ok(Bool(l >= r)),
+            (Timestamp(l), Timestamp(r)) => Ok(Bool(l >= r)),
+            (Duration(l), Duration(r)) => Ok(Bool(l >= r)),
             _ => Err(CelError::NoMatchingOverload(format!(
                 "Cannot apply '>=' to {:?} and {:?}",
                 CelType::from(&left),
@@ -294,6 +428,9 @@ fn evaluate_relation(
             (Int(l), Int(r)) => Ok(Bool(l == r)),
             (Double(l), Double(r)) => Ok(Bool(l == r)),
             (Decimal(l), Decimal(r)) => Ok(Bool(l == r)),
+            (Date(l), Date(r)) => Ok(Bool(l == r)),
+            (Timestamp(l), Timestamp(r)) => Ok(Bool(l == r)),
+            (Duration(l), Duration(r)) => Ok(Bool(l == r)),
             _ => Err(CelError::NoMatchingOverload(format!(
                 "Cannot apply '==' to {:?} and {:?}",
                 CelType::from(&left),
@@ -305,13 +442,16 @@ fn evaluate_relation(
             (Int(l), Int(r)) => Ok(Bool(l != r)),
             (Double(l), Double(r)) => Ok(Bool(l != r)),
             (Decimal(l), Decimal(r)) => Ok(Bool(l != r)),
+            (Date(l), Date(r)) => Ok(Bool(l != r)),
+            (Timestamp(l), Timestamp(r)) => Ok(Bool(l != r)),
+            (Duration(l), Duration(r)) => Ok(Bool(l != r)),
             _ => Err(CelError::NoMatchingOverload(format!(
                 "Cannot apply '!=' to {:?} and {:?}",
                 CelType::from(&left),
                 CelType::from(&right)
             ))),
         },
-        _ => unimplemented!(),
+        op => Err(CelError::Unexpected(format!("unimplemented {op:?}"))),
     }
 }
 
@@ -327,10 +467,54 @@ impl TryFrom<String> for CelExpression {
     fn try_from(source: String) -> Result<Self, Self::Error> {
         let expr = ExpressionParser::new()
             .parse(&source)
-            .map_err(|e| CelError::CelParseError(e.to_string()))?;
-        Ok(Self { source, expr })
+            .map_err(|e| parse_error(&source, e))?;
+        let program = Arc::new(Program::compile(&expr));
+        Ok(Self {
+            source,
+            expr,
+            program,
+        })
+    }
+}
+fn parse_error(source: &str, e: ParseError<usize, Token<'_>, SyntaxError>) -> CelError {
+    let (offset, message) = match e {
+        ParseError::InvalidToken { location } => (location, "invalid token".to_string()),
+        ParseError::UnrecognizedEof { location, expected } => (
+            location,
+            format!("unexpected end of expression{}", expected_tokens(&expected)),
+        ),
+        ParseError::UnrecognizedToken {
+            token: (start, token, _),
+            expected,
+        } => (
+            start,
+            format!(
+                "unexpected token '{}'{}",
+                token.1,
+                expected_tokens(&expected)
+            ),
+        ),
+        ParseError::ExtraToken {
+            token: (start, token, _),
+        } => (start, format!("unexpected token '{}'", token.1)),
+        ParseError::User { error } => (error.span.start, error.message.to_string()),
+    };
+    CelError::CelParseError(message, SourceLocation::from_offset(source, offset))
+}
+
+fn expected_tokens(expected: &[String]) -> String {
+    let tokens: Vec<_> = expected
+        .iter()
+        .filter(|token| !token.starts_with('r'))
+        .map(String::as_str)
+        .collect();
+    if tokens.is_empty() {
+        String::new()
+    } else {
+        format!(", expected one of {}", tokens.join(" "))
     }
 }
+
 impl TryFrom<&str> for CelExpression {
     type Error = CelError;
 
@@ -445,4 +629,344 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn macros() -> anyhow::Result<()> {
+        let mut meta = CelMap::new();
+        meta.insert("tag", "fee");
+        let mut context = CelContext::new();
+        context.add_variable("meta", meta);
+        context.add_variable("amounts", [1, 5, 10].into_iter().collect::<CelArray>());
+
+        let expression = "has(meta.tag)".parse::<CelExpression>().unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Bool(true));
+        let expression = "has(meta.missing)".parse::<CelExpression>().unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Bool(false));
+
+        let expression = "amounts.exists(a, a > 5)".parse::<CelExpression>().unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Bool(true));
+        let expression = "amounts.all(a, a > 5)".parse::<CelExpression>().unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Bool(false));
+
+        let expression = "amounts.map(a, a + 1)".parse::<CelExpression>().unwrap();
+        assert_eq!(
+            expression.evaluate(&context)?,
+            CelValue::from([2, 6, 11].into_iter().collect::<CelArray>())
+        );
+        let expression = "amounts.filter(a, a < 10)"
+            .parse::<CelExpression>()
+            .unwrap();
+        assert_eq!(
+            expression.evaluate(&context)?,
+            CelValue::from([1, 5].into_iter().collect::<CelArray>())
+        );
+
+        let expression = "meta.exists(k, size(k) == 3)"
+            .parse::<CelExpression>()
+            .unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Bool(true));
+
+        let expression = "size(amounts) + meta.size()"
+            .parse::<CelExpression>()
+            .unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Int(4));
+        let expression = "size('héllo')".parse::<CelExpression>().unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Int(5));
+
+        let expression = "meta.tag.all(a, true)".parse::<CelExpression>().unwrap();
+        assert!(matches!(
+            expression.evaluate(&context).unwrap_err().root_cause(),
+            CelError::BadType(CelType::List, CelType::String)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn string_functions() -> anyhow::Result<()> {
+        let mut context = CelContext::new();
+        context.add_variable("code", "ASSETS:CASH:usd");
+
+        for (source, expected) in [
+            ("code.contains('CASH')", CelValue::Bool(true)),
+            ("code.startsWith('ASSETS')", CelValue::Bool(true)),
+            ("code.endsWith('eur')", CelValue::Bool(false)),
+            (
+                "code.matches('^[A-Z]+:[A-Z]+:[a-z]{3}$')",
+                CelValue::Bool(true),
+            ),
+            ("code.lowerAscii()", CelValue::from("assets:cash:usd")),
+            ("code.upperAscii()", CelValue::from("ASSETS:CASH:USD")),
+            ("code.substring(7)", CelValue::from("CASH:usd")),
+            ("code.substring(7, 11)", CelValue::from("CASH")),
+            (
+                "code.split(':').join('/')",
+                CelValue::from("ASSETS/CASH/usd"),
+            ),
+            ("code.split(':').size()", CelValue::Int(3)),
+            (
+                "'Fee for ' + code",
+                CelValue::from("Fee for ASSETS:CASH:usd"),
+            ),
+            (
+                "'amount: ' + string(decimal('1.50'))",
+                CelValue::from("amount: 1.50"),
+            ),
+            ("string(date('2024-01-31'))", CelValue::from("2024-01-31")),
+        ] {
+            let expression = source.parse::<CelExpression>().unwrap();
+            assert_eq!(expression.evaluate(&context)?, expected, "{source}");
+        }
+
+        let expression = "code.substring(20)".parse::<CelExpression>().unwrap();
+        assert!(expression.evaluate(&context).is_err());
+        let expression = "code.matches('[')".parse::<CelExpression>().unwrap();
+        assert!(expression.evaluate(&context).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn date_and_duration_functions() -> anyhow::Result<()> {
+        use chrono::{DateTime, Duration, Utc};
+
+        let time: DateTime<Utc> = "2024-01-31T23:30:00Z".parse().unwrap();
+        let mut context = CelContext::new();
+        context.add_variable("now", time);
+
+        for (source, expected) in [
+            (
+                "now + duration('1h')",
+                CelValue::from(time + Duration::hours(1)),
+            ),
+            (
+                "now - duration('90m')",
+                CelValue::from(time - Duration::minutes(90)),
+            ),
+            (
+                "(now + duration('2h')) - now",
+                CelValue::from(Duration::hours(2)),
+            ),
+            ("duration('1h30m').getMinutes()", CelValue::Int(90)),
+            ("now.date()", CelValue::from(date(2024, 1, 31))),
+            ("now.date('+02:00')", CelValue::from(date(2024, 2, 1))),
+            (
+                "now.date('Europe/Berlin')",
+                CelValue::from(date(2024, 2, 1)),
+            ),
+            (
+                "(now - duration('1h')).date('Europe/Berlin')",
+                CelValue::from(date(2024, 1, 31)),
+            ),
+            (
+                "(now + duration('4319h')).date('Europe/Berlin')",
+                CelValue::from(date(2024, 7, 30)),
+            ),
+            (
+                "(now + duration('4319h')).date('+01:00')",
+                CelValue::from(date(2024, 7, 29)),
+            ),
+            ("now.date().addMonths(1)", CelValue::from(date(2024, 2, 29))),
+            (
+                "now.date().addDays(-31)",
+                CelValue::from(date(2023, 12, 31)),
+            ),
+            (
+                "date('2024-02-10').startOfMonth()",
+                CelValue::from(date(2024, 2, 1)),
+            ),
+            (
+                "date('2024-02-10').endOfMonth()",
+                CelValue::from(date(2024, 2, 29)),
+            ),
+            ("date('2024-02-11').dayOfWeek()", CelValue::Int(0)),
+            ("date('2024-03-01') - date('2024-02-01')", CelValue::Int(29)),
+            (
+                "date('2024-03-01') > date('2024-02-01')",
+                CelValue::Bool(true),
+            ),
+            ("duration('1h') < duration('59m')", CelValue::Bool(false)),
+        ] {
+            let expression = source.parse::<CelExpression>().unwrap();
+            assert_eq!(expression.evaluate(&context)?, expected, "{source}");
+        }
+
+        let expression = "now.date('Mars/Olympus')".parse::<CelExpression>().unwrap();
+        assert!(expression.evaluate(&context).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn decimal_functions() -> anyhow::Result<()> {
+        let mut context = CelContext::new();
+        context.add_variable("amount", "1234.5".parse::<rust_decimal::Decimal>()?);
+
+        for (source, expected) in [
+            ("decimal.round(amount * decimal('0.015'), 2)", "18.52"),
+            ("decimal.round(decimal('2.345'), 2, 'HALF_UP')", "2.35"),
+            ("decimal.round(decimal('2.345'), 2, 'DOWN')", "2.34"),
+            ("decimal.floor(decimal('-1.5'))", "-2"),
+            ("decimal.ceil(decimal('1.1'))", "2"),
+            ("decimal.truncate(decimal('1.789'), 1)", "1.7"),
+            ("decimal.abs(decimal('-3'))", "3"),
+            ("decimal.neg(amount)", "-1234.5"),
+            ("decimal.max(decimal('5'), amount * decimal('0.001'))", "5"),
+            ("decimal.min(decimal('5'), amount)", "5"),
+            ("decimal.div(decimal('10'), decimal('3'), 4)", "3.3333"),
+            (
+                "decimal.div(decimal('2'), decimal('3'), 2, 'FLOOR')",
+                "0.66",
+            ),
+        ] {
+            let expression = source.parse::<CelExpression>().unwrap();
+            assert_eq!(
+                expression.evaluate(&context)?,
+                CelValue::Decimal(expected.parse()?),
+                "{source}"
+            );
+        }
+
+        let expression = "decimal.cmp(amount, decimal('2000'))"
+            .parse::<CelExpression>()
+            .unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Int(-1));
+
+        for source in [
+            "decimal.div(amount, decimal('0'), 2)",
+            "decimal.round(amount, 2, 'SIDEWAYS')",
+            "decimal.round(amount, -1)",
+        ] {
+            let expression = source.parse::<CelExpression>().unwrap();
+            assert!(expression.evaluate(&context).is_err(), "{source}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn host_functions() -> anyhow::Result<()> {
+        use crate::{CelPackage, CelSignature};
+        use chrono::Datelike;
+
+        let mut context = CelContext::new();
+        context.add_function(
+            "double",
+            CelSignature::new([CelType::Int], CelType::Int),
+            |args| match args[0] {
+                CelValue::Int(i) => Ok(CelValue::Int(i * 2)),
+                _ => unreachable!(),
+            },
+        )?;
+        let mut fx = CelPackage::default();
+        fx.add_function(
+            "rate",
+            CelSignature::new([CelType::String], CelType::Decimal),
+            |_| Ok(CelValue::Decimal("1.1".parse().unwrap())),
+        )?;
+        context.add_package("fx", fx)?;
+        let mut date_fns = CelPackage::default();
+        date_fns.add_member_function(
+            "isBusinessDay",
+            CelSignature::new([], CelType::Bool),
+            |target, _| match target {
+                CelValue::Date(d) => Ok(CelValue::Bool(d.weekday().num_days_from_monday() < 5)),
+                _ => unreachable!(),
+            },
+        )?;
+        context.add_package("date", date_fns)?;
+
+        let expression = "double(21)".parse::<CelExpression>().unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Int(42));
+        let expression = "fx.rate('EURUSD')".parse::<CelExpression>().unwrap();
+        assert_eq!(
+            expression.evaluate(&context)?,
+            CelValue::Decimal("1.1".parse().unwrap())
+        );
+        let expression = "date('2024-06-08').isBusinessDay()"
+            .parse::<CelExpression>()
+            .unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Bool(false));
+        // builtin members of the extended package are still available
+        let expression = "date('2024-06-08').addDays(2).isBusinessDay()"
+            .parse::<CelExpression>()
+            .unwrap();
+        assert_eq!(expression.evaluate(&context)?, CelValue::Bool(true));
+
+        let expression = "double('21')".parse::<CelExpression>().unwrap();
+        assert!(matches!(
+            expression.evaluate(&context).unwrap_err().root_cause(),
+            CelError::WrongArgumentType(CelType::String, CelType::Int)
+        ));
+        let expression = "double(1, 2)".parse::<CelExpression>().unwrap();
+        assert!(matches!(
+            expression.evaluate(&context).unwrap_err().root_cause(),
+            CelError::NoMatchingOverload(_)
+        ));
+
+        let noop = |_: Vec<CelValue>| Ok(CelValue::Null);
+        let sig = || CelSignature::new([], CelType::Null);
+        assert!(matches!(
+            context.add_function("double", sig(), noop),
+            Err(CelError::RegistrationError(_))
+        ));
+        assert!(matches!(
+            context.add_function("size", sig(), noop),
+            Err(CelError::RegistrationError(_))
+        ));
+        assert!(matches!(
+            context.add_function("has", sig(), noop),
+            Err(CelError::RegistrationError(_))
+        ));
+        assert!(matches!(
+            context.add_function("1st", sig(), noop),
+            Err(CelError::RegistrationError(_))
+        ));
+        let mut dup = CelPackage::default();
+        dup.add_function("rate", sig(), noop)?;
+        assert!(matches!(
+            context.add_package("fx", dup),
+            Err(CelError::RegistrationError(_))
+        ));
+        let mut members = CelPackage::default();
+        members.add_member_function("foo", sig(), |_, _| Ok(CelValue::Null))?;
+        assert!(matches!(
+            context.add_package("fx2", members),
+            Err(CelError::RegistrationError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn error_locations() {
+        let mut params = CelMap::new();
+        params.insert("amount", 1);
+        let mut context = CelContext::new();
+        context.add_variable("params", params);
+
+        let expression = "params.amount > 0 &&\n  unknown.field == 1"
+            .parse::<CelExpression>()
+            .unwrap();
+        let err = expression.evaluate(&context).unwrap_err();
+        assert_eq!(err.sub_expression(), Some("unknown"));
+        assert_eq!(
+            err.location(),
+            Some(cel_parser::SourceLocation { line: 2, column: 3 })
+        );
+        assert!(matches!(err.root_cause(), CelError::UnknownIdent(_)));
+
+        let err = "params.amount +\n\n  * 2"
+            .parse::<CelExpression>()
+            .unwrap_err();
+        assert!(matches!(err, CelError::CelParseError(..)));
+        assert_eq!(
+            err.location(),
+            Some(cel_parser::SourceLocation { line: 3, column: 3 })
+        );
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
 }
@@ -5,11 +5,17 @@ mod builtins;
 mod cel_type;
 mod context;
 mod error;
+mod extension;
 mod interpreter;
+mod program;
+mod type_check;
 mod value;
 
+pub use cel_parser::{SourceLocation, Span};
 pub use cel_type::*;
 pub use context::*;
 pub use error::*;
+pub use extension::*;
 pub use interpreter::*;
+pub use type_check::*;
 pub use value::*;
@@ -0,0 +1,460 @@
+use cel_parser::{
+    ast::{self, ArithmeticOp, Expression, ListMacro, RelationOp},
+    Span,
+};
+
+use smallvec::SmallVec;
+
+use std::sync::Arc;
+
+use crate::{context::*, error::*, interpreter::*, value::*};
+
+lazy_static::lazy_static! {
+    static ref BUILTINS: CelContext = CelContext::new();
+}
+
+/// Builtins that always return the same value for the same (constant) arguments.
+/// `date()` without arguments returns the current date and is excluded separately.
+const PURE_BUILTINS: [&str; 6] = ["date", "decimal", "duration", "string", "timestamp", "uuid"];
+
+/// An expression lowered to a flat sequence of stack operations.
+///
+/// Sub-expressions that only depend on literals and pure builtins are
+/// evaluated once at compile time and identifiers are interned into slots
+/// that get resolved at most once per evaluation.
+#[derive(Debug, Default)]
+pub(crate) struct Program {
+    ops: Vec<Op>,
+    spans: Vec<Option<Span>>,
+    idents: Vec<Arc<String>>,
+    /// Compiled without folding - used when the context binds a variable to
+    /// the name of a builtin (eg. a list macro variable called `decimal`).
+    unfolded: Option<Box<Program>>,
+}
+
+#[derive(Debug)]
+enum Op {
+    Const(CelValue),
+    Ident(usize),
+    Attribute(Arc<String>),
+    Call(usize),
+    Arithmetic(ArithmeticOp),
+    Relation(RelationOp),
+    Has(Arc<String>),
+    ListMacro(ListMacro, Arc<String>, Box<Program>),
+    MakeMap(usize),
+    JumpIfFalse(usize),
+    Jump(usize),
+    Unimplemented(String),
+}
+
+impl Program {
+    pub(crate) fn compile(expr: &Expression) -> Self {
+        let mut compiler = Compiler {
+            fold: true,
+            ..Default::default()
+        };
+        compiler.compile(expr);
+        let mut program = compiler.program;
+        if compiler.folded {
+            let mut compiler = Compiler::default();
+            compiler.compile(expr);
+            program.unfolded = Some(Box::new(compiler.program));
+        }
+        program
+    }
+
+    pub(crate) fn evaluate<'a>(&self, ctx: &'a CelContext) -> Result<EvalType<'a>, CelError> {
+        if let Some(unfolded) = self.unfolded.as_ref() {
+            if ctx.shadows_builtins() {
+                return unfolded.evaluate(ctx);
+            }
+        }
+        let mut resolved: SmallVec<[Option<&'a ContextItem>; 4]> =
+            SmallVec::from_elem(None, self.idents.len());
+        let mut stack: Stack<'a> = SmallVec::new();
+        let mut pc = 0;
+        while pc < self.ops.len() {
+            let next = self
+                .execute(pc, ctx, &mut resolved, &mut stack)
+                .map_err(|e| match self.spans[pc] {
+                    Some(span) => e.at_span(span),
+                    None => e,
+                })?;
+            pc = next;
+        }
+        stack
+            .pop()
+            .ok_or_else(|| CelError::Unexpected("program didn't produce a value".to_string()))
+    }
+
+    fn execute<'a>(
+        &self,
+        pc: usize,
+        ctx: &'a CelContext,
+        resolved: &mut [Option<&'a ContextItem>],
+        stack: &mut Stack<'a>,
+    ) -> Result<usize, CelError> {
+        match &self.ops[pc] {
+            Op::Const(val) => stack.push(EvalType::Value(val.clone())),
+            Op::Ident(slot) => {
+                let item = match resolved[*slot] {
+                    Some(item) => item,
+                    None => {
+                        let item = ctx.lookup_ident(&self.idents[*slot])?;
+                        resolved[*slot] = Some(item);
+                        item
+                    }
+                };
+                stack.push(EvalType::ContextItem(item));
+            }
+            Op::Attribute(name) => {
+                let target = pop(stack)?;
+                stack.push(evaluate_attribute(target, name, ctx)?);
+            }
+            Op::Call(argc) => {
+                let mut args = Vec::with_capacity(*argc);
+                for arg in stack.drain(stack.len() - argc..) {
+                    args.push(arg.try_into_value()?);
+                }
+                let target = pop(stack)?;
+                stack.push(EvalType::Value(call_function(target, args)?));
+            }
+            Op::Arithmetic(op) => {
+                let right = pop(stack)?.try_into_value()?;
+                let left = pop(stack)?.try_into_value()?;
+                stack.push(EvalType::Value(evaluate_arithmetic(*op, left, right)?));
+            }
+            Op::Relation(op) => {
+                let right = pop(stack)?.try_into_value()?;
+                let left = pop(stack)?.try_into_value()?;
+                stack.push(EvalType::Value(evaluate_relation(*op, left, right)?));
+            }
+            Op::Has(name) => {
+                let target = pop(stack)?.try_into_value()?;
+                stack.push(EvalType::Value(evaluate_has(target, name)?));
+            }
+            Op::ListMacro(list_macro, var, body) => {
+                let target = pop(stack)?.try_into_value()?;
+                stack.push(EvalType::Value(evaluate_list_macro(
+                    *list_macro,
+                    target,
+                    var,
+                    |inner_ctx| body.evaluate(inner_ctx)?.try_into_value(),
+                    ctx,
+                )?));
+            }
+            Op::MakeMap(len) => {
+                let mut map = CelMap::new();
+                let mut entries = stack.drain(stack.len() - 2 * len..);
+                while let (Some(k), Some(v)) = (entries.next(), entries.next()) {
+                    map.insert(k.try_into_key()?, v.try_into_value()?);
+                }
+                drop(entries);
+                stack.push(EvalType::Value(CelValue::from(map)));
+            }
+            Op::JumpIfFalse(target) => {
+                if !pop(stack)?.try_into_bool()? {
+                    return Ok(*target);
+                }
+            }
+            Op::Jump(target) => return Ok(*target),
+            Op::Unimplemented(e) => {
+                return Err(CelError::Unexpected(format!("unimplemented {e}")));
+            }
+        }
+        Ok(pc + 1)
+    }
+}
+
+type Stack<'a> = SmallVec<[EvalType<'a>; 8]>;
+
+fn pop<'a>(stack: &mut Stack<'a>) -> Result<EvalType<'a>, CelError> {
+    stack
+        .pop()
+        .ok_or_else(|| CelError::Unexpected("stack underflow".to_string()))
+}
+
+#[derive(Default)]
+struct Compiler {
+    program: Program,
+    span: Option<Span>,
+    fold: bool,
+    folded: bool,
+}
+
+impl Compiler {
+    fn emit(&mut self, op: Op) -> usize {
+        self.program.ops.push(op);
+        self.program.spans.push(self.span);
+        self.program.ops.len() - 1
+    }
+
+    fn patch_jump(&mut self, idx: usize) {
+        let target = self.program.ops.len();
+        match &mut self.program.ops[idx] {
+            Op::JumpIfFalse(t) | Op::Jump(t) => *t = target,
+            _ => unreachable!("not a jump"),
+        }
+    }
+
+    fn slot(&mut self, name: &Arc<String>) -> usize {
+        match self.program.idents.iter().position(|n| n == name) {
+            Some(slot) => slot,
+            None => {
+                self.program.idents.push(Arc::clone(name));
+                self.program.idents.len() - 1
+            }
+        }
+    }
+
+    fn compile(&mut self, expr: &Expression) {
+        if let Some(val) = self.fold.then(|| fold_constant(expr)).flatten() {
+            self.emit(Op::Const(val));
+            self.folded = true;
+            return;
+        }
+        use Expression::*;
+        match expr {
+            Spanned(span, inner) => {
+                let outer = self.span.replace(*span);
+                self.compile(inner);
+                self.span = outer;
+            }
+            Ternary(cond, left, right) => {
+                self.compile(cond);
+                let to_else = self.emit(Op::JumpIfFalse(0));
+                self.compile(left);
+                let to_end = self.emit(Op::Jump(0));
+                self.patch_jump(to_else);
+                self.compile(right);
+                self.patch_jump(to_end);
+            }
+            Member(target, member) => {
+                self.compile(target);
+                match member.as_ref() {
+                    ast::Member::Attribute(name) => {
+                        self.emit(Op::Attribute(Arc::clone(name)));
+                    }
+                    ast::Member::FunctionCall(args) => {
+                        for arg in args {
+                            self.compile(arg);
+                        }
+                        self.emit(Op::Call(args.len()));
+                    }
+                    member => {
+                        self.emit(Op::Unimplemented(format!("{member:?}")));
+                    }
+                }
+            }
+            Map(entries) => {
+                for (k, v) in entries {
+                    self.compile(k);
+                    self.compile(v);
+                }
+                self.emit(Op::MakeMap(entries.len()));
+            }
+            Ident(name) => {
+                let slot = self.slot(name);
+                self.emit(Op::Ident(slot));
+            }
+            Literal(val) => {
+                self.emit(Op::Const(CelValue::from(val)));
+            }
+            Arithmetic(op, left, right) => {
+                self.compile(left);
+                self.compile(right);
+                self.emit(Op::Arithmetic(*op));
+            }
+            Relation(op, left, right) => {
+                self.compile(left);
+                self.compile(right);
+                self.emit(Op::Relation(*op));
+            }
+            Has(target, name) => {
+                self.compile(target);
+                self.emit(Op::Has(Arc::clone(name)));
+            }
+            ListMacro(list_macro, target, var, body) => {
+                self.compile(target);
+                let body = Program::compile(body);
+                self.emit(Op::ListMacro(*list_macro, Arc::clone(var), Box::new(body)));
+            }
+            e => {
+                self.emit(Op::Unimplemented(format!("{e:?}")));
+            }
+        }
+    }
+}
+
+/// Evaluates `expr` up front if it doesn't reference any variables.
+/// Failures are left to be reported when the program is evaluated.
+fn fold_constant(expr: &Expression) -> Option<CelValue> {
+    if matches!(expr.unspanned(), Expression::Literal(_)) || !is_constant(expr) {
+        return None;
+    }
+    match evaluate_expression(expr, &BUILTINS) {
+        Ok(EvalType::Value(val)) => Some(val),
+        _ => None,
+    }
+}
+
+fn is_constant(expr: &Expression) -> bool {
+    use Expression::*;
+    match expr {
+        Spanned(_, inner) => is_constant(inner),
+        Literal(_) => true,
+        Ternary(cond, left, right) => is_constant(cond) && is_constant(left) && is_constant(right),
+        // Only ops the interpreter implements - the others fail on evaluation
+        Arithmetic(ArithmeticOp::Divide | ArithmeticOp::Modulus, ..)
+        | Relation(RelationOp::In, ..) => false,
+        Arithmetic(_, left, right) | Relation(_, left, right) => {
+            is_constant(left) && is_constant(right)
+        }
+        Map(entries) => entries
+            .iter()
+            .all(|(k, v)| is_constant(k) && is_constant(v)),
+        Has(target, _) => is_constant(target),
+        Member(target, member) => match (target.unspanned(), member.as_ref()) {
+            (Ident(name), ast::Member::FunctionCall(args)) => {
+                PURE_BUILTINS.contains(&name.as_str())
+                    && !(name.as_str() == "date" && args.is_empty())
+                    && args.iter().all(is_constant)
+            }
+            (_, ast::Member::FunctionCall(args)) => {
+                is_constant(target) && args.iter().all(is_constant)
+            }
+            (_, ast::Member::Attribute(_)) => is_constant(target),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(source: &str) -> Program {
+        let expr: CelExpression = source.parse().unwrap();
+        Program::compile(expr.ast())
+    }
+
+    #[test]
+    fn folds_constants() {
+        let program = compile("decimal('1') + decimal('2')");
+        assert!(matches!(
+            program.ops.as_slice(),
+            [Op::Const(CelValue::Decimal(d))] if *d == 3.into()
+        ));
+
+        let program = compile("params.amount > decimal('0') ? 'DEBIT' : 'CREDIT'");
+        assert_eq!(program.idents.len(), 1);
+        assert!(program
+            .ops
+            .iter()
+            .any(|op| matches!(op, Op::Const(CelValue::Decimal(_)))));
+
+        // `date()` depends on the time of evaluation
+        let program = compile("date()");
+        assert!(!matches!(program.ops.as_slice(), [Op::Const(_)]));
+        // Errors are raised on evaluation
+        let program = compile("decimal('abc')");
+        assert!(!matches!(program.ops.as_slice(), [Op::Const(_)]));
+    }
+
+    #[test]
+    fn matches_interpreter() {
+        let mut params = CelMap::new();
+        params.insert("amount", CelValue::Decimal(10.into()));
+        params.insert(
+            "tags",
+            [CelValue::from("a"), CelValue::from("bb")]
+                .into_iter()
+                .collect::<CelArray>(),
+        );
+        let mut ctx = CelContext::new();
+        ctx.add_variable("params", params);
+
+        let mut map = CelMap::new();
+        map.insert("a", CelValue::Decimal(20.into()));
+        map.insert(
+            "b",
+            CelValue::Date(chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()),
+        );
+        // `None` marks cases that are expected to fail
+        for (source, expected) in [
+            (
+                "params.amount > decimal('5') ? decimal.round(params.amount, 0) : decimal('0')",
+                Some(CelValue::Decimal(10.into())),
+            ),
+            (
+                "params.tags.exists(t, size(t) > 1) && has(params.amount)",
+                Some(CelValue::Bool(true)),
+            ),
+            (
+                "params.tags.map(t, t.upperAscii()).join('-')",
+                Some(CelValue::from("A-BB")),
+            ),
+            (
+                "{'a': params.amount * decimal('2'), 'b': date('2024-01-31').addMonths(1)}",
+                Some(CelValue::from(map)),
+            ),
+            (
+                "timestamp('2024-01-01T00:00:00Z') + duration('1h') > timestamp('2024-01-01T00:30:00Z')",
+                Some(CelValue::Bool(true)),
+            ),
+            ("params.amount + 1", None),
+            ("params.unknown.attr", None),
+            ("unknown(1)", None),
+            ("decimal('1') / decimal('2')", None),
+            ("1 % 2", None),
+            ("1 in {1: 2}", None),
+        ] {
+            let expr: CelExpression = source.parse().unwrap();
+            match (expr.evaluate(&ctx), expr.interpret(&ctx), expected) {
+                (Ok(compiled), Ok(interpreted), Some(expected)) => {
+                    assert_eq!(compiled, expected, "{source}");
+                    assert_eq!(interpreted, expected, "{source}");
+                }
+                (Err(compiled), Err(interpreted), None) => {
+                    assert_eq!(compiled.to_string(), interpreted.to_string(), "{source}")
+                }
+                (compiled, interpreted, expected) => {
+                    panic!("{source}: compiled {compiled:?}, interpreted {interpreted:?}, expected {expected:?}")
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parses_unimplemented_ops() {
+        for source in ["decimal('1') / decimal('2')", "1 % 2", "1 in {1: 2}"] {
+            let expr = CelExpression::try_from(source).unwrap();
+            assert!(expr.evaluate(&CelContext::new()).is_err(), "{source}");
+        }
+    }
+
+    #[test]
+    fn does_not_fold_shadowed_builtins() {
+        let expr: CelExpression = "['1'].map(decimal, decimal('2'))".parse().unwrap();
+        let ctx = CelContext::new();
+        assert_eq!(
+            expr.evaluate(&ctx).unwrap_err().to_string(),
+            expr.interpret(&ctx).unwrap_err().to_string()
+        );
+
+        let expr: CelExpression = "decimal('1') + decimal('2')".parse().unwrap();
+        let mut ctx = CelContext::new();
+        ctx.add_variable("decimal", CelValue::Decimal(1.into()));
+        assert!(expr.evaluate(&ctx).is_err());
+        ctx.remove_variable("decimal");
+        assert!(expr.evaluate(&ctx).is_err());
+    }
+
+    #[test]
+    fn interns_identifiers() {
+        let program = compile("params.a + params.b + params.c");
+        assert_eq!(program.idents, vec![Arc::new("params".to_string())]);
+    }
+}
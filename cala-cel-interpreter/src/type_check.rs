@@ -0,0 +1,1025 @@
+use cel_parser::ast::{self, ArithmeticOp, Expression, Literal, RelationOp};
+
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{cel_type::*, context::CelPackage, error::*, extension::*, interpreter::CelExpression};
+
+/// The statically known type of a value or expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CelTypeDecl {
+    Type(CelType),
+    /// A map whose attributes are known up front (eg. `params`).
+    Struct(HashMap<String, CelTypeDecl>),
+    /// Only known at evaluation time - checks involving it are deferred.
+    Dyn,
+}
+
+impl CelTypeDecl {
+    pub fn cel_type(&self) -> Option<CelType> {
+        match self {
+            CelTypeDecl::Type(t) => Some(*t),
+            CelTypeDecl::Struct(_) => Some(CelType::Map),
+            CelTypeDecl::Dyn => None,
+        }
+    }
+
+    /// Returns false if a value of this type can never be coerced into `expected`.
+    pub fn is_assignable_to(&self, expected: CelType) -> bool {
+        self.cel_type().is_none_or(|t| t == expected)
+    }
+}
+
+impl From<CelType> for CelTypeDecl {
+    fn from(t: CelType) -> Self {
+        CelTypeDecl::Type(t)
+    }
+}
+
+impl std::fmt::Display for CelTypeDecl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.cel_type() {
+            Some(t) => write!(f, "{t:?}"),
+            None => write!(f, "Dyn"),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Signature {
+    overloads: Vec<(Vec<CelType>, CelType)>,
+}
+
+impl From<&CelSignature> for Signature {
+    fn from(signature: &CelSignature) -> Self {
+        Self::new([(signature.params.clone(), signature.returns)])
+    }
+}
+
+impl Signature {
+    fn new(overloads: impl IntoIterator<Item = (Vec<CelType>, CelType)>) -> Self {
+        Self {
+            overloads: overloads.into_iter().collect(),
+        }
+    }
+
+    fn check_call(&self, name: &str, args: &[CelTypeDecl]) -> Result<CelTypeDecl, CelError> {
+        let matching = self.overloads.iter().find(|(params, _)| {
+            params.len() == args.len()
+                && params
+                    .iter()
+                    .zip(args.iter())
+                    .all(|(param, arg)| arg.is_assignable_to(*param))
+        });
+        match matching {
+            Some((_, ret)) => Ok(CelTypeDecl::Type(*ret)),
+            None => Err(CelError::NoMatchingOverload(format!(
+                "Cannot call '{name}' with ({})",
+                args.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum TypeItem {
+    Value(CelTypeDecl),
+    Function(Signature),
+    Package(HashMap<&'static str, Signature>),
+}
+
+const SELF_PACKAGE_NAME: &str = "self";
+
+/// The static counterpart of a [CelContext](crate::CelContext).
+/// Holds the types of the variables an expression may reference
+/// and the signatures of the builtin functions.
+#[derive(Clone)]
+pub struct CelTypeContext {
+    idents: HashMap<Cow<'static, str>, TypeItem>,
+    member_fns: HashMap<CelType, HashMap<&'static str, Signature>>,
+}
+
+impl CelTypeContext {
+    pub fn new() -> Self {
+        use CelType::*;
+
+        let mut idents = HashMap::new();
+        idents.insert(
+            Cow::Borrowed("date"),
+            TypeItem::Package(HashMap::from([(
+                SELF_PACKAGE_NAME,
+                Signature::new([(vec![], Date), (vec![String], Date)]),
+            )])),
+        );
+        idents.insert(
+            Cow::Borrowed("duration"),
+            TypeItem::Package(HashMap::from([(
+                SELF_PACKAGE_NAME,
+                Signature::new([(vec![String], Duration), (vec![Duration], Duration)]),
+            )])),
+        );
+        idents.insert(
+            Cow::Borrowed("uuid"),
+            TypeItem::Function(Signature::new([(vec![String], Uuid)])),
+        );
+        idents.insert(
+            Cow::Borrowed("size"),
+            TypeItem::Function(Signature::new(
+                [List, Map, String, Bytes].map(|t| (vec![t], Int)),
+            )),
+        );
+        idents.insert(
+            Cow::Borrowed("decimal"),
+            TypeItem::Package(HashMap::from([
+                (
+                    SELF_PACKAGE_NAME,
+                    Signature::new([(vec![Decimal], Decimal), (vec![String], Decimal)]),
+                ),
+                ("Add", Signature::new([(vec![Decimal, Decimal], Decimal)])),
+                (
+                    "round",
+                    Signature::new([
+                        (vec![Decimal, Int], Decimal),
+                        (vec![Decimal, Int, String], Decimal),
+                    ]),
+                ),
+                ("floor", Signature::new([(vec![Decimal], Decimal)])),
+                ("ceil", Signature::new([(vec![Decimal], Decimal)])),
+                (
+                    "truncate",
+                    Signature::new([(vec![Decimal], Decimal), (vec![Decimal, Int], Decimal)]),
+                ),
+                ("abs", Signature::new([(vec![Decimal], Decimal)])),
+                ("neg", Signature::new([(vec![Decimal], Decimal)])),
+                ("min", Signature::new([(vec![Decimal, Decimal], Decimal)])),
+                ("max", Signature::new([(vec![Decimal, Decimal], Decimal)])),
+                ("cmp", Signature::new([(vec![Decimal, Decimal], Int)])),
+                (
+                    "div",
+                    Signature::new([
+                        (vec![Decimal, Decimal, Int], Decimal),
+                        (vec![Decimal, Decimal, Int, String], Decimal),
+                    ]),
+                ),
+            ])),
+        );
+        idents.insert(
+            Cow::Borrowed("string"),
+            TypeItem::Package(HashMap::from([(
+                SELF_PACKAGE_NAME,
+                Signature::new(
+                    [
+                        String, Int, UInt, Double, Decimal, Bool, Uuid, Date, Timestamp, Bytes,
+                    ]
+                    .map(|t| (vec![t], String)),
+                ),
+            )])),
+        );
+        idents.insert(
+            Cow::Borrowed("timestamp"),
+            TypeItem::Package(HashMap::from([(
+                SELF_PACKAGE_NAME,
+                Signature::new([(vec![String], Timestamp)]),
+            )])),
+        );
+
+        let mut member_fns = HashMap::new();
+        for t in [List, Map, String] {
+            member_fns.insert(
+                t,
+                HashMap::from([("size", Signature::new([(vec![], Int)]))]),
+            );
+        }
+        let string_fns = member_fns.entry(String).or_insert_with(HashMap::new);
+        for name in ["contains", "startsWith", "endsWith", "matches"] {
+            string_fns.insert(name, Signature::new([(vec![String], Bool)]));
+        }
+        for name in ["lowerAscii", "upperAscii"] {
+            string_fns.insert(name, Signature::new([(vec![], String)]));
+        }
+        string_fns.insert("split", Signature::new([(vec![String], List)]));
+        string_fns.insert(
+            "substring",
+            Signature::new([(vec![Int], String), (vec![Int, Int], String)]),
+        );
+        member_fns.entry(List).or_insert_with(HashMap::new).insert(
+            "join",
+            Signature::new([(vec![], String), (vec![String], String)]),
+        );
+        member_fns.insert(
+            Timestamp,
+            HashMap::from([
+                ("format", Signature::new([(vec![String], String)])),
+                (
+                    "date",
+                    Signature::new([(vec![], Date), (vec![String], Date)]),
+                ),
+            ]),
+        );
+        member_fns.insert(
+            Date,
+            HashMap::from([
+                ("addDays", Signature::new([(vec![Int], Date)])),
+                ("addMonths", Signature::new([(vec![Int], Date)])),
+                ("startOfMonth", Signature::new([(vec![], Date)])),
+                ("endOfMonth", Signature::new([(vec![], Date)])),
+                ("dayOfWeek", Signature::new([(vec![], Int)])),
+            ]),
+        );
+        member_fns.insert(
+            Duration,
+            ["getHours", "getMinutes", "getSeconds", "getMilliseconds"]
+                .into_iter()
+                .map(|name| (name, Signature::new([(vec![], Int)])))
+                .collect(),
+        );
+
+        Self { idents, member_fns }
+    }
+
+    pub fn add_variable(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        decl: impl Into<CelTypeDecl>,
+    ) {
+        self.idents
+            .insert(name.into(), TypeItem::Value(decl.into()));
+    }
+
+    /// Declares a function registered via [CelContext::add_function](crate::CelContext::add_function).
+    pub fn add_function(
+        &mut self,
+        name: &'static str,
+        signature: &CelSignature,
+    ) -> Result<(), CelError> {
+        validate_name(name)?;
+        if self.idents.contains_key(name) {
+            return Err(CelError::RegistrationError(format!(
+                "'{name}' already exists"
+            )));
+        }
+        self.idents
+            .insert(Cow::Borrowed(name), TypeItem::Function(signature.into()));
+        Ok(())
+    }
+
+    /// Declares a package registered via [CelContext::add_package](crate::CelContext::add_package).
+    pub fn add_package(
+        &mut self,
+        name: &'static str,
+        package: &CelPackage,
+    ) -> Result<(), CelError> {
+        validate_name(name)?;
+        if !package.member_signatures.is_empty() && CelType::from_package_name(name).is_none() {
+            return Err(CelError::RegistrationError(format!(
+                "'{name}' does not name a type - cannot register member functions"
+            )));
+        }
+        let package_type = CelType::from_package_name(name);
+        match self.idents.get(name) {
+            None => (),
+            Some(TypeItem::Package(fns)) => {
+                if let Some(fn_name) = package.signatures.keys().find(|f| fns.contains_key(*f)) {
+                    return Err(CelError::RegistrationError(format!(
+                        "function '{fn_name}' already exists"
+                    )));
+                }
+            }
+            Some(_) => {
+                return Err(CelError::RegistrationError(format!(
+                    "'{name}' already exists and is not a package"
+                )))
+            }
+        }
+        if let Some(member_fns) = package_type.and_then(|t| self.member_fns.get(&t)) {
+            if let Some(fn_name) = package
+                .member_signatures
+                .keys()
+                .find(|f| member_fns.contains_key(*f))
+            {
+                return Err(CelError::RegistrationError(format!(
+                    "member function '{fn_name}' already exists"
+                )));
+            }
+        }
+
+        let fns = match self
+            .idents
+            .entry(Cow::Borrowed(name))
+            .or_insert_with(|| TypeItem::Package(HashMap::new()))
+        {
+            TypeItem::Package(fns) => fns,
+            _ => unreachable!("checked above"),
+        };
+        fns.extend(
+            package
+                .signatures
+                .iter()
+                .map(|(fn_name, signature)| (*fn_name, signature.into())),
+        );
+        if let Some(t) = package_type {
+            self.member_fns.entry(t).or_default().extend(
+                package
+                    .member_signatures
+                    .iter()
+                    .map(|(fn_name, signature)| (*fn_name, signature.into())),
+            );
+        }
+        Ok(())
+    }
+
+    /// Declares all functions and packages of the extensions.
+    pub fn extend(&mut self, extensions: &CelExtensions) -> Result<(), CelError> {
+        for (name, signature, _) in extensions.functions.iter() {
+            self.add_function(name, signature)?;
+        }
+        for (name, package) in extensions.packages.iter() {
+            self.add_package(name, package)?;
+        }
+        Ok(())
+    }
+
+    /// Removes a variable previously added via [add_variable](Self::add_variable).
+    pub fn remove_variable(&mut self, name: &str) {
+        if let Some(TypeItem::Value(_)) = self.idents.get(name) {
+            self.idents.remove(name);
+        }
+    }
+
+    fn lookup_ident(&self, name: &str) -> Result<&TypeItem, CelError> {
+        self.idents
+            .get(name)
+            .ok_or_else(|| CelError::UnknownIdent(name.to_string()))
+    }
+}
+
+impl Default for CelTypeContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CelExpression {
+    /// Infers the type the expression evaluates to without evaluating it.
+    /// Fails with the error evaluation would produce when the expression
+    /// can't succeed for any values of the declared types.
+    pub fn check(&self, ctx: &CelTypeContext) -> Result<CelTypeDecl, CelError> {
+        match check_expression(&self.expr, ctx)
+            .map_err(|e| e.locate(&self.source, CelError::TypeError))?
+        {
+            Checked::Value(decl) => Ok(decl),
+            _ => Err(CelError::Unexpected(
+                "check didn't resolve to a value".to_string(),
+            )),
+        }
+    }
+
+    /// Like [check](Self::check) but also fails if the expression is known to
+    /// evaluate to a type other than `expected`.
+    pub fn check_expecting(
+        &self,
+        ctx: &CelTypeContext,
+        expected: CelType,
+    ) -> Result<CelTypeDecl, CelError> {
+        let decl = self.check(ctx)?;
+        match decl.cel_type() {
+            Some(found) if found != expected => {
+                let mut e = CelError::BadType(expected, found);
+                if let Some(span) = self.expr.span() {
+                    e = e.at_span(span);
+                }
+                Err(e.locate(&self.source, CelError::TypeError))
+            }
+            _ => Ok(decl),
+        }
+    }
+}
+
+enum Checked<'a> {
+    Value(CelTypeDecl),
+    Function(String, &'a Signature),
+    Package(&'a HashMap<&'static str, Signature>),
+    MemberFn(String, &'a Signature),
+}
+
+impl Checked<'_> {
+    fn try_into_value(self) -> Result<CelTypeDecl, CelError> {
+        if let Checked::Value(decl) = self {
+            Ok(decl)
+        } else {
+            Err(CelError::Unexpected("Couldn't unwrap value".to_string()))
+        }
+    }
+}
+
+fn check_expression<'a>(
+    expr: &Expression,
+    ctx: &'a CelTypeContext,
+) -> Result<Checked<'a>, CelError> {
+    use Expression::*;
+    match expr {
+        Spanned(span, expr) => check_expression(expr, ctx).map_err(|e| e.at_span(*span)),
+        Ternary(cond, left, right) => {
+            let cond = check_expression(cond, ctx)?.try_into_value()?;
+            if !cond.is_assignable_to(CelType::Bool) {
+                return Err(CelError::BadType(
+                    CelType::Bool,
+                    cond.cel_type().expect("Dyn is always assignable"),
+                ));
+            }
+            let left = check_expression(left, ctx)?.try_into_value()?;
+            let right = check_expression(right, ctx)?.try_into_value()?;
+            if left == right {
+                Ok(Checked::Value(left))
+            } else {
+                Ok(Checked::Value(CelTypeDecl::Dyn))
+            }
+        }
+        Member(expr, member) => {
+            let target = check_expression(expr, ctx)?;
+            check_member(target, member, ctx)
+        }
+        Map(entries) => {
+            let mut fields = HashMap::new();
+            let mut all_attributes = true;
+            for (k, v) in entries {
+                let key = check_expression(k, ctx)?.try_into_value()?;
+                let value = check_expression(v, ctx)?.try_into_value()?;
+                match key.cel_type() {
+                    None | Some(CelType::Int | CelType::UInt | CelType::Bool) => {
+                        all_attributes = false
+                    }
+                    Some(CelType::String) => {
+                        if let Literal(ast::Literal::String(name)) = k {
+                            fields.insert(name.to_string(), value);
+                        } else {
+                            all_attributes = false;
+                        }
+                    }
+                    _ => {
+                        return Err(CelError::Unexpected(
+                            "Expression didn't resolve to a valid key".to_string(),
+                        ))
+                    }
+                }
+            }
+            if all_attributes {
+                Ok(Checked::Value(CelTypeDecl::Struct(fields)))
+            } else {
+                Ok(Checked::Value(CelTypeDecl::Type(CelType::Map)))
+            }
+        }
+        Ident(name) => match ctx.lookup_ident(name)? {
+            TypeItem::Value(decl) => Ok(Checked::Value(decl.clone())),
+            TypeItem::Function(sig) => Ok(Checked::Function(name.to_string(), sig)),
+            TypeItem::Package(p) => Ok(Checked::Package(p)),
+        },
+        Literal(val) => Ok(Checked::Value(CelTypeDecl::Type(literal_type(val)))),
+        Arithmetic(op, left, right) => {
+            let left = check_expression(left, ctx)?.try_into_value()?;
+            let right = check_expression(right, ctx)?.try_into_value()?;
+            Ok(Checked::Value(check_arithmetic(*op, left, right)?))
+        }
+        Relation(op, left, right) => {
+            let left = check_expression(left, ctx)?.try_into_value()?;
+            let right = check_expression(right, ctx)?.try_into_value()?;
+            Ok(Checked::Value(check_relation(*op, left, right)?))
+        }
+        Has(target, _) => {
+            let target = check_expression(target, ctx)?.try_into_value()?;
+            if !target.is_assignable_to(CelType::Map) {
+                return Err(CelError::BadType(
+                    CelType::Map,
+                    target.cel_type().expect("Dyn is always assignable"),
+                ));
+            }
+            Ok(Checked::Value(CelType::Bool.into()))
+        }
+        ListMacro(list_macro, target, var, expr) => {
+            let target = check_expression(target, ctx)?.try_into_value()?;
+            if !target.is_assignable_to(CelType::List) && !target.is_assignable_to(CelType::Map) {
+                return Err(CelError::BadType(
+                    CelType::List,
+                    target.cel_type().expect("Dyn is always assignable"),
+                ));
+            }
+            let mut inner_ctx = ctx.clone();
+            inner_ctx.add_variable(var.to_string(), CelTypeDecl::Dyn);
+            let res = check_expression(expr, &inner_ctx)?.try_into_value()?;
+            match list_macro {
+                ast::ListMacro::All | ast::ListMacro::Exists | ast::ListMacro::Filter
+                    if !res.is_assignable_to(CelType::Bool) =>
+                {
+                    Err(CelError::BadType(
+                        CelType::Bool,
+                        res.cel_type().expect("Dyn is always assignable"),
+                    ))
+                }
+                ast::ListMacro::All | ast::ListMacro::Exists => {
+                    Ok(Checked::Value(CelType::Bool.into()))
+                }
+                ast::ListMacro::Map | ast::ListMacro::Filter => {
+                    Ok(Checked::Value(CelType::List.into()))
+                }
+            }
+        }
+        e => Err(CelError::Unexpected(format!("unimplemented {e:?}"))),
+    }
+}
+
+fn check_member<'a>(
+    target: Checked<'a>,
+    member: &ast::Member,
+    ctx: &'a CelTypeContext,
+) -> Result<Checked<'a>, CelError> {
+    use ast::Member::*;
+    match member {
+        Attribute(name) => match target {
+            Checked::Value(CelTypeDecl::Struct(fields)) => {
+                if let Some(field) = fields.get(name.as_str()) {
+                    return Ok(Checked::Value(field.clone()));
+                }
+                ctx.member_fns
+                    .get(&CelType::Map)
+                    .and_then(|fns| fns.get(name.as_str()))
+                    .map(|sig| Checked::MemberFn(name.to_string(), sig))
+                    .ok_or_else(|| CelError::UnknownAttribute(CelType::Map, name.to_string()))
+            }
+            Checked::Value(CelTypeDecl::Dyn | CelTypeDecl::Type(CelType::Map)) => {
+                Ok(Checked::Value(CelTypeDecl::Dyn))
+            }
+            Checked::Value(CelTypeDecl::Type(t)) => ctx
+                .member_fns
+                .get(&t)
+                .and_then(|fns| fns.get(name.as_str()))
+                .map(|sig| Checked::MemberFn(name.to_string(), sig))
+                .ok_or_else(|| CelError::UnknownAttribute(t, name.to_string())),
+            Checked::Package(p) => p
+                .get_key_value(name.as_str())
+                .map(|(name, sig)| Checked::Function(name.to_string(), sig))
+                .ok_or_else(|| CelError::UnknownIdent(name.to_string())),
+            _ => Err(CelError::IllegalTarget),
+        },
+        FunctionCall(exprs) => {
+            let mut args = Vec::new();
+            for e in exprs {
+                args.push(check_expression(e, ctx)?.try_into_value()?);
+            }
+            match target {
+                Checked::Function(name, sig) | Checked::MemberFn(name, sig) => {
+                    Ok(Checked::Value(sig.check_call(&name, &args)?))
+                }
+                Checked::Package(p) => {
+                    let (name, sig) = p
+                        .get_key_value(SELF_PACKAGE_NAME)
+                        .ok_or_else(|| CelError::UnknownIdent(SELF_PACKAGE_NAME.to_string()))?;
+                    Ok(Checked::Value(sig.check_call(name, &args)?))
+                }
+                Checked::Value(CelTypeDecl::Dyn) => Ok(Checked::Value(CelTypeDecl::Dyn)),
+                _ => Err(CelError::IllegalTarget),
+            }
+        }
+        e => Err(CelError::Unexpected(format!("unimplemented {e:?}"))),
+    }
+}
+
+fn check_arithmetic(
+    op: ArithmeticOp,
+    left: CelTypeDecl,
+    right: CelTypeDecl,
+) -> Result<CelTypeDecl, CelError> {
+    let symbol = match op {
+        ArithmeticOp::Add => "+",
+        ArithmeticOp::Subtract => "-",
+        ArithmeticOp::Multiply => "*",
+        ArithmeticOp::Divide => "/",
+        ArithmeticOp::Modulus => "%",
+    };
+    if op == ArithmeticOp::Add
+        && left.is_assignable_to(CelType::String)
+        && right.is_assignable_to(CelType::String)
+        && (left.cel_type().is_some() || right.cel_type().is_some())
+    {
+        return Ok(CelType::String.into());
+    }
+    if matches!(
+        op,
+        ArithmeticOp::Add | ArithmeticOp::Subtract | ArithmeticOp::Multiply
+    ) {
+        if let Some(t) = unify_numeric(&left, &right) {
+            return Ok(t);
+        }
+    }
+    if let Some(t) = temporal_arithmetic(op, &left, &right) {
+        return Ok(t);
+    }
+    Err(CelError::NoMatchingOverload(format!(
+        "Cannot apply '{symbol}' to {left} and {right}"
+    )))
+}
+
+fn check_relation(
+    op: RelationOp,
+    left: CelTypeDecl,
+    right: CelTypeDecl,
+) -> Result<CelTypeDecl, CelError> {
+    let symbol = match op {
+        RelationOp::LessThan => "<",
+        RelationOp::LessThanEq => "<=",
+        RelationOp::GreaterThan => ">",
+        RelationOp::GreaterThanEq => ">=",
+        RelationOp::Equals => "==",
+        RelationOp::NotEquals => "!=",
+        RelationOp::In => "in",
+    };
+    if op != RelationOp::In
+        && (unify_numeric(&left, &right).is_some() || unify_temporal(&left, &right))
+    {
+        return Ok(CelTypeDecl::Type(CelType::Bool));
+    }
+    Err(CelError::NoMatchingOverload(format!(
+        "Cannot apply '{symbol}' to {left} and {right}"
+    )))
+}
+
+fn unify_numeric(left: &CelTypeDecl, right: &CelTypeDecl) -> Option<CelTypeDecl> {
+    let is_numeric = |t: CelType| {
+        matches!(
+            t,
+            CelType::UInt | CelType::Int | CelType::Double | CelType::Decimal
+        )
+    };
+    match (left.cel_type(), right.cel_type()) {
+        (Some(l), Some(r)) if l == r && is_numeric(l) => Some(CelTypeDecl::Type(l)),
+        (Some(t), None) | (None, Some(t)) if is_numeric(t) => Some(CelTypeDecl::Type(t)),
+        (None, None) => Some(CelTypeDecl::Dyn),
+        _ => None,
+    }
+}
+
+fn is_temporal(t: CelType) -> bool {
+    matches!(t, CelType::Date | CelType::Timestamp | CelType::Duration)
+}
+
+fn unify_temporal(left: &CelTypeDecl, right: &CelTypeDecl) -> bool {
+    match (left.cel_type(), right.cel_type()) {
+        (Some(l), Some(r)) => l == r && is_temporal(l),
+        (Some(t), None) | (None, Some(t)) => is_temporal(t),
+        (None, None) => true,
+    }
+}
+
+fn temporal_arithmetic(
+    op: ArithmeticOp,
+    left: &CelTypeDecl,
+    right: &CelTypeDecl,
+) -> Option<CelTypeDecl> {
+    use CelType::*;
+    let res = match (op, left.cel_type(), right.cel_type()) {
+        (ArithmeticOp::Add, Some(Timestamp), Some(Duration))
+        | (ArithmeticOp::Add, Some(Duration), Some(Timestamp))
+        | (ArithmeticOp::Subtract, Some(Timestamp), Some(Duration)) => Timestamp,
+        (ArithmeticOp::Add | ArithmeticOp::Subtract, Some(Duration), Some(Duration))
+        | (ArithmeticOp::Subtract, Some(Timestamp), Some(Timestamp)) => Duration,
+        (ArithmeticOp::Subtract, Some(Date), Some(Date)) => Int,
+        (ArithmeticOp::Add | ArithmeticOp::Subtract, Some(t), None)
+        | (ArithmeticOp::Add | ArithmeticOp::Subtract, None, Some(t))
+            if is_temporal(t) =>
+        {
+            return Some(CelTypeDecl::Dyn)
+        }
+        _ => return None,
+    };
+    Some(res.into())
+}
+
+fn literal_type(literal: &Literal) -> CelType {
+    match literal {
+        Literal::Int(_) => CelType::Int,
+        Literal::UInt(_) => CelType::UInt,
+        Literal::Double(_) => CelType::Double,
+        Literal::String(_) => CelType::String,
+        Literal::Bytes(_) => CelType::Bytes,
+        Literal::Bool(_) => CelType::Bool,
+        Literal::Null => CelType::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(expr: &str, ctx: &CelTypeContext) -> Result<CelTypeDecl, CelError> {
+        expr.parse::<CelExpression>()
+            .unwrap()
+            .check(ctx)
+            .map_err(|e| match e {
+                CelError::TypeError(_, _, e) => *e,
+                e => e,
+            })
+    }
+
+    #[test]
+    fn error_locations() {
+        let ctx = params_ctx();
+        let err = "params.amount > decimal('0')\n  || params.amount + 'x' > decimal('1')"
+            .parse::<CelExpression>()
+            .unwrap()
+            .check(&ctx)
+            .unwrap_err();
+        assert_eq!(err.sub_expression(), Some("params.amount + 'x'"));
+        assert_eq!(
+            err.location(),
+            Some(cel_parser::SourceLocation { line: 2, column: 6 })
+        );
+        assert!(matches!(err.root_cause(), CelError::NoMatchingOverload(_)));
+    }
+
+    fn params_ctx() -> CelTypeContext {
+        let mut ctx = CelTypeContext::new();
+        ctx.add_variable(
+            "params",
+            CelTypeDecl::Struct(HashMap::from([
+                ("amount".to_string(), CelType::Decimal.into()),
+                ("name".to_string(), CelType::String.into()),
+                ("meta".to_string(), CelTypeDecl::Dyn),
+            ])),
+        );
+        ctx
+    }
+
+    #[test]
+    fn literals_and_builtins() {
+        let ctx = CelTypeContext::new();
+        assert_eq!(check("1", &ctx).unwrap(), CelType::Int.into());
+        assert_eq!(check("'a'", &ctx).unwrap(), CelType::String.into());
+        assert_eq!(check("date()", &ctx).unwrap(), CelType::Date.into());
+        assert_eq!(
+            check("decimal('1')", &ctx).unwrap(),
+            CelType::Decimal.into()
+        );
+        assert_eq!(
+            check("decimal.Add(decimal('1'), decimal('2'))", &ctx).unwrap(),
+            CelType::Decimal.into()
+        );
+        assert_eq!(
+            check("timestamp('2024-01-01T00:00:00Z').format('%Y')", &ctx).unwrap(),
+            CelType::String.into()
+        );
+        assert!(check("uuid(1)", &ctx).is_err());
+    }
+
+    #[test]
+    fn params() {
+        let ctx = params_ctx();
+        assert_eq!(
+            check("params.amount + decimal('1')", &ctx).unwrap(),
+            CelType::Decimal.into()
+        );
+        assert_eq!(
+            check("params.amount > decimal('0')", &ctx).unwrap(),
+            CelType::Bool.into()
+        );
+        assert_eq!(check("params.meta.nested", &ctx).unwrap(), CelTypeDecl::Dyn);
+        assert!(matches!(
+            check("params.amount + 'x'", &ctx),
+            Err(CelError::NoMatchingOverload(_))
+        ));
+        assert!(matches!(
+            check("params.missing", &ctx),
+            Err(CelError::UnknownAttribute(CelType::Map, _))
+        ));
+        assert!(matches!(
+            check("unknown.amount", &ctx),
+            Err(CelError::UnknownIdent(_))
+        ));
+    }
+
+    #[test]
+    fn macros() {
+        let mut ctx = params_ctx();
+        ctx.add_variable("items", CelType::List);
+        assert_eq!(
+            check("has(params.name)", &ctx).unwrap(),
+            CelType::Bool.into()
+        );
+        assert_eq!(
+            check("items.exists(i, i.amount > decimal('1'))", &ctx).unwrap(),
+            CelType::Bool.into()
+        );
+        assert_eq!(
+            check("items.map(i, i.amount)", &ctx).unwrap(),
+            CelType::List.into()
+        );
+        assert_eq!(check("size(items)", &ctx).unwrap(), CelType::Int.into());
+        assert_eq!(check("items.size()", &ctx).unwrap(), CelType::Int.into());
+        assert!(matches!(
+            check("params.amount.all(x, true)", &ctx),
+            Err(CelError::BadType(CelType::List, CelType::Decimal))
+        ));
+        assert!(matches!(
+            check("items.filter(i, 1)", &ctx),
+            Err(CelError::BadType(CelType::Bool, CelType::Int))
+        ));
+    }
+
+    #[test]
+    fn string_functions() {
+        let mut ctx = params_ctx();
+        ctx.add_variable("code", CelType::String);
+        assert_eq!(
+            check("code.startsWith('A') && code.matches('^A')", &ctx).unwrap(),
+            CelType::Bool.into()
+        );
+        assert_eq!(
+            check("code.split(':').join('/') + string(1)", &ctx).unwrap(),
+            CelType::String.into()
+        );
+        assert_eq!(
+            check("code.substring(0, 2)", &ctx).unwrap(),
+            CelType::String.into()
+        );
+        assert!(matches!(
+            check("code.contains(1)", &ctx),
+            Err(CelError::NoMatchingOverload(_))
+        ));
+        assert!(matches!(
+            check("code + 1", &ctx),
+            Err(CelError::NoMatchingOverload(_))
+        ));
+    }
+
+    #[test]
+    fn date_and_duration_functions() {
+        let mut ctx = params_ctx();
+        ctx.add_variable("now", CelType::Timestamp);
+        assert_eq!(
+            check("now + duration('1h')", &ctx).unwrap(),
+            CelType::Timestamp.into()
+        );
+        assert_eq!(check("now - now", &ctx).unwrap(), CelType::Duration.into());
+        assert_eq!(
+            check("now.date('+02:00').endOfMonth()", &ctx).unwrap(),
+            CelType::Date.into()
+        );
+        assert_eq!(
+            check("date('2024-01-01') - now.date() < 30", &ctx).unwrap(),
+            CelType::Bool.into()
+        );
+        assert!(matches!(
+            check("now + 1", &ctx),
+            Err(CelError::NoMatchingOverload(_))
+        ));
+    }
+
+    #[test]
+    fn decimal_functions() {
+        let ctx = params_ctx();
+        assert_eq!(
+            check(
+                "decimal.max(decimal.round(params.amount, 2, 'HALF_EVEN'), decimal('1'))",
+                &ctx
+            )
+            .unwrap(),
+            CelType::Decimal.into()
+        );
+        assert_eq!(
+            check("decimal.cmp(params.amount, decimal('1')) > 0", &ctx).unwrap(),
+            CelType::Bool.into()
+        );
+        assert!(matches!(
+            check("decimal.div(params.amount, decimal('3'))", &ctx),
+            Err(CelError::NoMatchingOverload(_))
+        ));
+    }
+
+    #[test]
+    fn host_functions() {
+        use crate::{CelPackage, CelValue};
+
+        let mut extensions = CelExtensions::new();
+        extensions.add_function(
+            "accountCode",
+            CelSignature::new([CelType::Uuid], CelType::String),
+            |_| Ok(CelValue::from("code")),
+        );
+        let mut fx = CelPackage::default();
+        fx.add_function(
+            "rate",
+            CelSignature::new([CelType::String], CelType::Decimal),
+            |_| Ok(CelValue::Decimal(1.into())),
+        )
+        .unwrap();
+        let mut date_fns = CelPackage::default();
+        date_fns
+            .add_member_function(
+                "isBusinessDay",
+                CelSignature::new([], CelType::Bool),
+                |_, _| Ok(CelValue::Bool(true)),
+            )
+            .unwrap();
+        extensions
+            .add_package("fx", fx)
+            .add_package("date", date_fns);
+
+        let mut ctx = params_ctx();
+        ctx.extend(&extensions).unwrap();
+        assert_eq!(
+            check("fx.rate('EURUSD') > params.amount", &ctx).unwrap(),
+            CelType::Bool.into()
+        );
+        assert_eq!(
+            check("date('2024-01-01').addDays(1).isBusinessDay()", &ctx).unwrap(),
+            CelType::Bool.into()
+        );
+        assert_eq!(
+            check(
+                "accountCode(uuid('00000000-0000-0000-0000-000000000000'))",
+                &ctx
+            )
+            .unwrap(),
+            CelType::String.into()
+        );
+        assert!(matches!(
+            check("accountCode('code')", &ctx),
+            Err(CelError::NoMatchingOverload(_))
+        ));
+        assert!(matches!(
+            ctx.extend(&extensions),
+            Err(CelError::RegistrationError(_))
+        ));
+    }
+
+    #[test]
+    fn expected_type_mismatch() {
+        let ctx = params_ctx();
+        let expr = CelExpression::try_from("\n  params.amount + decimal('1')").unwrap();
+        assert_eq!(
+            expr.check_expecting(&ctx, CelType::Decimal).unwrap(),
+            CelType::Decimal.into()
+        );
+        let err = expr.check_expecting(&ctx, CelType::String).unwrap_err();
+        assert_eq!(err.sub_expression(), Some("params.amount + decimal('1')"));
+        assert_eq!(
+            err.location(),
+            Some(cel_parser::SourceLocation { line: 2, column: 3 })
+        );
+        assert!(matches!(
+            err.root_cause(),
+            CelError::BadType(CelType::String, CelType::Decimal)
+        ));
+    }
+
+    #[test]
+    fn rejected_package_is_not_declared() {
+        use crate::{CelPackage, CelValue};
+
+        let rate = |_: Vec<CelValue>| Ok(CelValue::Decimal(1.into()));
+        let mut fx = CelPackage::default();
+        fx.add_function(
+            "rate",
+            CelSignature::new([CelType::String], CelType::Decimal),
+            rate,
+        )
+        .unwrap();
+        let mut ctx = params_ctx();
+        ctx.add_package("fx", &fx).unwrap();
+
+        let mut more_fx = CelPackage::default();
+        more_fx
+            .add_function(
+                "inverse",
+                CelSignature::new([CelType::String], CelType::Decimal),
+                rate,
+            )
+            .unwrap();
+        more_fx
+            .add_function(
+                "rate",
+                CelSignature::new([CelType::String], CelType::Decimal),
+                rate,
+            )
+            .unwrap();
+        assert!(matches!(
+            ctx.add_package("fx", &more_fx),
+            Err(CelError::RegistrationError(_))
+        ));
+        assert!(check("fx.inverse('EURUSD')", &ctx).is_err());
+        assert!(matches!(
+            ctx.add_package("params", &more_fx),
+            Err(CelError::RegistrationError(_))
+        ));
+        assert!(check("params.amount", &ctx).is_ok());
+    }
+
+    #[test]
+    fn maps() {
+        let ctx = params_ctx();
+        let CelTypeDecl::Struct(fields) = check("{'name': params.name}", &ctx).unwrap() else {
+            panic!("expected struct");
+        };
+        assert_eq!(fields.get("name"), Some(&CelType::String.into()));
+    }
+}
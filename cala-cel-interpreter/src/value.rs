@@ -1,5 +1,5 @@
 use cel_parser::{ast::Literal, Expression};
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use uuid::Uuid;
 
@@ -30,6 +30,7 @@ pub enum CelValue {
     Date(NaiveDate),
     Timestamp(DateTime<Utc>),
     Uuid(Uuid),
+    Duration(Duration),
 }
 
 impl CelValue {
@@ -65,9 +66,25 @@ impl CelMap {
             .unwrap_or(CelValue::Null)
     }
 
+    pub(crate) fn lookup(&self, key: &CelKey) -> Option<&CelValue> {
+        self.inner.get(key)
+    }
+
     pub fn contains_key(&self, key: impl Into<CelKey>) -> bool {
         self.inner.contains_key(&key.into())
     }
+
+    pub fn keys(&self) -> impl Iterator<Item = &CelKey> {
+        self.inner.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
 }
 
 impl Default for CelMap {
@@ -105,6 +122,18 @@ impl CelArray {
     pub fn push(&mut self, elem: impl Into<CelValue>) {
         self.inner.push(elem.into());
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CelValue> {
+        self.inner.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
 }
 
 impl Default for CelArray {
@@ -113,6 +142,20 @@ impl Default for CelArray {
     }
 }
 
+impl<T: Into<CelValue>> FromIterator<T> for CelArray {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            inner: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<CelArray> for CelValue {
+    fn from(a: CelArray) -> Self {
+        CelValue::List(Arc::from(a))
+    }
+}
+
 impl From<i64> for CelValue {
     fn from(i: i64) -> Self {
         CelValue::Int(i)
@@ -167,7 +210,7 @@ impl From<serde_json::Value> for CelValue {
                 } else if let Some(i) = n.as_i64() {
                     CelValue::Int(i)
                 } else {
-                    unimplemented!()
+                    CelValue::Double(n.as_f64().expect("number is not a float"))
                 }
             }
             String(s) => CelValue::String(Arc::from(s)),
@@ -189,6 +232,55 @@ impl From<serde_json::Value> for CelValue {
     }
 }
 
+/// Renders the value as the CEL expression that would produce it.
+impl std::fmt::Display for CelValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CelValue::Map(m) => {
+                let mut keys: Vec<_> = m.keys().collect();
+                keys.sort();
+                write!(f, "{{")?;
+                for (idx, k) in keys.into_iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", CelValue::from(k), m.inner[k])?;
+                }
+                write!(f, "}}")
+            }
+            CelValue::List(l) => {
+                write!(f, "[")?;
+                for (idx, v) in l.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                write!(f, "]")
+            }
+            CelValue::Int(i) => write!(f, "{i}"),
+            CelValue::UInt(u) => write!(f, "{u}u"),
+            CelValue::Double(d) => write!(f, "{d:?}"),
+            CelValue::String(s) => write!(f, "{s:?}"),
+            CelValue::Bytes(b) => write!(f, "b{:?}", String::from_utf8_lossy(b)),
+            CelValue::Bool(b) => write!(f, "{b}"),
+            CelValue::Null => write!(f, "null"),
+            CelValue::Decimal(d) => write!(f, "decimal('{d}')"),
+            CelValue::Date(d) => write!(f, "date('{d}')"),
+            CelValue::Timestamp(t) => write!(
+                f,
+                "timestamp('{}')",
+                t.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+            ),
+            CelValue::Uuid(u) => write!(f, "uuid('{u}')"),
+            CelValue::Duration(d) => match d.num_nanoseconds() {
+                Some(ns) if ns % 1_000_000_000 != 0 => write!(f, "duration('{ns}ns')"),
+                _ => write!(f, "duration('{}s')", d.num_seconds()),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum CelKey {
     Int(i64),
@@ -215,6 +307,17 @@ impl From<&Arc<String>> for CelKey {
     }
 }
 
+impl From<&CelKey> for CelValue {
+    fn from(k: &CelKey) -> Self {
+        match k {
+            CelKey::Int(i) => CelValue::Int(*i),
+            CelKey::UInt(u) => CelValue::UInt(*u),
+            CelKey::Bool(b) => CelValue::Bool(*b),
+            CelKey::String(s) => CelValue::String(s.clone()),
+        }
+    }
+}
+
 impl From<&CelValue> for CelType {
     fn from(v: &CelValue) -> Self {
         match v {
@@ -232,6 +335,7 @@ impl From<&CelValue> for CelType {
             CelValue::Date(_) => CelType::Date,
             CelValue::Uuid(_) => CelType::Uuid,
             CelValue::Timestamp(_) => CelType::Timestamp,
+            CelValue::Duration(_) => CelType::Duration,
         }
     }
 }
@@ -257,6 +361,12 @@ impl From<DateTime<Utc>> for CelValue {
     }
 }
 
+impl From<Duration> for CelValue {
+    fn from(d: Duration) -> Self {
+        CelValue::Duration(d)
+    }
+}
+
 impl TryFrom<&CelValue> for Arc<String> {
     type Error = CelError;
 
@@ -297,6 +407,22 @@ impl TryFrom<CelResult<'_>> for bool {
     }
 }
 
+impl TryFrom<CelResult<'_>> for Vec<CelValue> {
+    type Error = ResultCoercionError;
+
+    fn try_from(CelResult { expr, val }: CelResult) -> Result<Self, Self::Error> {
+        if let CelValue::List(list) = val {
+            Ok(list.inner.clone())
+        } else {
+            Err(ResultCoercionError::BadCoreTypeCoercion(
+                format!("{expr:?}"),
+                CelType::from(&val),
+                CelType::List,
+            ))
+        }
+    }
+}
+
 impl TryFrom<CelResult<'_>> for NaiveDate {
     type Error = ResultCoercionError;
 
@@ -329,6 +455,22 @@ impl TryFrom<CelResult<'_>> for DateTime<Utc> {
     }
 }
 
+impl TryFrom<CelResult<'_>> for Duration {
+    type Error = ResultCoercionError;
+
+    fn try_from(CelResult { expr, val }: CelResult) -> Result<Self, Self::Error> {
+        if let CelValue::Duration(d) = val {
+            Ok(d)
+        } else {
+            Err(ResultCoercionError::BadCoreTypeCoercion(
+                format!("{expr:?}"),
+                CelType::from(&val),
+                CelType::Duration,
+            ))
+        }
+    }
+}
+
 impl TryFrom<CelResult<'_>> for Uuid {
     type Error = ResultCoercionError;
 
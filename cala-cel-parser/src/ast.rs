@@ -41,7 +41,89 @@ pub enum LeftRightOp {
     Arithmetic(ArithmeticOp),
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ListMacro {
+    All,
+    Exists,
+    Map,
+    Filter,
+}
+
+impl ListMacro {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ListMacro::All => "all",
+            ListMacro::Exists => "exists",
+            ListMacro::Map => "map",
+            ListMacro::Filter => "filter",
+        }
+    }
+}
+
+/// Byte offsets of a node within the parsed source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// The text of the source covered by this span.
+    pub fn slice<'a>(&self, source: &'a str) -> &'a str {
+        source.get(self.start..self.end).unwrap_or(source)
+    }
+
+    pub fn location(&self, source: &str) -> SourceLocation {
+        SourceLocation::from_offset(source, self.start)
+    }
+}
+
+/// 1-based line and column (in characters) within the parsed source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SourceLocation {
+    pub fn from_offset(source: &str, offset: usize) -> Self {
+        let offset = offset.min(source.len());
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Error raised by the grammar for syntactically valid input that is not a valid expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub span: Span,
+    pub message: &'static str,
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Spans are source metadata. [Expression::Spanned] nodes are transparent
+/// to equality and debug output so that structurally identical
+/// expressions compare equal regardless of where they were written.
+#[derive(Clone)]
 pub enum Expression {
     Ternary(Box<Expression>, Box<Expression>, Box<Expression>),
     Relation(RelationOp, Box<Expression>, Box<Expression>),
@@ -56,9 +138,46 @@ pub enum Expression {
 
     Literal(Literal),
     Ident(Arc<String>),
+
+    /// `has(target.attribute)`
+    Has(Box<Expression>, Arc<String>),
+    /// `target.<macro>(var, expression)`
+    ListMacro(ListMacro, Box<Expression>, Arc<String>, Box<Expression>),
+
+    /// Records the position of the wrapped expression in the source.
+    Spanned(Span, Box<Expression>),
 }
 
 impl Expression {
+    pub(crate) fn spanned(start: usize, end: usize, expr: Expression) -> Self {
+        match expr {
+            Expression::Spanned(_, inner) => Expression::Spanned(Span::new(start, end), inner),
+            expr => Expression::Spanned(Span::new(start, end), Box::new(expr)),
+        }
+    }
+
+    /// The expression with any span information at the top level removed.
+    pub fn unspanned(&self) -> &Expression {
+        match self {
+            Expression::Spanned(_, inner) => inner.unspanned(),
+            expr => expr,
+        }
+    }
+
+    pub(crate) fn into_unspanned(self) -> Expression {
+        match self {
+            Expression::Spanned(_, inner) => inner.into_unspanned(),
+            expr => expr,
+        }
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Expression::Spanned(span, _) => Some(*span),
+            _ => None,
+        }
+    }
+
     pub(crate) fn from_op(op: LeftRightOp, left: Box<Expression>, right: Box<Expression>) -> Self {
         use LeftRightOp::*;
         match op {
@@ -76,6 +195,116 @@ impl Expression {
             Arithmetic(op) => Expression::Arithmetic(op, left, right),
         }
     }
+
+    pub(crate) fn global_call(
+        identifier: Arc<String>,
+        mut arguments: Vec<Expression>,
+    ) -> Result<Self, &'static str> {
+        if identifier.as_str() == "has" {
+            return match arguments.pop().map(Expression::into_unspanned) {
+                Some(Expression::Member(target, member)) if arguments.is_empty() => match *member {
+                    Member::Attribute(name) => Ok(Expression::Has(target, name)),
+                    _ => Err("has() requires a field selection argument"),
+                },
+                _ => Err("has() requires a field selection argument"),
+            };
+        }
+        Ok(Expression::Member(
+            Box::new(Expression::Ident(identifier)),
+            Box::new(Member::FunctionCall(arguments)),
+        ))
+    }
+
+    pub(crate) fn member_call(
+        target: Expression,
+        identifier: Arc<String>,
+        mut arguments: Vec<Expression>,
+    ) -> Result<Self, &'static str> {
+        let list_macro = match identifier.as_str() {
+            "all" => Some(ListMacro::All),
+            "exists" => Some(ListMacro::Exists),
+            "map" => Some(ListMacro::Map),
+            "filter" => Some(ListMacro::Filter),
+            _ => None,
+        };
+        if let Some(list_macro) = list_macro {
+            if arguments.len() == 2 {
+                let expression = arguments.pop().expect("2 arguments");
+                return match arguments.pop().map(Expression::into_unspanned) {
+                    Some(Expression::Ident(var)) => Ok(Expression::ListMacro(
+                        list_macro,
+                        Box::new(target),
+                        var,
+                        Box::new(expression),
+                    )),
+                    _ => Err("macro variable must be a simple identifier"),
+                };
+            }
+        }
+        let inner = Expression::Member(Box::new(target), Box::new(Member::Attribute(identifier)));
+        Ok(Expression::Member(
+            Box::new(inner),
+            Box::new(Member::FunctionCall(arguments)),
+        ))
+    }
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        use Expression::*;
+        match (self.unspanned(), other.unspanned()) {
+            (Ternary(a, b, c), Ternary(x, y, z)) => a == x && b == y && c == z,
+            (Relation(op, a, b), Relation(op2, x, y)) => op == op2 && a == x && b == y,
+            (Arithmetic(op, a, b), Arithmetic(op2, x, y)) => op == op2 && a == x && b == y,
+            (Unary(op, a), Unary(op2, x)) => op == op2 && a == x,
+            (Member(a, m), Member(x, n)) => a == x && m == n,
+            (List(a), List(x)) => a == x,
+            (Map(a), Map(x)) => a == x,
+            (Struct(a, b), Struct(x, y)) => a == x && b == y,
+            (Literal(a), Literal(x)) => a == x,
+            (Ident(a), Ident(x)) => a == x,
+            (Has(a, b), Has(x, y)) => a == x && b == y,
+            (ListMacro(m, a, b, c), ListMacro(n, x, y, z)) => m == n && a == x && b == y && c == z,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Debug for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Expression::*;
+        match self {
+            Ternary(a, b, c) => f.debug_tuple("Ternary").field(a).field(b).field(c).finish(),
+            Relation(op, a, b) => f
+                .debug_tuple("Relation")
+                .field(op)
+                .field(a)
+                .field(b)
+                .finish(),
+            Arithmetic(op, a, b) => f
+                .debug_tuple("Arithmetic")
+                .field(op)
+                .field(a)
+                .field(b)
+                .finish(),
+            Unary(op, a) => f.debug_tuple("Unary").field(op).field(a).finish(),
+            Member(a, m) => f.debug_tuple("Member").field(a).field(m).finish(),
+            List(a) => f.debug_tuple("List").field(a).finish(),
+            Map(a) => f.debug_tuple("Map").field(a).finish(),
+            Struct(a, b) => f.debug_tuple("Struct").field(a).field(b).finish(),
+            Literal(a) => f.debug_tuple("Literal").field(a).finish(),
+            Ident(a) => f.debug_tuple("Ident").field(a).finish(),
+            Has(a, b) => f.debug_tuple("Has").field(a).field(b).finish(),
+            ListMacro(m, a, b, c) => f
+                .debug_tuple("ListMacro")
+                .field(m)
+                .field(a)
+                .field(b)
+                .field(c)
+                .finish(),
+            Spanned(_, inner) => inner.fmt(f),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -160,6 +389,29 @@ mod tests {
         )
     }
 
+    #[test]
+    fn has_macro() {
+        assert_parse_eq(
+            "has(a.b)",
+            Has(Ident("a".to_string().into()).into(), "b".to_string().into()),
+        );
+        assert!(ExpressionParser::new().parse("has(a)").is_err());
+    }
+
+    #[test]
+    fn list_macros() {
+        assert_parse_eq(
+            "a.exists(x, x)",
+            ListMacro(
+                crate::ListMacro::Exists,
+                Ident("a".to_string().into()).into(),
+                "x".to_string().into(),
+                Ident("x".to_string().into()).into(),
+            ),
+        );
+        assert!(ExpressionParser::new().parse("a.all(1, x)").is_err());
+    }
+
     #[test]
     fn nested_attributes() {
         assert_parse_eq(
@@ -174,4 +426,26 @@ mod tests {
             ),
         )
     }
+
+    #[test]
+    fn spans() {
+        let source = "1 +\n  params.amount";
+        let expr = parse(source);
+        assert_eq!(expr.span(), Some(crate::Span::new(0, source.len())));
+        let Arithmetic(_, _, right) = expr.unspanned() else {
+            panic!("expected arithmetic");
+        };
+        let span = right.span().unwrap();
+        assert_eq!(span.slice(source), "params.amount");
+        assert_eq!(
+            span.location(source),
+            crate::SourceLocation { line: 2, column: 3 }
+        );
+
+        let err = ExpressionParser::new().parse("a.all(1, x)").unwrap_err();
+        assert!(matches!(
+            err,
+            lalrpop_util::ParseError::User { error } if error.span == crate::Span::new(0, 11)
+        ));
+    }
 }
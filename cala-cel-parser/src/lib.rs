@@ -6,5 +6,6 @@ use lalrpop_util::lalrpop_mod;
 pub mod ast;
 
 pub use ast::*;
+pub use lalrpop_util;
 
 lalrpop_mod!(#[allow(clippy::all)] pub parser, "/cel.rs");
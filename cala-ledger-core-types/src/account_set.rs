@@ -26,6 +26,16 @@ pub struct AccountSetMemberByExternalId {
     pub id: AccountSetMemberId,
     pub external_id: Option<String>,
 }
+/// A node reached while walking an account set hierarchy.
+/// `depth` is 1 for direct members (or direct parents) and `path` holds the
+/// intermediate account sets between the starting point and this node.
+#[derive(Clone, Debug)]
+pub struct AccountSetHierarchyNode {
+    pub id: AccountSetMemberId,
+    pub depth: u32,
+    pub path: Vec<AccountSetId>,
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "type", content = "id")]
 pub enum AccountSetMemberId {
@@ -24,8 +24,15 @@ impl Clone for OutboxEvent {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, strum::EnumDiscriminants)]
 #[serde(tag = "type", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+#[strum_discriminants(
+    name(OutboxEventPayloadType),
+    derive(Hash, strum::Display, strum::EnumString, Serialize, Deserialize),
+    strum(serialize_all = "snake_case"),
+    serde(rename_all = "snake_case")
+)]
 pub enum OutboxEventPayload {
     Empty,
     AccountCreated {
@@ -46,6 +53,10 @@ pub enum OutboxEventPayload {
         account_set: AccountSetValues,
         fields: Vec<String>,
     },
+    AccountSetArchived {
+        source: DataSource,
+        account_set: AccountSetValues,
+    },
     AccountSetMemberCreated {
         source: DataSource,
         account_set_id: AccountSetId,
@@ -56,6 +67,16 @@ pub enum OutboxEventPayload {
         account_set_id: AccountSetId,
         member_id: AccountSetMemberId,
     },
+    AccountSetMembersCreated {
+        source: DataSource,
+        account_set_id: AccountSetId,
+        member_ids: Vec<AccountSetMemberId>,
+    },
+    AccountSetMembersRemoved {
+        source: DataSource,
+        account_set_id: AccountSetId,
+        member_ids: Vec<AccountSetMemberId>,
+    },
     JournalCreated {
         source: DataSource,
         journal: JournalValues,
@@ -73,6 +94,11 @@ pub enum OutboxEventPayload {
         source: DataSource,
         transaction: TransactionValues,
     },
+    TransactionAnnotated {
+        source: DataSource,
+        transaction: TransactionValues,
+        annotation: TransactionAnnotation,
+    },
     EntryCreated {
         source: DataSource,
         entry: EntryValues,
@@ -87,6 +113,132 @@ pub enum OutboxEventPayload {
     },
 }
 
+impl OutboxEventPayload {
+    pub fn payload_type(&self) -> OutboxEventPayloadType {
+        OutboxEventPayloadType::from(self)
+    }
+
+    pub fn source(&self) -> Option<DataSource> {
+        use OutboxEventPayload::*;
+        match self {
+            Empty => None,
+            AccountCreated { source, .. }
+            | AccountUpdated { source, .. }
+            | AccountSetCreated { source, .. }
+            | AccountSetUpdated { source, .. }
+            | AccountSetArchived { source, .. }
+            | AccountSetMemberCreated { source, .. }
+            | AccountSetMemberRemoved { source, .. }
+            | AccountSetMembersCreated { source, .. }
+            | AccountSetMembersRemoved { source, .. }
+            | JournalCreated { source, .. }
+            | JournalUpdated { source, .. }
+            | TxTemplateCreated { source, .. }
+            | TransactionCreated { source, .. }
+            | TransactionAnnotated { source, .. }
+            | EntryCreated { source, .. }
+            | BalanceCreated { source, .. }
+            | BalanceUpdated { source, .. } => Some(*source),
+        }
+    }
+
+    /// The journal the event belongs to - if the payload carries one.
+    pub fn journal_id(&self) -> Option<JournalId> {
+        use OutboxEventPayload::*;
+        match self {
+            AccountSetCreated { account_set, .. }
+            | AccountSetUpdated { account_set, .. }
+            | AccountSetArchived { account_set, .. } => Some(account_set.journal_id),
+            JournalCreated { journal, .. } | JournalUpdated { journal, .. } => Some(journal.id),
+            TransactionCreated { transaction, .. } | TransactionAnnotated { transaction, .. } => {
+                Some(transaction.journal_id)
+            }
+            EntryCreated { entry, .. } => Some(entry.journal_id),
+            BalanceCreated { balance, .. } | BalanceUpdated { balance, .. } => {
+                Some(balance.journal_id)
+            }
+            _ => None,
+        }
+    }
+
+    /// The accounts the payload refers to directly.
+    /// Transactions only reference accounts via their entries.
+    pub fn account_ids(&self) -> Vec<AccountId> {
+        use OutboxEventPayload::*;
+        let member_account = |member_id: &AccountSetMemberId| match member_id {
+            AccountSetMemberId::Account(id) => Some(*id),
+            AccountSetMemberId::AccountSet(_) => None,
+        };
+        match self {
+            AccountCreated { account, .. } | AccountUpdated { account, .. } => vec![account.id],
+            AccountSetMemberCreated { member_id, .. }
+            | AccountSetMemberRemoved { member_id, .. } => {
+                member_account(member_id).into_iter().collect()
+            }
+            AccountSetMembersCreated { member_ids, .. }
+            | AccountSetMembersRemoved { member_ids, .. } => {
+                member_ids.iter().filter_map(member_account).collect()
+            }
+            EntryCreated { entry, .. } => vec![entry.account_id],
+            BalanceCreated { balance, .. } | BalanceUpdated { balance, .. } => {
+                vec![balance.account_id]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Restricts the events delivered to an outbox subscriber.
+/// Every non-empty criterion must match while any of the values listed
+/// within a criterion may match. Events that don't carry the attribute a
+/// criterion refers to (eg. a journal id) are excluded by it.
+/// Delivered events keep their original sequence so resuming works as usual.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutboxEventFilter {
+    pub payload_types: Vec<OutboxEventPayloadType>,
+    pub journal_ids: Vec<JournalId>,
+    pub account_ids: Vec<AccountId>,
+    pub data_source_ids: Vec<DataSourceId>,
+}
+
+impl OutboxEventFilter {
+    pub fn is_empty(&self) -> bool {
+        self.payload_types.is_empty()
+            && self.journal_ids.is_empty()
+            && self.account_ids.is_empty()
+            && self.data_source_ids.is_empty()
+    }
+
+    pub fn matches(&self, payload: &OutboxEventPayload) -> bool {
+        (self.payload_types.is_empty() || self.payload_types.contains(&payload.payload_type()))
+            && (self.journal_ids.is_empty()
+                || payload
+                    .journal_id()
+                    .is_some_and(|id| self.journal_ids.contains(&id)))
+            && (self.account_ids.is_empty()
+                || payload
+                    .account_ids()
+                    .iter()
+                    .any(|id| self.account_ids.contains(id)))
+            && (self.data_source_ids.is_empty()
+                || payload
+                    .source()
+                    .is_some_and(|source| self.data_source_ids.contains(&source.into())))
+    }
+}
+
+/// A named consumer whose acknowledged position is persisted by the ledger.
+/// `lag` is the number of events recorded after `last_acked_sequence`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxConsumer {
+    pub name: String,
+    pub last_acked_sequence: EventSequence,
+    pub lag: u64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub modified_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(
     sqlx::Type, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Clone, Serialize, Deserialize,
 )]
@@ -128,3 +280,66 @@ impl std::fmt::Display for EventSequence {
         write!(f, "{}", self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance_updated(journal_id: JournalId, account_id: AccountId) -> OutboxEventPayload {
+        let now = chrono::Utc::now();
+        let entry_id = EntryId::new();
+        let amount = || BalanceAmount {
+            dr_balance: rust_decimal::Decimal::ZERO,
+            cr_balance: rust_decimal::Decimal::ZERO,
+            entry_id,
+            modified_at: now,
+        };
+        OutboxEventPayload::BalanceUpdated {
+            source: DataSource::Local,
+            balance: BalanceSnapshot {
+                journal_id,
+                account_id,
+                currency: "USD".parse().unwrap(),
+                version: 1,
+                created_at: now,
+                modified_at: now,
+                entry_id,
+                settled: amount(),
+                pending: amount(),
+                encumbrance: amount(),
+            },
+        }
+    }
+
+    #[test]
+    fn filter_matches() {
+        let journal_id = JournalId::new();
+        let account_id = AccountId::new();
+        let payload = balance_updated(journal_id, account_id);
+        assert_eq!(payload.payload_type().to_string(), "balance_updated");
+
+        assert!(OutboxEventFilter::default().matches(&payload));
+        let filter = OutboxEventFilter {
+            payload_types: vec![
+                OutboxEventPayloadType::BalanceCreated,
+                OutboxEventPayloadType::BalanceUpdated,
+            ],
+            journal_ids: vec![journal_id],
+            account_ids: vec![AccountId::new(), account_id],
+            data_source_ids: vec![DataSource::Local.into()],
+        };
+        assert!(filter.matches(&payload));
+
+        let filter = OutboxEventFilter {
+            payload_types: vec!["transaction_created".parse().unwrap()],
+            ..Default::default()
+        };
+        assert!(!filter.matches(&payload));
+        let filter = OutboxEventFilter {
+            journal_ids: vec![JournalId::new()],
+            ..Default::default()
+        };
+        assert!(!filter.matches(&payload));
+        assert!(!filter.matches(&OutboxEventPayload::Empty));
+    }
+}
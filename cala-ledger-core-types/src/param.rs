@@ -1,4 +1,5 @@
-use cel_interpreter::{CelExpression, CelType, CelValue};
+use cel_interpreter::{CelExpression, CelType, CelTypeDecl, CelValue};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -7,6 +8,68 @@ pub struct ParamDefinition {
     pub r#type: ParamDataType,
     pub default: Option<CelExpression>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub constraints: ParamConstraints,
+}
+
+/// Restrictions a param value must satisfy in addition to matching its type.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParamConstraints {
+    /// The param must be supplied explicitly or have a default.
+    pub required: bool,
+    /// When non-empty the value must equal one of the evaluated expressions.
+    pub allowed_values: Vec<CelExpression>,
+    /// Inclusive lower bound for `Integer` and `Decimal` params.
+    pub min: Option<Decimal>,
+    /// Inclusive upper bound for `Integer` and `Decimal` params.
+    pub max: Option<Decimal>,
+    /// Maximum number of fractional digits for `Decimal` params.
+    pub scale: Option<u32>,
+    /// Pattern `String` params must match.
+    pub regex: Option<ParamRegex>,
+    /// Must evaluate to `true` once all params are bound to `params`.
+    pub predicate: Option<CelExpression>,
+}
+
+/// A pattern compiled once when the definition is built or loaded.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String")]
+#[serde(into = "String")]
+pub struct ParamRegex(regex::Regex);
+
+impl ParamRegex {
+    pub fn is_match(&self, value: &str) -> bool {
+        self.0.is_match(value)
+    }
+}
+
+impl TryFrom<String> for ParamRegex {
+    type Error = regex::Error;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        pattern.parse()
+    }
+}
+
+impl std::str::FromStr for ParamRegex {
+    type Err = regex::Error;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Ok(Self(regex::Regex::new(pattern)?))
+    }
+}
+
+impl From<ParamRegex> for String {
+    fn from(regex: ParamRegex) -> Self {
+        regex.0.as_str().to_string()
+    }
+}
+
+impl std::fmt::Display for ParamRegex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.as_str())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -19,9 +82,25 @@ pub enum ParamDataType {
     Date,
     Timestamp,
     Json,
+    List,
 }
 
 impl ParamDataType {
+    /// The static type of the param within `params` when type checking expressions.
+    pub fn type_decl(&self) -> CelTypeDecl {
+        match self {
+            ParamDataType::String => CelType::String.into(),
+            ParamDataType::Integer => CelType::Int.into(),
+            ParamDataType::Decimal => CelType::Decimal.into(),
+            ParamDataType::Boolean => CelType::Bool.into(),
+            ParamDataType::Uuid => CelType::Uuid.into(),
+            ParamDataType::Date => CelType::Date.into(),
+            ParamDataType::Timestamp => CelType::Timestamp.into(),
+            ParamDataType::Json => CelTypeDecl::Dyn,
+            ParamDataType::List => CelType::List.into(),
+        }
+    }
+
     pub fn coerce_value(&self, value: CelValue) -> Result<CelValue, String> {
         use cel_interpreter::CelType::*;
         match CelType::from(&value) {
@@ -33,6 +112,7 @@ impl ParamDataType {
             Uuid if *self == ParamDataType::Uuid => Ok(value),
             Decimal if *self == ParamDataType::Decimal => Ok(value),
             Bool if *self == ParamDataType::Boolean => Ok(value),
+            List if *self == ParamDataType::List => Ok(value),
 
             // Coercions
             String if *self == ParamDataType::Uuid => {
@@ -83,6 +163,7 @@ impl TryFrom<&CelValue> for ParamDataType {
             Uuid => Ok(ParamDataType::Uuid),
             Decimal => Ok(ParamDataType::Decimal),
             Bool => Ok(ParamDataType::Boolean),
+            List => Ok(ParamDataType::List),
             _ => Err(format!("Unsupported type: {value:?}")),
         }
     }
@@ -35,6 +35,7 @@ impl From<AccountSetId> for AccountId {
     Copy,
     PartialEq,
     Eq,
+    Default,
     sqlx::Type,
     strum::Display,
     strum::EnumString,
@@ -44,15 +45,10 @@ impl From<AccountSetId> for AccountId {
 #[serde(rename_all = "snake_case")]
 pub enum DebitOrCredit {
     Debit,
+    #[default]
     Credit,
 }
 
-impl Default for DebitOrCredit {
-    fn default() -> Self {
-        Self::Credit
-    }
-}
-
 impl TryFrom<CelResult<'_>> for DebitOrCredit {
     type Error = ResultCoercionError;
 
@@ -78,25 +74,21 @@ impl From<DebitOrCredit> for CelValue {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, sqlx::Type)]
 #[sqlx(type_name = "Status", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
 pub enum Status {
+    #[default]
     Active,
     Locked,
 }
 
-impl Default for Status {
-    fn default() -> Self {
-        Self::Active
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default, sqlx::Type)]
 #[sqlx(type_name = "Layer", rename_all = "snake_case")]
 #[cfg_attr(feature = "graphql", derive(async_graphql::Enum))]
 pub enum Layer {
+    #[default]
     Settled,
     Pending,
     Encumbrance,
@@ -125,12 +117,6 @@ impl TryFrom<CelResult<'_>> for Layer {
     }
 }
 
-impl Default for Layer {
-    fn default() -> Self {
-        Self::Settled
-    }
-}
-
 impl From<Layer> for CelValue {
     fn from(l: Layer) -> Self {
         match l {
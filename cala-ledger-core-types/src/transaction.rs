@@ -17,6 +17,20 @@ pub struct TransactionValues {
     pub external_id: Option<String>,
     pub description: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Information attached to a transaction after it has been posted.
+/// Annotations never affect the entries or balances of the transaction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionAnnotation {
+    pub actor: String,
+    pub annotated_at: DateTime<Utc>,
+    /// Merged key by key into the metadata of the transaction.
+    pub metadata: Option<serde_json::Value>,
+    /// Added to the tags of the transaction, skipping the ones already present.
+    pub tags: Vec<String>,
 }
 
 mod cel {
@@ -26,6 +26,19 @@ pub struct TxTemplateEntry {
     pub currency: CelExpression,
     pub description: Option<CelExpression>,
     pub metadata: Option<CelExpression>,
+    /// When present the entry is only emitted if the expression evaluates to `true`.
+    pub condition: Option<CelExpression>,
+    /// When present one entry is emitted per element of the list.
+    pub for_each: Option<TxTemplateEntryForEach>,
+}
+
+/// Repeats a [TxTemplateEntry] for every element of `list`.
+/// While evaluating the other expressions of the entry (including its `condition`)
+/// the current element is bound to the variable named `var`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TxTemplateEntryForEach {
+    pub list: CelExpression,
+    pub var: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
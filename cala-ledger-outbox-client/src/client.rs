@@ -11,10 +11,13 @@ use cala_types::outbox::*;
 
 type ProtoClient = proto::outbox_service_client::OutboxServiceClient<tonic::transport::Channel>;
 
+#[derive(Clone)]
 pub struct CalaLedgerOutboxClient {
     _config: CalaLedgerOutboxClientConfig,
     proto_client: ProtoClient,
 }
+
+#[allow(clippy::result_large_err)]
 impl CalaLedgerOutboxClient {
     pub async fn connect(
         config: CalaLedgerOutboxClientConfig,
@@ -27,7 +30,6 @@ impl CalaLedgerOutboxClient {
         })
     }
 
-    #[instrument(name = "cala_ledger_outbox_client.subscribe", skip(self))]
     pub async fn subscribe(
         &mut self,
         after_sequence: Option<EventSequence>,
@@ -35,9 +37,109 @@ impl CalaLedgerOutboxClient {
         impl futures::Stream<Item = Result<OutboxEvent, CalaLedgerOutboxClientError>>,
         CalaLedgerOutboxClientError,
     > {
+        self.subscribe_filtered(after_sequence, OutboxEventFilter::default())
+            .await
+    }
+
+    /// Subscribes to the events matching the filter. The filter is applied by the server.
+    #[instrument(name = "cala_ledger_outbox_client.subscribe", skip(self))]
+    pub async fn subscribe_filtered(
+        &mut self,
+        after_sequence: Option<EventSequence>,
+        filter: OutboxEventFilter,
+    ) -> Result<
+        impl futures::Stream<Item = Result<OutboxEvent, CalaLedgerOutboxClientError>>,
+        CalaLedgerOutboxClientError,
+    > {
+        let filter = if filter.is_empty() {
+            None
+        } else {
+            Some(proto::EventFilter::from(filter))
+        };
+        let request = tonic::Request::new(proto::SubscribeRequest {
+            after_sequence: after_sequence.map(|s| s.into()),
+            filter,
+            consumer: None,
+        });
+        self.stream_events(request).await
+    }
+
+    /// Subscribes as a durable, named consumer. Unless `after_sequence` is given
+    /// the stream resumes after the last sequence acknowledged via [ack](Self::ack).
+    #[instrument(name = "cala_ledger_outbox_client.subscribe_as", skip(self))]
+    pub async fn subscribe_as(
+        &mut self,
+        consumer: &str,
+        after_sequence: Option<EventSequence>,
+        filter: OutboxEventFilter,
+    ) -> Result<
+        impl futures::Stream<Item = Result<OutboxEvent, CalaLedgerOutboxClientError>>,
+        CalaLedgerOutboxClientError,
+    > {
+        let filter = if filter.is_empty() {
+            None
+        } else {
+            Some(proto::EventFilter::from(filter))
+        };
         let request = tonic::Request::new(proto::SubscribeRequest {
             after_sequence: after_sequence.map(|s| s.into()),
+            filter,
+            consumer: Some(consumer.to_string()),
+        });
+        self.stream_events(request).await
+    }
+
+    /// Persists the position of the consumer on the server.
+    #[instrument(name = "cala_ledger_outbox_client.ack", skip(self))]
+    pub async fn ack(
+        &mut self,
+        consumer: &str,
+        sequence: EventSequence,
+    ) -> Result<OutboxConsumer, CalaLedgerOutboxClientError> {
+        let request = tonic::Request::new(proto::AckRequest {
+            consumer: consumer.to_string(),
+            sequence: sequence.into(),
+        });
+        let consumer = self.proto_client.ack(request).await?.into_inner();
+        OutboxConsumer::try_from(consumer)
+    }
+
+    #[instrument(name = "cala_ledger_outbox_client.get_consumer", skip(self))]
+    pub async fn get_consumer(
+        &mut self,
+        name: &str,
+    ) -> Result<OutboxConsumer, CalaLedgerOutboxClientError> {
+        let request = tonic::Request::new(proto::GetConsumerRequest {
+            name: name.to_string(),
         });
+        let consumer = self.proto_client.get_consumer(request).await?.into_inner();
+        OutboxConsumer::try_from(consumer)
+    }
+
+    #[instrument(name = "cala_ledger_outbox_client.list_consumers", skip(self))]
+    pub async fn list_consumers(
+        &mut self,
+    ) -> Result<Vec<OutboxConsumer>, CalaLedgerOutboxClientError> {
+        let request = tonic::Request::new(proto::ListConsumersRequest {});
+        let response = self
+            .proto_client
+            .list_consumers(request)
+            .await?
+            .into_inner();
+        let mut consumers = Vec::new();
+        for consumer in response.consumers {
+            consumers.push(OutboxConsumer::try_from(consumer)?);
+        }
+        Ok(consumers)
+    }
+
+    async fn stream_events(
+        &mut self,
+        request: tonic::Request<proto::SubscribeRequest>,
+    ) -> Result<
+        impl futures::Stream<Item = Result<OutboxEvent, CalaLedgerOutboxClientError>>,
+        CalaLedgerOutboxClientError,
+    > {
         let stream = self.proto_client.subscribe(request).await?.into_inner();
         Ok(stream.map(|e| {
             e.map_err(CalaLedgerOutboxClientError::from)
@@ -27,6 +27,26 @@ impl TryFrom<proto::CalaLedgerEvent> for OutboxEvent {
     }
 }
 
+impl TryFrom<proto::Consumer> for OutboxConsumer {
+    type Error = CalaLedgerOutboxClientError;
+
+    fn try_from(consumer: proto::Consumer) -> Result<Self, Self::Error> {
+        Ok(OutboxConsumer {
+            name: consumer.name,
+            last_acked_sequence: EventSequence::from(consumer.last_acked_sequence),
+            lag: consumer.lag,
+            created_at: consumer
+                .created_at
+                .ok_or(CalaLedgerOutboxClientError::MissingField)?
+                .into(),
+            modified_at: consumer
+                .modified_at
+                .ok_or(CalaLedgerOutboxClientError::MissingField)?
+                .into(),
+        })
+    }
+}
+
 impl TryFrom<proto::cala_ledger_event::Payload> for OutboxEventPayload {
     type Error = CalaLedgerOutboxClientError;
 
@@ -73,6 +93,15 @@ impl TryFrom<proto::cala_ledger_event::Payload> for OutboxEventPayload {
                 )?,
                 fields,
             },
+            proto::cala_ledger_event::Payload::AccountSetArchived(proto::AccountSetArchived {
+                data_source_id,
+                account_set,
+            }) => AccountSetArchived {
+                source: data_source_id.parse()?,
+                account_set: AccountSetValues::try_from(
+                    account_set.ok_or(CalaLedgerOutboxClientError::MissingField)?,
+                )?,
+            },
             proto::cala_ledger_event::Payload::AccountSetMemberCreated(
                 proto::AccountSetMemberCreated {
                     data_source_id,
@@ -165,6 +194,21 @@ impl TryFrom<proto::cala_ledger_event::Payload> for OutboxEventPayload {
                     transaction.ok_or(CalaLedgerOutboxClientError::MissingField)?,
                 )?,
             },
+            proto::cala_ledger_event::Payload::TransactionAnnotated(
+                proto::TransactionAnnotated {
+                    data_source_id,
+                    transaction,
+                    annotation,
+                },
+            ) => TransactionAnnotated {
+                source: data_source_id.parse()?,
+                transaction: TransactionValues::try_from(
+                    transaction.ok_or(CalaLedgerOutboxClientError::MissingField)?,
+                )?,
+                annotation: TransactionAnnotation::try_from(
+                    annotation.ok_or(CalaLedgerOutboxClientError::MissingField)?,
+                )?,
+            },
             proto::cala_ledger_event::Payload::EntryCreated(proto::EntryCreated {
                 data_source_id,
                 entry,
@@ -192,6 +236,30 @@ impl TryFrom<proto::cala_ledger_event::Payload> for OutboxEventPayload {
                     balance.ok_or(CalaLedgerOutboxClientError::MissingField)?,
                 )?,
             },
+            proto::cala_ledger_event::Payload::AccountSetMembersCreated(
+                proto::AccountSetMembersCreated {
+                    data_source_id,
+                    account_set_id,
+                    member_account_ids,
+                    member_account_set_ids,
+                },
+            ) => AccountSetMembersCreated {
+                source: data_source_id.parse()?,
+                account_set_id: account_set_id.parse()?,
+                member_ids: join_member_ids(member_account_ids, member_account_set_ids)?,
+            },
+            proto::cala_ledger_event::Payload::AccountSetMembersRemoved(
+                proto::AccountSetMembersRemoved {
+                    data_source_id,
+                    account_set_id,
+                    member_account_ids,
+                    member_account_set_ids,
+                },
+            ) => AccountSetMembersRemoved {
+                source: data_source_id.parse()?,
+                account_set_id: account_set_id.parse()?,
+                member_ids: join_member_ids(member_account_ids, member_account_set_ids)?,
+            },
 
             proto::cala_ledger_event::Payload::Empty(_) => Empty,
         };
@@ -199,6 +267,26 @@ impl TryFrom<proto::cala_ledger_event::Payload> for OutboxEventPayload {
     }
 }
 
+#[allow(clippy::result_large_err)]
+fn join_member_ids(
+    member_account_ids: Vec<String>,
+    member_account_set_ids: Vec<String>,
+) -> Result<Vec<cala_types::account_set::AccountSetMemberId>, CalaLedgerOutboxClientError> {
+    let mut member_ids =
+        Vec::with_capacity(member_account_ids.len() + member_account_set_ids.len());
+    for account_id in member_account_ids {
+        member_ids.push(cala_types::account_set::AccountSetMemberId::from(
+            account_id.parse::<AccountId>()?,
+        ));
+    }
+    for account_set_id in member_account_set_ids {
+        member_ids.push(cala_types::account_set::AccountSetMemberId::from(
+            account_set_id.parse::<AccountSetId>()?,
+        ));
+    }
+    Ok(member_ids)
+}
+
 impl TryFrom<proto::Account> for AccountValues {
     type Error = CalaLedgerOutboxClientError;
 
@@ -354,6 +442,7 @@ impl TryFrom<proto::ParamDefinition> for ParamDefinition {
             data_type,
             default,
             description,
+            constraints,
         }: proto::ParamDefinition,
     ) -> Result<Self, Self::Error> {
         let res = Self {
@@ -361,6 +450,39 @@ impl TryFrom<proto::ParamDefinition> for ParamDefinition {
             r#type: proto::ParamDataType::try_from(data_type).map(ParamDataType::from)?,
             default: default.map(CelExpression::try_from).transpose()?,
             description,
+            constraints: constraints
+                .map(ParamConstraints::try_from)
+                .transpose()?
+                .unwrap_or_default(),
+        };
+        Ok(res)
+    }
+}
+
+impl TryFrom<proto::ParamConstraints> for ParamConstraints {
+    type Error = CalaLedgerOutboxClientError;
+    fn try_from(
+        proto::ParamConstraints {
+            required,
+            allowed_values,
+            min,
+            max,
+            scale,
+            regex,
+            predicate,
+        }: proto::ParamConstraints,
+    ) -> Result<Self, Self::Error> {
+        let res = Self {
+            required,
+            allowed_values: allowed_values
+                .into_iter()
+                .map(CelExpression::try_from)
+                .collect::<Result<_, _>>()?,
+            min: min.map(|m| m.parse()).transpose()?,
+            max: max.map(|m| m.parse()).transpose()?,
+            scale,
+            regex: regex.map(ParamRegex::try_from).transpose()?,
+            predicate: predicate.map(CelExpression::try_from).transpose()?,
         };
         Ok(res)
     }
@@ -402,6 +524,8 @@ impl TryFrom<proto::TxTemplateEntry> for TxTemplateEntry {
             currency,
             description,
             metadata,
+            condition,
+            for_each,
         }: proto::TxTemplateEntry,
     ) -> Result<Self, Self::Error> {
         let res = Self {
@@ -413,6 +537,14 @@ impl TryFrom<proto::TxTemplateEntry> for TxTemplateEntry {
             currency: CelExpression::try_from(currency)?,
             description: description.map(CelExpression::try_from).transpose()?,
             metadata: metadata.map(CelExpression::try_from).transpose()?,
+            condition: condition.map(CelExpression::try_from).transpose()?,
+            for_each: match for_each {
+                Some(proto::TxTemplateEntryForEach { list, var }) => Some(TxTemplateEntryForEach {
+                    list: CelExpression::try_from(list)?,
+                    var,
+                }),
+                None => None,
+            },
         };
         Ok(res)
     }
@@ -429,6 +561,7 @@ impl From<proto::ParamDataType> for ParamDataType {
             proto::ParamDataType::Date => ParamDataType::Date,
             proto::ParamDataType::Timestamp => ParamDataType::Timestamp,
             proto::ParamDataType::Json => ParamDataType::Json,
+            proto::ParamDataType::List => ParamDataType::List,
         }
     }
 }
@@ -449,6 +582,7 @@ impl TryFrom<proto::Transaction> for TransactionValues {
             external_id,
             description,
             metadata,
+            tags,
         }: proto::Transaction,
     ) -> Result<Self, Self::Error> {
         let res = Self {
@@ -471,6 +605,29 @@ impl TryFrom<proto::Transaction> for TransactionValues {
             external_id,
             description,
             metadata: metadata.map(serde_json::to_value).transpose()?,
+            tags,
+        };
+        Ok(res)
+    }
+}
+
+impl TryFrom<proto::TransactionAnnotation> for TransactionAnnotation {
+    type Error = CalaLedgerOutboxClientError;
+    fn try_from(
+        proto::TransactionAnnotation {
+            actor,
+            annotated_at,
+            metadata,
+            tags,
+        }: proto::TransactionAnnotation,
+    ) -> Result<Self, Self::Error> {
+        let res = Self {
+            actor,
+            annotated_at: annotated_at
+                .ok_or(CalaLedgerOutboxClientError::MissingField)?
+                .into(),
+            metadata: metadata.map(serde_json::to_value).transpose()?,
+            tags,
         };
         Ok(res)
     }
@@ -582,3 +739,21 @@ impl From<proto::Layer> for Layer {
         }
     }
 }
+
+impl From<OutboxEventFilter> for proto::EventFilter {
+    fn from(
+        OutboxEventFilter {
+            payload_types,
+            journal_ids,
+            account_ids,
+            data_source_ids,
+        }: OutboxEventFilter,
+    ) -> Self {
+        Self {
+            payload_types: payload_types.iter().map(ToString::to_string).collect(),
+            journal_ids: journal_ids.iter().map(ToString::to_string).collect(),
+            account_ids: account_ids.iter().map(ToString::to_string).collect(),
+            data_source_ids: data_source_ids.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
@@ -21,6 +21,8 @@ pub enum CalaLedgerOutboxClientError {
     CelError(#[from] cel_interpreter::CelError),
     #[error("CalaLedgerOutboxError - ChronoError: {0}")]
     ChronoError(#[from] chrono::ParseError),
+    #[error("CalaLedgerOutboxError - RegexError: {0}")]
+    RegexError(#[from] regex::Error),
     #[error("CalaLedgerOutboxError - DecimalError: {0}")]
     DecimalError(#[from] rust_decimal::Error),
     #[error("CalaLedgerOutboxError - ParseCurrencyError: {0}")]
@@ -0,0 +1 @@
+ALTER TABLE cala_account_sets ADD COLUMN deleted BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,12 @@
+ALTER TABLE cala_journals ADD COLUMN status Status NOT NULL DEFAULT 'active';
+
+UPDATE cala_journals j
+SET status = (latest.event->'values'->>'status')::Status
+FROM (
+  SELECT DISTINCT ON (id) id, event
+  FROM cala_journal_events
+  ORDER BY id, sequence DESC
+) latest
+WHERE latest.id = j.id;
+
+CREATE INDEX idx_cala_journals_status ON cala_journals (status);
@@ -0,0 +1,44 @@
+ALTER TABLE cala_entries
+  ADD COLUMN entry_type VARCHAR,
+  ADD COLUMN layer Layer,
+  ADD COLUMN currency VARCHAR,
+  ADD COLUMN direction DebitOrCredit;
+
+-- Backfill in batches so no single statement has to join every entry with its event
+DO $$
+DECLARE
+  last_id UUID := '00000000-0000-0000-0000-000000000000';
+  batch_ids UUID[];
+BEGIN
+  LOOP
+    SELECT array_agg(id ORDER BY id) INTO batch_ids
+    FROM (
+      SELECT id FROM cala_entries
+      WHERE id > last_id
+      ORDER BY id
+      LIMIT 10000
+    ) batch;
+
+    EXIT WHEN batch_ids IS NULL;
+
+    UPDATE cala_entries e
+    SET entry_type = ev.event->'values'->>'entry_type',
+        layer = lower(ev.event->'values'->>'layer')::Layer,
+        currency = ev.event->'values'->>'currency',
+        direction = (ev.event->'values'->>'direction')::DebitOrCredit
+    FROM cala_entry_events ev
+    WHERE ev.id = e.id AND e.id = ANY(batch_ids);
+
+    last_id := batch_ids[array_length(batch_ids, 1)];
+  END LOOP;
+END $$;
+
+ALTER TABLE cala_entries
+  ALTER COLUMN entry_type SET NOT NULL,
+  ALTER COLUMN layer SET NOT NULL,
+  ALTER COLUMN currency SET NOT NULL,
+  ALTER COLUMN direction SET NOT NULL;
+
+CREATE INDEX idx_cala_entries_account_id ON cala_entries (account_id);
+CREATE INDEX idx_cala_entries_journal_id ON cala_entries (journal_id);
+CREATE INDEX idx_cala_balance_history_latest_entry_id ON cala_balance_history (latest_entry_id);
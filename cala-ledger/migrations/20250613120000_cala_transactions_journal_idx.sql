@@ -0,0 +1 @@
+CREATE INDEX idx_cala_transactions_journal_id_created_at ON cala_transactions (journal_id, created_at, id);
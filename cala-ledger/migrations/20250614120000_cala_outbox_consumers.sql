@@ -0,0 +1,6 @@
+CREATE TABLE cala_outbox_consumers (
+  name VARCHAR PRIMARY KEY,
+  last_acked_sequence BIGINT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  modified_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
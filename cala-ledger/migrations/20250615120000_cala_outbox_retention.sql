@@ -0,0 +1,17 @@
+CREATE TABLE cala_outbox_retention (
+  id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+  pruned_through BIGINT NOT NULL,
+  modified_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+INSERT INTO cala_outbox_retention (pruned_through) VALUES (0);
+
+CREATE TABLE cala_outbox_events_archive (
+  id UUID PRIMARY KEY,
+  sequence BIGINT UNIQUE NOT NULL,
+  payload JSONB,
+  recorded_at TIMESTAMPTZ NOT NULL,
+  seen_at TIMESTAMPTZ NOT NULL,
+  archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_cala_outbox_events_recorded_at ON cala_outbox_events (recorded_at);
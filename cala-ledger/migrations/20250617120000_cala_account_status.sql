@@ -0,0 +1,5 @@
+ALTER TABLE cala_accounts ADD COLUMN status Status NOT NULL DEFAULT 'active';
+
+UPDATE cala_accounts SET status = (latest_values->>'status')::Status;
+
+CREATE INDEX idx_cala_accounts_status ON cala_accounts (status);
@@ -0,0 +1 @@
+CREATE INDEX idx_cala_accounts_code_prefix ON cala_accounts (code text_pattern_ops);
@@ -169,6 +169,21 @@ impl TryFromEvents<AccountEvent> for Account {
     }
 }
 
+/// Criteria for [Accounts::list_filtered](crate::account::Accounts::list_filtered).
+/// Every criterion that is set must match.
+#[derive(Debug, Clone, Default)]
+pub struct AccountsFilter {
+    pub status: Option<Status>,
+    pub normal_balance_type: Option<DebitOrCredit>,
+    pub code_prefix: Option<String>,
+    pub is_account_set: Option<bool>,
+    /// Matches accounts that roll up into the given set (directly or via nested sets)
+    /// as well as account sets that are direct members of it.
+    pub member_of: Option<AccountSetId>,
+    /// Matches accounts whose metadata contains this JSON value (`@>`).
+    pub metadata: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Builder, Default)]
 #[builder(name = "AccountUpdate", default)]
 pub struct AccountUpdateValues {
@@ -117,6 +117,15 @@ impl Accounts {
         self.repo.list_by_name(query, Default::default()).await
     }
 
+    #[instrument(name = "cala_ledger.accounts.list_filtered", skip(self), err)]
+    pub async fn list_filtered(
+        &self,
+        filter: AccountsFilter,
+        query: es_entity::PaginatedQueryArgs<AccountsByNameCursor>,
+    ) -> Result<es_entity::PaginatedQueryRet<Account, AccountsByNameCursor>, AccountError> {
+        self.repo.list_filtered(filter, query).await
+    }
+
     #[instrument(name = "cala_ledger.accounts.persist", skip(self, account))]
     pub async fn persist(&self, account: &mut Account) -> Result<(), AccountError> {
         let mut op = LedgerOperation::init(&self.pool, &self.outbox).await?;
@@ -1,7 +1,7 @@
 use es_entity::*;
 use sqlx::PgPool;
 
-use crate::primitives::{DataSourceId, DebitOrCredit};
+use crate::primitives::{AccountSetId, DataSourceId, DebitOrCredit, Status};
 
 use super::{entity::*, error::AccountError};
 
@@ -21,6 +21,7 @@ use super::{entity::*, error::AccountError};
             ty = "DebitOrCredit",
             update(accessor = "values().normal_balance_type")
         ),
+        status(ty = "Status", update(accessor = "values().status")),
         eventually_consistent(ty = "bool", update(persist = false)),
         latest_values(
             ty = "serde_json::Value",
@@ -44,6 +45,75 @@ impl AccountRepo {
         Self { pool: pool.clone() }
     }
 
+    pub async fn list_filtered(
+        &self,
+        filter: AccountsFilter,
+        query: es_entity::PaginatedQueryArgs<account_cursor::AccountsByNameCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<Account, account_cursor::AccountsByNameCursor>,
+        AccountError,
+    > {
+        // Matched via LIKE to use idx_cala_accounts_code_prefix, wildcards are escaped
+        let code_pattern = filter.code_prefix.map(|prefix| {
+            let escaped = prefix
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("{escaped}%")
+        });
+        let rows = sqlx::query_as!(
+            account_repo_types::Repo__DbEvent,
+            r#"
+            WITH accounts AS (
+              SELECT a.id, a.name
+              FROM cala_accounts a
+              WHERE ((a.name, a.id) > ($2, $1) OR ($1 IS NULL AND $2 IS NULL))
+              AND ($3::Status IS NULL OR a.status = $3)
+              AND ($4::DebitOrCredit IS NULL OR a.normal_balance_type = $4)
+              AND ($5::text IS NULL OR a.code LIKE $5)
+              AND ($6::boolean IS NULL OR (a.latest_values->'config'->>'is_account_set')::boolean = $6)
+              AND ($7::uuid IS NULL
+                OR EXISTS (
+                  SELECT 1 FROM cala_account_set_member_accounts m
+                  WHERE m.account_set_id = $7 AND m.member_account_id = a.id
+                )
+                OR EXISTS (
+                  SELECT 1 FROM cala_account_set_member_account_sets m
+                  WHERE m.account_set_id = $7 AND m.member_account_set_id = a.id
+                ))
+              AND ($8::jsonb IS NULL OR a.latest_values->'metadata' @> $8)
+              ORDER BY a.name, a.id
+              LIMIT $9
+            )
+            SELECT a.id AS "entity_id!: AccountId", e.sequence, e.event, e.recorded_at
+              FROM accounts a
+              JOIN cala_account_events e ON a.id = e.id
+              ORDER BY a.name, a.id, e.sequence
+            "#,
+            query.after.as_ref().map(|c| c.id) as Option<AccountId>,
+            query.after.map(|c| c.name),
+            filter.status as Option<Status>,
+            filter.normal_balance_type as Option<DebitOrCredit>,
+            code_pattern,
+            filter.is_account_set,
+            filter.member_of as Option<AccountSetId>,
+            filter.metadata,
+            query.first as i64 + 1
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        let (entities, has_next_page) = EntityEvents::load_n::<Account>(rows, query.first)?;
+        let end_cursor = entities
+            .last()
+            .map(account_cursor::AccountsByNameCursor::from);
+        Ok(es_entity::PaginatedQueryRet {
+            entities,
+            has_next_page,
+            end_cursor,
+        })
+    }
+
     #[cfg(feature = "import")]
     pub async fn import_in_op(
         &self,
@@ -53,14 +123,15 @@ impl AccountRepo {
     ) -> Result<(), AccountError> {
         let recorded_at = op.now();
         sqlx::query!(
-            r#"INSERT INTO cala_accounts (data_source_id, id, code, name, external_id, normal_balance_type, eventually_consistent, created_at, latest_values)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
+            r#"INSERT INTO cala_accounts (data_source_id, id, code, name, external_id, normal_balance_type, status, eventually_consistent, created_at, latest_values)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
             origin as DataSourceId,
             account.values().id as AccountId,
             account.values().code,
             account.values().name,
             account.values().external_id,
             account.values().normal_balance_type as DebitOrCredit,
+            account.values().status as Status,
             account.values().config.eventually_consistent,
             recorded_at,
             serde_json::to_value(account.values()).expect("Failed to serialize account values"),
@@ -22,6 +22,9 @@ pub enum AccountSetEvent {
         values: AccountSetValues,
         fields: Vec<String>,
     },
+    Archived {
+        values: AccountSetValues,
+    },
 }
 
 #[derive(EsEntity, Builder)]
@@ -29,6 +32,8 @@ pub enum AccountSetEvent {
 pub struct AccountSet {
     pub id: AccountSetId,
     values: AccountSetValues,
+    #[builder(default)]
+    archived: bool,
     events: EntityEvents<AccountSetEvent>,
 }
 
@@ -105,6 +110,21 @@ impl AccountSet {
         }
     }
 
+    pub fn is_archived(&self) -> bool {
+        self.archived
+    }
+
+    pub(super) fn archive(&mut self) -> Idempotent<()> {
+        if self.archived {
+            return Idempotent::Ignored;
+        }
+        self.archived = true;
+        self.events.push(AccountSetEvent::Archived {
+            values: self.values.clone(),
+        });
+        Idempotent::Executed(())
+    }
+
     pub fn into_values(self) -> AccountSetValues {
         self.values
     }
@@ -201,6 +221,9 @@ impl TryFromEvents<AccountSetEvent> for AccountSet {
                 AccountSetEvent::Updated { values, .. } => {
                     builder = builder.values(values.clone());
                 }
+                AccountSetEvent::Archived { .. } => {
+                    builder = builder.archived(true);
+                }
             }
         }
         builder.events(events).build()
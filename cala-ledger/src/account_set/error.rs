@@ -26,6 +26,14 @@ pub enum AccountSetError {
     JournalIdMismatch,
     #[error("AccountSetError - Member already added to account set")]
     MemberAlreadyAdded,
+    #[error("AccountSetError - account set '{0}' still has members")]
+    ArchiveWithMembers(AccountSetId),
+    #[error("AccountSetError - account set '{0}' is still a member of other account sets")]
+    ArchiveWithParents(AccountSetId),
+    #[error("AccountSetError - account set '{0}' is archived")]
+    Archived(AccountSetId),
+    #[error("AccountSetError - hierarchy is deeper than {0} levels")]
+    HierarchyTooDeep(u32),
 }
 
 es_entity::from_es_entity_error!(AccountSetError);
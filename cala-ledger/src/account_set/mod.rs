@@ -5,7 +5,7 @@ mod repo;
 
 use es_entity::EsEntity;
 use sqlx::PgPool;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::instrument;
 
 #[cfg(feature = "import")]
@@ -16,7 +16,7 @@ use crate::{
     entry::*,
     ledger_operation::*,
     outbox::*,
-    primitives::{DataSource, DebitOrCredit, JournalId, Layer},
+    primitives::{Currency, DataSource, DebitOrCredit, JournalId, Layer},
 };
 
 pub use cursor::*;
@@ -26,6 +26,9 @@ use repo::*;
 pub use repo::{account_set_cursor::*, members_cursor::*};
 
 const UNASSIGNED_TRANSACTION_ID: uuid::Uuid = uuid::Uuid::nil();
+/// Upper bound on the depth traversed by the hierarchy queries. Deeper hierarchies
+/// fail with [`AccountSetError::HierarchyTooDeep`].
+pub const MAX_HIERARCHY_DEPTH: u32 = 64;
 
 #[derive(Clone)]
 pub struct AccountSets {
@@ -148,6 +151,117 @@ impl AccountSets {
         Ok(())
     }
 
+    pub async fn archive(
+        &self,
+        account_set_id: AccountSetId,
+        detach: bool,
+    ) -> Result<(), AccountSetError> {
+        let mut op = LedgerOperation::init(&self.pool, &self.outbox).await?;
+        self.archive_in_op(&mut op, account_set_id, detach).await?;
+        op.commit().await?;
+        Ok(())
+    }
+
+    /// Archives an account set so that it stops receiving balance updates and is
+    /// hidden from listings. Fails while the set still has members or parents unless
+    /// `detach` is set, in which case they are removed first.
+    #[instrument(name = "cala_ledger.account_sets.archive_in_op", skip(self, op), err)]
+    pub async fn archive_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        account_set_id: AccountSetId,
+        detach: bool,
+    ) -> Result<(), AccountSetError> {
+        let mut account_set = self
+            .repo
+            .find_by_id_in_tx_include_deleted(op.tx(), account_set_id)
+            .await?;
+        if account_set.is_archived() {
+            return Ok(());
+        }
+
+        let parents = self
+            .repo
+            .find_direct_parents_in_tx(op.tx(), account_set_id)
+            .await?;
+        let members = self
+            .repo
+            .find_direct_members_in_tx(op.tx(), account_set_id)
+            .await?;
+        if !detach && !members.is_empty() {
+            return Err(AccountSetError::ArchiveWithMembers(account_set_id));
+        }
+        if !detach && !parents.is_empty() {
+            return Err(AccountSetError::ArchiveWithParents(account_set_id));
+        }
+        if !parents.is_empty() {
+            self.detach_from_parents_in_op(op, &account_set).await?;
+        }
+        self.remove_members_in_op(op, account_set_id, members)
+            .await?;
+
+        account_set.archive().expect("account set is not archived");
+        let n_events = self.repo.update_in_op(op.op(), &mut account_set).await?;
+        op.accumulate(account_set.last_persisted(n_events).map(|p| &p.event));
+        self.repo.delete_in_op(op.op(), account_set).await?;
+        Ok(())
+    }
+
+    async fn detach_from_parents_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        account_set: &AccountSet,
+    ) -> Result<(), AccountSetError> {
+        let account_set_id = account_set.id();
+        let (time, ancestors) = self
+            .repo
+            .detach_member_set_and_return_ancestors(op.tx(), account_set_id)
+            .await?;
+        op.accumulate(ancestors.keys().map(|parent_id| {
+            OutboxEventPayload::AccountSetMemberRemoved {
+                source: DataSource::Local,
+                account_set_id: *parent_id,
+                member_id: AccountSetMemberId::AccountSet(account_set_id),
+            }
+        }));
+
+        let balances = self
+            .balances
+            .find_balances_for_update(
+                op.tx(),
+                account_set.values().journal_id,
+                AccountId::from(&account_set_id),
+            )
+            .await?;
+
+        let mut entries = Vec::new();
+        for parent_id in ancestors.keys() {
+            for balance in balances.values() {
+                entries_for_remove_balance(&mut entries, parent_id.into(), balance.clone());
+            }
+        }
+
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let entries = self.entries.create_all_in_op(op, entries).await?;
+        let mappings = ancestors
+            .into_iter()
+            .map(|(parent_id, ancestors)| (AccountId::from(&parent_id), ancestors))
+            .collect();
+        self.balances
+            .update_balances_in_op(
+                op,
+                account_set.values().journal_id,
+                entries,
+                time.date_naive(),
+                time,
+                mappings,
+            )
+            .await?;
+        Ok(())
+    }
+
     pub async fn add_member(
         &self,
         account_set_id: AccountSetId,
@@ -170,7 +284,13 @@ impl AccountSets {
         let member = member.into();
         let (time, parents, account_set, member_id) = match member {
             AccountSetMemberId::Account(id) => {
-                let set = self.repo.find_by_id_in_tx(op.tx(), account_set_id).await?;
+                let set = self
+                    .repo
+                    .find_by_id_in_tx_include_deleted(op.tx(), account_set_id)
+                    .await?;
+                if set.is_archived() {
+                    return Err(AccountSetError::Archived(account_set_id));
+                }
                 let (time, parents) = self
                     .repo
                     .add_member_account_and_return_parents(op.tx(), account_set_id, id)
@@ -192,6 +312,12 @@ impl AccountSets {
                 if target.values().journal_id != member.values().journal_id {
                     return Err(AccountSetError::JournalIdMismatch);
                 }
+                if target.is_archived() {
+                    return Err(AccountSetError::Archived(account_set_id));
+                }
+                if member.is_archived() {
+                    return Err(AccountSetError::Archived(id));
+                }
 
                 let (time, parents) = self
                     .repo
@@ -261,7 +387,13 @@ impl AccountSets {
         let member = member.into();
         let (time, parents, account_set, member_id) = match member {
             AccountSetMemberId::Account(id) => {
-                let set = self.repo.find_by_id_in_tx(op.tx(), account_set_id).await?;
+                let set = self
+                    .repo
+                    .find_by_id_in_tx_include_deleted(op.tx(), account_set_id)
+                    .await?;
+                if set.is_archived() {
+                    return Err(AccountSetError::Archived(account_set_id));
+                }
                 let (time, parents) = self
                     .repo
                     .remove_member_account_and_return_parents(op.tx(), account_set_id, id)
@@ -283,6 +415,9 @@ impl AccountSets {
                 if target.values().journal_id != member.values().journal_id {
                     return Err(AccountSetError::JournalIdMismatch);
                 }
+                if target.is_archived() {
+                    return Err(AccountSetError::Archived(account_set_id));
+                }
 
                 let (time, parents) = self
                     .repo
@@ -330,6 +465,234 @@ impl AccountSets {
         Ok(account_set)
     }
 
+    pub async fn add_members(
+        &self,
+        account_set_id: AccountSetId,
+        members: Vec<AccountSetMemberId>,
+    ) -> Result<AccountSet, AccountSetError> {
+        let mut op = LedgerOperation::init(&self.pool, &self.outbox).await?;
+        let account_set = self
+            .add_members_in_op(&mut op, account_set_id, members)
+            .await?;
+        op.commit().await?;
+        Ok(account_set)
+    }
+
+    /// Adds many members to an account set at once. Member balances are locked in a
+    /// single query and rolled up into one set of entries per currency.
+    #[instrument(
+        name = "cala_ledger.account_sets.add_members_in_op",
+        skip(self, op),
+        err
+    )]
+    pub async fn add_members_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        account_set_id: AccountSetId,
+        mut members: Vec<AccountSetMemberId>,
+    ) -> Result<AccountSet, AccountSetError> {
+        dedup_members(&mut members);
+        let account_set = self
+            .find_for_batch_membership(op, account_set_id, &members)
+            .await?;
+        if members.is_empty() {
+            return Ok(account_set);
+        }
+
+        let (account_ids, set_ids) = split_members(&members);
+        let (time, parents) = self
+            .repo
+            .lock_and_find_parents(op.tx(), account_set_id)
+            .await?;
+        if !account_ids.is_empty() {
+            self.repo
+                .add_member_accounts(op.tx(), account_set_id, &account_ids, &parents)
+                .await?;
+        }
+        if !set_ids.is_empty() {
+            self.repo
+                .add_member_sets(op.tx(), account_set_id, &set_ids, &parents)
+                .await?;
+        }
+
+        let member_ids: Vec<_> = account_ids
+            .into_iter()
+            .chain(set_ids.iter().map(AccountId::from))
+            .collect();
+        op.accumulate(std::iter::once(
+            OutboxEventPayload::AccountSetMembersCreated {
+                source: DataSource::Local,
+                account_set_id,
+                member_ids: members,
+            },
+        ));
+
+        let balances = self
+            .balances
+            .find_all_balances_for_update(op.tx(), account_set.values().journal_id, &member_ids)
+            .await?;
+
+        let target_account_id = AccountId::from(&account_set.id());
+        let mut entries = Vec::new();
+        for balance in aggregate_balances(balances).into_values() {
+            entries_for_add_balance(&mut entries, target_account_id, balance);
+        }
+
+        if entries.is_empty() {
+            return Ok(account_set);
+        }
+        let entries = self.entries.create_all_in_op(op, entries).await?;
+        let mappings = std::iter::once((target_account_id, parents)).collect();
+        self.balances
+            .update_balances_in_op(
+                op,
+                account_set.values().journal_id,
+                entries,
+                time.date_naive(),
+                time,
+                mappings,
+            )
+            .await?;
+
+        Ok(account_set)
+    }
+
+    pub async fn remove_members(
+        &self,
+        account_set_id: AccountSetId,
+        members: Vec<AccountSetMemberId>,
+    ) -> Result<AccountSet, AccountSetError> {
+        let mut op = LedgerOperation::init(&self.pool, &self.outbox).await?;
+        let account_set = self
+            .remove_members_in_op(&mut op, account_set_id, members)
+            .await?;
+        op.commit().await?;
+        Ok(account_set)
+    }
+
+    /// Removes many members from an account set at once, the counterpart of
+    /// [`add_members_in_op`](Self::add_members_in_op).
+    #[instrument(
+        name = "cala_ledger.account_sets.remove_members_in_op",
+        skip(self, op),
+        err
+    )]
+    pub async fn remove_members_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        account_set_id: AccountSetId,
+        mut members: Vec<AccountSetMemberId>,
+    ) -> Result<AccountSet, AccountSetError> {
+        dedup_members(&mut members);
+        let account_set = self
+            .find_for_batch_membership(op, account_set_id, &members)
+            .await?;
+        if members.is_empty() {
+            return Ok(account_set);
+        }
+
+        let (account_ids, set_ids) = split_members(&members);
+        let (time, parents) = self
+            .repo
+            .lock_and_find_parents(op.tx(), account_set_id)
+            .await?;
+        if !account_ids.is_empty() {
+            self.repo
+                .remove_member_accounts(op.tx(), account_set_id, &account_ids, &parents)
+                .await?;
+        }
+        if !set_ids.is_empty() {
+            self.repo
+                .remove_member_sets(op.tx(), account_set_id, &set_ids, &parents)
+                .await?;
+        }
+
+        let member_ids: Vec<_> = account_ids
+            .into_iter()
+            .chain(set_ids.iter().map(AccountId::from))
+            .collect();
+        op.accumulate(std::iter::once(
+            OutboxEventPayload::AccountSetMembersRemoved {
+                source: DataSource::Local,
+                account_set_id,
+                member_ids: members,
+            },
+        ));
+
+        let balances = self
+            .balances
+            .find_all_balances_for_update(op.tx(), account_set.values().journal_id, &member_ids)
+            .await?;
+
+        let target_account_id = AccountId::from(&account_set.id());
+        let mut entries = Vec::new();
+        for balance in aggregate_balances(balances).into_values() {
+            entries_for_remove_balance(&mut entries, target_account_id, balance);
+        }
+
+        if entries.is_empty() {
+            return Ok(account_set);
+        }
+        let entries = self.entries.create_all_in_op(op, entries).await?;
+        let mappings = std::iter::once((target_account_id, parents)).collect();
+        self.balances
+            .update_balances_in_op(
+                op,
+                account_set.values().journal_id,
+                entries,
+                time.date_naive(),
+                time,
+                mappings,
+            )
+            .await?;
+
+        Ok(account_set)
+    }
+
+    async fn find_for_batch_membership(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        account_set_id: AccountSetId,
+        members: &[AccountSetMemberId],
+    ) -> Result<AccountSet, AccountSetError> {
+        let (account_ids, mut set_ids) = split_members(members);
+        let accounts = self
+            .accounts
+            .find_all_in_op::<Account>(op, &account_ids)
+            .await?;
+        for id in account_ids {
+            let account = accounts
+                .get(&id)
+                .ok_or(crate::account::error::AccountError::CouldNotFindById(id))?;
+            // Plain accounts aren't bound to a journal. An account backing an account set
+            // is checked through its set below: same journal as the target, not archived.
+            if account.values().config.is_account_set {
+                set_ids.push(AccountSetId::from(uuid::Uuid::from(id)));
+            }
+        }
+        let ids: Vec<_> = std::iter::once(account_set_id).chain(set_ids).collect();
+        let mut sets = self
+            .repo
+            .find_all_in_tx::<AccountSet>(op.tx(), &ids)
+            .await?;
+        let target = sets
+            .remove(&account_set_id)
+            .ok_or(AccountSetError::CouldNotFindById(account_set_id))?;
+        if target.is_archived() {
+            return Err(AccountSetError::Archived(account_set_id));
+        }
+        for id in ids.into_iter().skip(1) {
+            let member = sets.get(&id).ok_or(AccountSetError::CouldNotFindById(id))?;
+            if target.values().journal_id != member.values().journal_id {
+                return Err(AccountSetError::JournalIdMismatch);
+            }
+            if member.is_archived() {
+                return Err(AccountSetError::Archived(id));
+            }
+        }
+        Ok(target)
+    }
+
     #[instrument(name = "cala_ledger.account_sets.find_all", skip(self), err)]
     pub async fn find_all<T: From<AccountSet>>(
         &self,
@@ -348,8 +711,9 @@ impl AccountSets {
     }
 
     #[instrument(name = "cala_ledger.account_sets.find", skip(self), err)]
+    /// Also returns archived account sets - check [AccountSet::is_archived].
     pub async fn find(&self, account_set_id: AccountSetId) -> Result<AccountSet, AccountSetError> {
-        self.repo.find_by_id(account_set_id).await
+        self.repo.find_by_id_include_deleted(account_set_id).await
     }
 
     #[instrument(
@@ -500,6 +864,77 @@ impl AccountSets {
             .await
     }
 
+    /// Walks the full hierarchy below `id`, returning every nested member in
+    /// depth-first order. With `leaf_accounts_only` set, nested account sets are
+    /// traversed but omitted from the result.
+    #[instrument(name = "cala_ledger.account_sets.list_descendants", skip(self), err)]
+    pub async fn list_descendants(
+        &self,
+        id: AccountSetId,
+        leaf_accounts_only: bool,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        self.repo
+            .list_descendants(id, leaf_accounts_only, args)
+            .await
+    }
+
+    #[instrument(
+        name = "cala_ledger.account_sets.list_descendants_in_op",
+        skip(self, op),
+        err
+    )]
+    pub async fn list_descendants_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        id: AccountSetId,
+        leaf_accounts_only: bool,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        self.repo
+            .list_descendants_in_tx(op.tx(), id, leaf_accounts_only, args)
+            .await
+    }
+
+    /// Walks the hierarchy above `member`, returning every account set that
+    /// contains it directly or transitively.
+    #[instrument(name = "cala_ledger.account_sets.list_ancestors", skip(self), err)]
+    pub async fn list_ancestors(
+        &self,
+        member: impl Into<AccountSetMemberId> + std::fmt::Debug,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        self.repo.list_ancestors(member.into(), args).await
+    }
+
+    #[instrument(
+        name = "cala_ledger.account_sets.list_ancestors_in_op",
+        skip(self, op),
+        err
+    )]
+    pub async fn list_ancestors_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        member: impl Into<AccountSetMemberId> + std::fmt::Debug,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        self.repo
+            .list_ancestors_in_tx(op.tx(), member.into(), args)
+            .await
+    }
+
     pub(crate) async fn fetch_mappings_in_op(
         &self,
         op: &mut LedgerOperation<'_>,
@@ -554,6 +989,32 @@ impl AccountSets {
         Ok(())
     }
 
+    #[cfg(feature = "import")]
+    pub(crate) async fn sync_account_set_archival(
+        &self,
+        mut db: es_entity::DbOp<'_>,
+        values: AccountSetValues,
+    ) -> Result<(), AccountSetError> {
+        let mut account_set = self
+            .repo
+            .find_by_id_in_tx_include_deleted(db.tx(), values.id)
+            .await?;
+        if account_set.archive().was_ignored() {
+            return Ok(());
+        }
+        let n_events = self.repo.update_in_op(&mut db, &mut account_set).await?;
+        let recorded_at = db.now();
+        let outbox_events: Vec<_> = account_set
+            .last_persisted(n_events)
+            .map(|p| OutboxEventPayload::from(&p.event))
+            .collect();
+        self.repo.delete_in_op(&mut db, account_set).await?;
+        self.outbox
+            .persist_events_at(db.into_tx(), outbox_events, recorded_at)
+            .await?;
+        Ok(())
+    }
+
     #[cfg(feature = "import")]
     pub(crate) async fn sync_account_set_member_creation(
         &self,
@@ -623,6 +1084,121 @@ impl AccountSets {
             .await?;
         Ok(())
     }
+
+    #[cfg(feature = "import")]
+    pub(crate) async fn sync_account_set_members_creation(
+        &self,
+        mut db: es_entity::DbOp<'_>,
+        origin: DataSourceId,
+        account_set_id: AccountSetId,
+        member_ids: Vec<AccountSetMemberId>,
+    ) -> Result<(), AccountSetError> {
+        for member_id in member_ids.iter() {
+            match *member_id {
+                AccountSetMemberId::Account(account_id) => {
+                    self.repo
+                        .import_member_account_in_op(&mut db, account_set_id, account_id)
+                        .await?;
+                }
+                AccountSetMemberId::AccountSet(member_account_set_id) => {
+                    self.repo
+                        .import_member_set_in_op(&mut db, account_set_id, member_account_set_id)
+                        .await?;
+                }
+            }
+        }
+        let recorded_at = db.now();
+        self.outbox
+            .persist_events_at(
+                db.into_tx(),
+                std::iter::once(OutboxEventPayload::AccountSetMembersCreated {
+                    source: DataSource::Remote { id: origin },
+                    account_set_id,
+                    member_ids,
+                }),
+                recorded_at,
+            )
+            .await?;
+        Ok(())
+    }
+
+    #[cfg(feature = "import")]
+    pub(crate) async fn sync_account_set_members_removal(
+        &self,
+        mut db: es_entity::DbOp<'_>,
+        origin: DataSourceId,
+        account_set_id: AccountSetId,
+        member_ids: Vec<AccountSetMemberId>,
+    ) -> Result<(), AccountSetError> {
+        for member_id in member_ids.iter() {
+            match *member_id {
+                AccountSetMemberId::Account(account_id) => {
+                    self.repo
+                        .import_remove_member_account(db.tx(), account_set_id, account_id)
+                        .await?;
+                }
+                AccountSetMemberId::AccountSet(member_account_set_id) => {
+                    self.repo
+                        .import_remove_member_set(db.tx(), account_set_id, member_account_set_id)
+                        .await?;
+                }
+            }
+        }
+        let recorded_at = db.now();
+        self.outbox
+            .persist_events_at(
+                db.into_tx(),
+                std::iter::once(OutboxEventPayload::AccountSetMembersRemoved {
+                    source: DataSource::Remote { id: origin },
+                    account_set_id,
+                    member_ids,
+                }),
+                recorded_at,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+fn dedup_members(members: &mut Vec<AccountSetMemberId>) {
+    let mut account_ids = HashSet::new();
+    let mut set_ids = HashSet::new();
+    members.retain(|member| match member {
+        AccountSetMemberId::Account(id) => account_ids.insert(*id),
+        AccountSetMemberId::AccountSet(id) => set_ids.insert(*id),
+    });
+}
+
+fn split_members(members: &[AccountSetMemberId]) -> (Vec<AccountId>, Vec<AccountSetId>) {
+    let mut account_ids = Vec::new();
+    let mut set_ids = Vec::new();
+    for member in members {
+        match member {
+            AccountSetMemberId::Account(id) => account_ids.push(*id),
+            AccountSetMemberId::AccountSet(id) => set_ids.push(*id),
+        }
+    }
+    (account_ids, set_ids)
+}
+
+fn aggregate_balances(balances: Vec<BalanceSnapshot>) -> HashMap<Currency, BalanceSnapshot> {
+    let mut ret: HashMap<Currency, BalanceSnapshot> = HashMap::new();
+    for balance in balances {
+        match ret.get_mut(&balance.currency) {
+            Some(total) => {
+                total.settled.dr_balance += balance.settled.dr_balance;
+                total.settled.cr_balance += balance.settled.cr_balance;
+                total.pending.dr_balance += balance.pending.dr_balance;
+                total.pending.cr_balance += balance.pending.cr_balance;
+                total.encumbrance.dr_balance += balance.encumbrance.dr_balance;
+                total.encumbrance.cr_balance += balance.encumbrance.cr_balance;
+            }
+            None => {
+                ret.insert(balance.currency, balance);
+            }
+        }
+    }
+    ret
 }
 
 fn entries_for_add_balance(
@@ -857,6 +1433,10 @@ impl From<&AccountSetEvent> for OutboxEventPayload {
                 account_set: values.clone(),
                 fields: fields.clone(),
             },
+            AccountSetEvent::Archived { values } => OutboxEventPayload::AccountSetArchived {
+                source: DataSource::Local,
+                account_set: values.clone(),
+            },
         }
     }
 }
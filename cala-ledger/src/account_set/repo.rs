@@ -6,13 +6,17 @@ use std::collections::HashMap;
 
 use crate::primitives::{AccountId, DataSourceId, JournalId};
 
-use super::{entity::*, error::*};
+use super::{entity::*, error::*, MAX_HIERARCHY_DEPTH};
 
 const ADDVISORY_LOCK_ID: i64 = 123456;
 
 pub mod members_cursor {
-    use cala_types::account_set::{
-        AccountSetMember, AccountSetMemberByExternalId, AccountSetMemberId,
+    use cala_types::{
+        account_set::{
+            AccountSetHierarchyNode, AccountSetMember, AccountSetMemberByExternalId,
+            AccountSetMemberId,
+        },
+        primitives::AccountSetId,
     };
     use serde::{Deserialize, Serialize};
 
@@ -85,6 +89,55 @@ pub mod members_cursor {
             serde_json::from_str(&json).map_err(|e| e.to_string())
         }
     }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct AccountSetHierarchyCursor {
+        pub id: AccountSetMemberId,
+        pub path: Vec<AccountSetId>,
+    }
+
+    impl From<&AccountSetHierarchyNode> for AccountSetHierarchyCursor {
+        fn from(node: &AccountSetHierarchyNode) -> Self {
+            Self {
+                id: node.id,
+                path: node.path.clone(),
+            }
+        }
+    }
+
+    impl AccountSetHierarchyCursor {
+        pub(super) fn sort_key(&self) -> Vec<uuid::Uuid> {
+            let id = match self.id {
+                AccountSetMemberId::Account(id) => uuid::Uuid::from(id),
+                AccountSetMemberId::AccountSet(id) => uuid::Uuid::from(id),
+            };
+            self.path
+                .iter()
+                .map(|id| uuid::Uuid::from(*id))
+                .chain(std::iter::once(id))
+                .collect()
+        }
+    }
+
+    #[cfg(feature = "graphql")]
+    impl async_graphql::connection::CursorType for AccountSetHierarchyCursor {
+        type Error = String;
+
+        fn encode_cursor(&self) -> String {
+            use base64::{engine::general_purpose, Engine as _};
+            let json = serde_json::to_string(&self).expect("could not serialize token");
+            general_purpose::STANDARD_NO_PAD.encode(json.as_bytes())
+        }
+
+        fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+            use base64::{engine::general_purpose, Engine as _};
+            let bytes = general_purpose::STANDARD_NO_PAD
+                .decode(s.as_bytes())
+                .map_err(|e| e.to_string())?;
+            let json = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+            serde_json::from_str(&json).map_err(|e| e.to_string())
+        }
+    }
 }
 
 use account_set_cursor::*;
@@ -108,7 +161,8 @@ use members_cursor::*;
             update(persist = false)
         ),
     ),
-    tbl_prefix = "cala"
+    tbl_prefix = "cala",
+    delete = "soft"
 )]
 pub(super) struct AccountSetRepo {
     pool: PgPool,
@@ -409,6 +463,227 @@ impl AccountSetRepo {
         })
     }
 
+    pub async fn list_descendants(
+        &self,
+        id: AccountSetId,
+        leaf_accounts_only: bool,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        self.list_descendants_in_executor(&self.pool, id, leaf_accounts_only, args)
+            .await
+    }
+
+    pub async fn list_descendants_in_tx(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        id: AccountSetId,
+        leaf_accounts_only: bool,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        self.list_descendants_in_executor(&mut **db, id, leaf_accounts_only, args)
+            .await
+    }
+
+    async fn list_descendants_in_executor(
+        &self,
+        executor: impl Executor<'_, Database = Postgres>,
+        account_set_id: AccountSetId,
+        leaf_accounts_only: bool,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        let es_entity::PaginatedQueryArgs { first, after } = args;
+        let after = after.map(|cursor| cursor.sort_key());
+
+        let rows = sqlx::query!(
+            r#"
+            WITH RECURSIVE sets AS (
+              SELECT $1::uuid AS id, 0 AS depth, ARRAY[]::uuid[] AS trail
+              UNION ALL
+              SELECT m.member_account_set_id, s.depth + 1, s.trail || s.id
+              FROM sets s
+              JOIN cala_account_set_member_account_sets m ON m.account_set_id = s.id
+              WHERE NOT m.member_account_set_id = ANY(s.trail || s.id) AND s.depth < $5
+            ), nodes AS (
+              SELECT NULL::uuid AS member_account_id, s.id AS member_account_set_id, s.depth, s.trail
+              FROM sets s
+              WHERE s.depth > 0 AND $2 IS FALSE
+              UNION ALL
+              SELECT m.member_account_id, NULL::uuid, s.depth + 1, s.trail || s.id
+              FROM sets s
+              JOIN cala_account_set_member_accounts m
+                ON m.account_set_id = s.id AND m.transitive IS FALSE
+            )
+            SELECT
+              member_account_id,
+              member_account_set_id,
+              depth AS "depth!",
+              trail[2:] AS "path!: Vec<uuid::Uuid>",
+              EXISTS (
+                SELECT 1
+                FROM sets s
+                JOIN cala_account_set_member_account_sets m ON m.account_set_id = s.id
+                WHERE s.depth = $5 AND NOT m.member_account_set_id = ANY(s.trail || s.id)
+              ) AS "too_deep!"
+            FROM nodes
+            WHERE $3::uuid[] IS NULL
+              OR trail || COALESCE(member_account_id, member_account_set_id) > ARRAY[$1::uuid] || $3::uuid[]
+            ORDER BY trail || COALESCE(member_account_id, member_account_set_id)
+            LIMIT $4
+          "#,
+            uuid::Uuid::from(account_set_id),
+            leaf_accounts_only,
+            after.as_deref(),
+            (first + 1) as i64,
+            MAX_HIERARCHY_DEPTH as i32,
+        )
+        .fetch_all(executor)
+        .await?;
+        if rows.first().is_some_and(|row| row.too_deep) {
+            return Err(AccountSetError::HierarchyTooDeep(MAX_HIERARCHY_DEPTH));
+        }
+
+        let has_next_page = rows.len() > first;
+        let nodes: Vec<_> = rows
+            .into_iter()
+            .take(first)
+            .map(|row| {
+                let id = match (row.member_account_id, row.member_account_set_id) {
+                    (Some(member_account_id), _) => {
+                        AccountSetMemberId::Account(AccountId::from(member_account_id))
+                    }
+                    (_, Some(member_account_set_id)) => {
+                        AccountSetMemberId::AccountSet(AccountSetId::from(member_account_set_id))
+                    }
+                    _ => unreachable!(),
+                };
+                AccountSetHierarchyNode {
+                    id,
+                    depth: row.depth as u32,
+                    path: row.path.into_iter().map(AccountSetId::from).collect(),
+                }
+            })
+            .collect();
+        let end_cursor = nodes.last().map(AccountSetHierarchyCursor::from);
+
+        Ok(es_entity::PaginatedQueryRet {
+            entities: nodes,
+            has_next_page,
+            end_cursor,
+        })
+    }
+
+    pub async fn list_ancestors(
+        &self,
+        member: AccountSetMemberId,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        self.list_ancestors_in_executor(&self.pool, member, args)
+            .await
+    }
+
+    pub async fn list_ancestors_in_tx(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        member: AccountSetMemberId,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        self.list_ancestors_in_executor(&mut **db, member, args)
+            .await
+    }
+
+    async fn list_ancestors_in_executor(
+        &self,
+        executor: impl Executor<'_, Database = Postgres>,
+        member: AccountSetMemberId,
+        args: es_entity::PaginatedQueryArgs<AccountSetHierarchyCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<AccountSetHierarchyNode, AccountSetHierarchyCursor>,
+        AccountSetError,
+    > {
+        let es_entity::PaginatedQueryArgs { first, after } = args;
+        let after = after.map(|cursor| cursor.sort_key());
+        let (member_id, member_is_set) = match member {
+            AccountSetMemberId::Account(id) => (uuid::Uuid::from(id), false),
+            AccountSetMemberId::AccountSet(id) => (uuid::Uuid::from(id), true),
+        };
+
+        let rows = sqlx::query!(
+            r#"
+            WITH RECURSIVE parents AS (
+              SELECT m.account_set_id AS id, 1 AS depth, ARRAY[]::uuid[] AS trail
+              FROM cala_account_set_member_accounts m
+              WHERE $2 IS FALSE AND m.member_account_id = $1 AND m.transitive IS FALSE
+              UNION ALL
+              SELECT m.account_set_id, 1, ARRAY[]::uuid[]
+              FROM cala_account_set_member_account_sets m
+              WHERE $2 IS TRUE AND m.member_account_set_id = $1
+              UNION ALL
+              SELECT m.account_set_id, p.depth + 1, p.trail || p.id
+              FROM parents p
+              JOIN cala_account_set_member_account_sets m ON m.member_account_set_id = p.id
+              WHERE NOT m.account_set_id = ANY(p.trail || p.id) AND p.depth < $5
+            )
+            SELECT
+              id AS "id!",
+              depth AS "depth!",
+              trail AS "path!: Vec<uuid::Uuid>",
+              EXISTS (
+                SELECT 1
+                FROM parents p
+                JOIN cala_account_set_member_account_sets m ON m.member_account_set_id = p.id
+                WHERE p.depth = $5 AND NOT m.account_set_id = ANY(p.trail || p.id)
+              ) AS "too_deep!"
+            FROM parents
+            WHERE $3::uuid[] IS NULL OR trail || id > $3::uuid[]
+            ORDER BY trail || id
+            LIMIT $4
+          "#,
+            member_id,
+            member_is_set,
+            after.as_deref(),
+            (first + 1) as i64,
+            MAX_HIERARCHY_DEPTH as i32,
+        )
+        .fetch_all(executor)
+        .await?;
+        if rows.first().is_some_and(|row| row.too_deep) {
+            return Err(AccountSetError::HierarchyTooDeep(MAX_HIERARCHY_DEPTH));
+        }
+
+        let has_next_page = rows.len() > first;
+        let nodes: Vec<_> = rows
+            .into_iter()
+            .take(first)
+            .map(|row| AccountSetHierarchyNode {
+                id: AccountSetMemberId::AccountSet(AccountSetId::from(row.id)),
+                depth: row.depth as u32,
+                path: row.path.into_iter().map(AccountSetId::from).collect(),
+            })
+            .collect();
+        let end_cursor = nodes.last().map(AccountSetHierarchyCursor::from);
+
+        Ok(es_entity::PaginatedQueryRet {
+            entities: nodes,
+            has_next_page,
+            end_cursor,
+        })
+    }
+
     pub async fn add_member_account_and_return_parents(
         &self,
         db: &mut Transaction<'_, Postgres>,
@@ -653,6 +928,269 @@ impl AccountSetRepo {
         Ok((time.expect("time not set"), ret))
     }
 
+    /// Takes the membership lock and returns the current time together with the
+    /// (transitive) parents of the account set.
+    pub async fn lock_and_find_parents(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        account_set_id: AccountSetId,
+    ) -> Result<(DateTime<Utc>, Vec<AccountSetId>), AccountSetError> {
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", ADDVISORY_LOCK_ID)
+            .execute(&mut **db)
+            .await?;
+        let row = sqlx::query!(
+            r#"
+          WITH RECURSIVE parents AS (
+            SELECT m.member_account_set_id, m.account_set_id
+            FROM cala_account_set_member_account_sets m
+            JOIN cala_account_sets s
+            ON s.id = m.account_set_id
+            WHERE m.member_account_set_id = $1
+
+            UNION ALL
+            SELECT p.member_account_set_id, m.account_set_id
+            FROM parents p
+            JOIN cala_account_set_member_account_sets m
+                ON p.account_set_id = m.member_account_set_id
+          )
+          SELECT NOW() AS "now!", ARRAY(SELECT account_set_id FROM parents) AS "parents!"
+          "#,
+            account_set_id as AccountSetId,
+        )
+        .fetch_one(&mut **db)
+        .await?;
+        Ok((
+            row.now,
+            row.parents.into_iter().map(AccountSetId::from).collect(),
+        ))
+    }
+
+    /// Expects the parents as returned by [`lock_and_find_parents`](Self::lock_and_find_parents).
+    pub async fn add_member_accounts(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        account_set_id: AccountSetId,
+        account_ids: &[AccountId],
+        parents: &[AccountSetId],
+    ) -> Result<(), AccountSetError> {
+        sqlx::query!(
+            r#"
+          WITH non_transitive_insert AS (
+            INSERT INTO cala_account_set_member_accounts (account_set_id, member_account_id)
+            SELECT $1, UNNEST($2::uuid[])
+          )
+          INSERT INTO cala_account_set_member_accounts (account_set_id, member_account_id, transitive)
+          SELECT p.id, a.id, TRUE
+          FROM UNNEST($3::uuid[]) AS p(id)
+          CROSS JOIN UNNEST($2::uuid[]) AS a(id)
+          "#,
+            account_set_id as AccountSetId,
+            account_ids as &[AccountId],
+            parents as &[AccountSetId],
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+
+    /// Expects the parents as returned by [`lock_and_find_parents`](Self::lock_and_find_parents).
+    pub async fn remove_member_accounts(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        account_set_id: AccountSetId,
+        account_ids: &[AccountId],
+        parents: &[AccountSetId],
+    ) -> Result<(), AccountSetError> {
+        sqlx::query!(
+            r#"
+          DELETE FROM cala_account_set_member_accounts
+          WHERE (account_set_id = $1 OR account_set_id = ANY($3))
+          AND member_account_id = ANY($2)
+          "#,
+            account_set_id as AccountSetId,
+            account_ids as &[AccountId],
+            parents as &[AccountSetId],
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+
+    /// Expects the parents as returned by [`lock_and_find_parents`](Self::lock_and_find_parents).
+    pub async fn add_member_sets(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        account_set_id: AccountSetId,
+        member_account_set_ids: &[AccountSetId],
+        parents: &[AccountSetId],
+    ) -> Result<(), AccountSetError> {
+        sqlx::query!(
+            r#"
+          WITH set_insert AS (
+            INSERT INTO cala_account_set_member_account_sets (account_set_id, member_account_set_id)
+            SELECT $1, UNNEST($2::uuid[])
+          ),
+          new_members AS (
+            INSERT INTO cala_account_set_member_accounts (account_set_id, member_account_id, transitive)
+            SELECT $1, m.member_account_id, TRUE
+            FROM cala_account_set_member_accounts m
+            WHERE m.account_set_id = ANY($2)
+            RETURNING member_account_id
+          )
+          INSERT INTO cala_account_set_member_accounts (account_set_id, member_account_id, transitive)
+          SELECT p.id, n.member_account_id, TRUE
+          FROM UNNEST($3::uuid[]) AS p(id)
+          CROSS JOIN new_members n
+          "#,
+            account_set_id as AccountSetId,
+            member_account_set_ids as &[AccountSetId],
+            parents as &[AccountSetId],
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+
+    /// Expects the parents as returned by [`lock_and_find_parents`](Self::lock_and_find_parents).
+    pub async fn remove_member_sets(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        account_set_id: AccountSetId,
+        member_account_set_ids: &[AccountSetId],
+        parents: &[AccountSetId],
+    ) -> Result<(), AccountSetError> {
+        sqlx::query!(
+            r#"
+          WITH member_accounts_deletion AS (
+            DELETE FROM cala_account_set_member_accounts
+            WHERE (account_set_id = $1 OR account_set_id = ANY($3))
+            AND member_account_id IN (SELECT member_account_id FROM cala_account_set_member_accounts
+                                      WHERE account_set_id = ANY($2))
+          )
+          DELETE FROM cala_account_set_member_account_sets
+          WHERE (account_set_id = $1 OR account_set_id = ANY($3))
+          AND member_account_set_id = ANY($2)
+          "#,
+            account_set_id as AccountSetId,
+            member_account_set_ids as &[AccountSetId],
+            parents as &[AccountSetId],
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the account set from all of its parents at once. Returns the current
+    /// time together with the (transitive) ancestors of each direct parent.
+    pub async fn detach_member_set_and_return_ancestors(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        member_account_set_id: AccountSetId,
+    ) -> Result<(DateTime<Utc>, HashMap<AccountSetId, Vec<AccountSetId>>), AccountSetError> {
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", ADDVISORY_LOCK_ID)
+            .execute(&mut **db)
+            .await?;
+        let rows = sqlx::query!(
+            r#"
+          WITH RECURSIVE ancestors AS (
+            SELECT account_set_id AS parent_id, account_set_id
+            FROM cala_account_set_member_account_sets
+            WHERE member_account_set_id = $1
+
+            UNION ALL
+            SELECT a.parent_id, m.account_set_id
+            FROM ancestors a
+            JOIN cala_account_set_member_account_sets m
+                ON a.account_set_id = m.member_account_set_id
+          ),
+          member_accounts_deletion AS (
+            DELETE FROM cala_account_set_member_accounts
+            WHERE account_set_id IN (SELECT account_set_id FROM ancestors)
+            AND member_account_id IN (SELECT member_account_id FROM cala_account_set_member_accounts
+                                      WHERE account_set_id = $1)
+          ),
+          member_account_set_deletion AS (
+            DELETE FROM cala_account_set_member_account_sets
+            WHERE account_set_id IN (SELECT account_set_id FROM ancestors)
+            AND member_account_set_id = $1
+          )
+          SELECT parent_id, account_set_id, NULL AS now
+          FROM ancestors
+          UNION ALL
+          SELECT NULL AS parent_id, NULL AS account_set_id, NOW() AS now
+          "#,
+            member_account_set_id as AccountSetId,
+        )
+        .fetch_all(&mut **db)
+        .await?;
+        let mut time = None;
+        let mut ancestors: HashMap<AccountSetId, Vec<AccountSetId>> = HashMap::new();
+        for row in rows {
+            if let Some(t) = row.now {
+                time = Some(t);
+                continue;
+            }
+            let parent_id = AccountSetId::from(row.parent_id.expect("parent_id not set"));
+            let account_set_id =
+                AccountSetId::from(row.account_set_id.expect("account_set_id not set"));
+            let parent_ancestors = ancestors.entry(parent_id).or_default();
+            if account_set_id != parent_id {
+                parent_ancestors.push(account_set_id);
+            }
+        }
+        Ok((time.expect("time not set"), ancestors))
+    }
+
+    pub async fn find_direct_members_in_tx(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        account_set_id: AccountSetId,
+    ) -> Result<Vec<AccountSetMemberId>, AccountSetError> {
+        let rows = sqlx::query!(
+            r#"
+          SELECT member_account_id AS "member_account_id?", NULL::uuid AS member_account_set_id
+          FROM cala_account_set_member_accounts
+          WHERE account_set_id = $1 AND transitive IS FALSE
+          UNION ALL
+          SELECT NULL::uuid AS member_account_id, member_account_set_id
+          FROM cala_account_set_member_account_sets
+          WHERE account_set_id = $1
+          "#,
+            account_set_id as AccountSetId,
+        )
+        .fetch_all(&mut **db)
+        .await?;
+        let ret = rows
+            .into_iter()
+            .map(
+                |row| match (row.member_account_id, row.member_account_set_id) {
+                    (Some(id), _) => AccountSetMemberId::Account(AccountId::from(id)),
+                    (_, Some(id)) => AccountSetMemberId::AccountSet(AccountSetId::from(id)),
+                    _ => unreachable!("member id not set"),
+                },
+            )
+            .collect();
+        Ok(ret)
+    }
+
+    pub async fn find_direct_parents_in_tx(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        account_set_id: AccountSetId,
+    ) -> Result<Vec<AccountSetId>, AccountSetError> {
+        let rows = sqlx::query!(
+            r#"
+          SELECT account_set_id AS "account_set_id: AccountSetId"
+          FROM cala_account_set_member_account_sets
+          WHERE member_account_set_id = $1
+          "#,
+            account_set_id as AccountSetId,
+        )
+        .fetch_all(&mut **db)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.account_set_id).collect())
+    }
+
     pub async fn find_where_account_is_member(
         &self,
         account_id: AccountId,
@@ -831,11 +1369,15 @@ impl AccountSetRepo {
     ) -> Result<HashMap<AccountId, Vec<AccountSetId>>, AccountSetError> {
         let rows = sqlx::query!(
             r#"
-          SELECT m.account_set_id AS "set_id!: AccountSetId", m.member_account_id AS "account_id!: AccountId"
+          SELECT m.account_set_id AS "set_id!: AccountSetId", m.member_account_id AS "account_id!: AccountId", FALSE AS "archived!"
           FROM cala_account_set_member_accounts m
           JOIN cala_account_sets s
           ON m.account_set_id = s.id AND s.journal_id = $1
           WHERE m.member_account_id = ANY($2)
+          UNION ALL
+          SELECT id, id, TRUE
+          FROM cala_account_sets
+          WHERE id = ANY($2) AND deleted = TRUE
           "#,
             journal_id as JournalId,
             account_ids as &[AccountId]
@@ -844,6 +1386,10 @@ impl AccountSetRepo {
         .await?;
         let mut mappings = HashMap::new();
         for row in rows {
+            // The backing account of an archived set can't be posted to
+            if row.archived {
+                return Err(AccountSetError::Archived(row.set_id));
+            }
             mappings
                 .entry(row.account_id)
                 .or_insert_with(Vec::new)
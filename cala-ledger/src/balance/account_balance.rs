@@ -18,6 +18,10 @@ impl AccountBalance {
         }
     }
 
+    pub fn normal_balance_type(&self) -> DebitOrCredit {
+        self.balance_type
+    }
+
     pub(super) fn derive_diff(mut self, since: &Self) -> Self {
         self.details.settled = BalanceAmount {
             dr_balance: self.details.settled.dr_balance - since.details.settled.dr_balance,
@@ -177,6 +177,17 @@ impl Balances {
             .await
     }
 
+    pub(crate) async fn find_all_balances_for_update(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        journal_id: JournalId,
+        account_ids: &[AccountId],
+    ) -> Result<Vec<BalanceSnapshot>, BalanceError> {
+        self.repo
+            .load_all_for_update_batch(db, journal_id, account_ids)
+            .await
+    }
+
     fn new_snapshots(
         time: DateTime<Utc>,
         mut current_balances: HashMap<(AccountId, Currency), Option<BalanceSnapshot>>,
@@ -348,6 +348,48 @@ impl BalanceRepo {
         Ok(ret)
     }
 
+    pub(crate) async fn load_all_for_update_batch(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        journal_id: JournalId,
+        account_ids: &[AccountId],
+    ) -> Result<Vec<BalanceSnapshot>, BalanceError> {
+        let rows = sqlx::query!(
+            r#"
+            WITH locked_accounts AS (
+              SELECT 1
+              FROM cala_accounts a
+              WHERE a.id = ANY($1)
+              FOR UPDATE
+            ), locked_balances AS (
+              SELECT journal_id, account_id, currency, latest_version
+              FROM cala_current_balances
+              WHERE journal_id = $2
+              AND account_id = ANY($1)
+              FOR UPDATE
+            )
+            SELECT h.values
+            FROM cala_balance_history h
+            JOIN locked_balances b
+            ON b.journal_id = h.journal_id
+              AND b.account_id = h.account_id
+              AND b.currency = h.currency
+              AND b.latest_version = h.version
+        "#,
+            account_ids as &[AccountId],
+            journal_id as JournalId
+        )
+        .fetch_all(&mut **db)
+        .await?;
+        let ret = rows
+            .into_iter()
+            .map(|row| {
+                serde_json::from_value(row.values).expect("Failed to deserialize balance snapshot")
+            })
+            .collect();
+        Ok(ret)
+    }
+
     #[cfg(feature = "import")]
     pub async fn import_balance_update(
         &self,
@@ -1,11 +1,32 @@
 pub use cel_interpreter::CelContext;
+use cel_interpreter::{CelError, CelExtensions, CelType, CelTypeContext};
+
+const CONSTANTS: [&str; 5] = ["SETTLED", "PENDING", "ENCUMBRANCE", "DEBIT", "CREDIT"];
 
 pub(crate) fn initialize() -> CelContext {
     let mut ctx = CelContext::new();
-    ctx.add_variable("SETTLED", "SETTLED");
-    ctx.add_variable("PENDING", "PENDING");
-    ctx.add_variable("ENCUMBRANCE", "ENCUMBRANCE");
-    ctx.add_variable("DEBIT", "DEBIT");
-    ctx.add_variable("CREDIT", "CREDIT");
+    for constant in CONSTANTS {
+        ctx.add_variable(constant, constant);
+    }
+    ctx
+}
+
+pub(crate) fn initialize_types() -> CelTypeContext {
+    let mut ctx = CelTypeContext::new();
+    for constant in CONSTANTS {
+        ctx.add_variable(constant, CelType::String);
+    }
     ctx
 }
+
+/// The base contexts for templates and velocity conditions including the
+/// functions registered by the host application.
+pub(crate) fn initialize_with(
+    extensions: &CelExtensions,
+) -> Result<(CelContext, CelTypeContext), CelError> {
+    let mut ctx = initialize();
+    ctx.extend(extensions)?;
+    let mut types = initialize_types();
+    types.extend(extensions)?;
+    Ok((ctx, types))
+}
@@ -77,6 +77,20 @@ impl TryFromEvents<EntryEvent> for Entry {
     }
 }
 
+/// Criteria for the `list_filtered_for_*` queries on [Entries](crate::entry::Entries).
+/// Every criterion that is set must match.
+#[derive(Debug, Clone, Default)]
+pub struct EntriesFilter {
+    /// Inclusive lower bound on the effective date of the entry's transaction.
+    pub effective_from: Option<chrono::NaiveDate>,
+    /// Inclusive upper bound on the effective date of the entry's transaction.
+    pub effective_until: Option<chrono::NaiveDate>,
+    pub entry_type: Option<String>,
+    pub layer: Option<Layer>,
+    pub currency: Option<Currency>,
+    pub direction: Option<DebitOrCredit>,
+}
+
 #[derive(Builder, Debug)]
 #[allow(dead_code)]
 pub struct NewEntry {
@@ -114,6 +128,10 @@ impl NewEntry {
     pub(super) fn data_source(&self) -> DataSource {
         DataSource::Local
     }
+
+    pub(super) fn currency_code(&self) -> String {
+        self.currency.code().to_string()
+    }
 }
 
 impl IntoEvents<EntryEvent> for NewEntry {
@@ -5,6 +5,7 @@ mod repo;
 use es_entity::EsEntity;
 use sqlx::PgPool;
 use std::collections::HashMap;
+use tracing::instrument;
 
 #[cfg(feature = "import")]
 use crate::primitives::DataSourceId;
@@ -16,8 +17,10 @@ use crate::{
 
 pub use entity::*;
 use error::*;
-pub use repo::entry_cursor::EntriesByCreatedAtCursor;
 use repo::*;
+pub use repo::{
+    effective_cursor::EntriesByEffectiveCursor, entry_cursor::EntriesByCreatedAtCursor,
+};
 
 #[derive(Clone)]
 pub struct Entries {
@@ -75,6 +78,62 @@ impl Entries {
             .await
     }
 
+    #[instrument(
+        name = "cala_ledger.entries.list_filtered_for_account_id",
+        skip(self),
+        err
+    )]
+    pub async fn list_filtered_for_account_id(
+        &self,
+        account_id: AccountId,
+        filter: EntriesFilter,
+        query: es_entity::PaginatedQueryArgs<EntriesByEffectiveCursor>,
+        direction: es_entity::ListDirection,
+    ) -> Result<es_entity::PaginatedQueryRet<Entry, EntriesByEffectiveCursor>, EntryError> {
+        self.repo
+            .list_filtered(EntriesScope::Account(account_id), filter, query, direction)
+            .await
+    }
+
+    #[instrument(
+        name = "cala_ledger.entries.list_filtered_for_account_set_id",
+        skip(self),
+        err
+    )]
+    pub async fn list_filtered_for_account_set_id(
+        &self,
+        account_set_id: AccountSetId,
+        filter: EntriesFilter,
+        query: es_entity::PaginatedQueryArgs<EntriesByEffectiveCursor>,
+        direction: es_entity::ListDirection,
+    ) -> Result<es_entity::PaginatedQueryRet<Entry, EntriesByEffectiveCursor>, EntryError> {
+        self.repo
+            .list_filtered(
+                EntriesScope::AccountSet(account_set_id),
+                filter,
+                query,
+                direction,
+            )
+            .await
+    }
+
+    #[instrument(
+        name = "cala_ledger.entries.list_filtered_for_journal_id",
+        skip(self),
+        err
+    )]
+    pub async fn list_filtered_for_journal_id(
+        &self,
+        journal_id: JournalId,
+        filter: EntriesFilter,
+        query: es_entity::PaginatedQueryArgs<EntriesByEffectiveCursor>,
+        direction: es_entity::ListDirection,
+    ) -> Result<es_entity::PaginatedQueryRet<Entry, EntriesByEffectiveCursor>, EntryError> {
+        self.repo
+            .list_filtered(EntriesScope::Journal(journal_id), filter, query, direction)
+            .await
+    }
+
     pub async fn list_for_transaction_id(
         &self,
         transaction_id: TransactionId,
@@ -1,9 +1,57 @@
-use crate::primitives::{AccountId, AccountSetId, DataSourceId, EntryId, JournalId, TransactionId};
+use crate::primitives::{
+    AccountId, AccountSetId, DataSourceId, DebitOrCredit, EntryId, JournalId, Layer, TransactionId,
+};
 use es_entity::*;
 use sqlx::PgPool;
 
 use super::{entity::*, error::*};
 
+pub mod effective_cursor {
+    use serde::{Deserialize, Serialize};
+
+    use super::{Entry, EntryId};
+
+    /// Entries listed by effective date are ordered by the effective date of their
+    /// transaction, then by creation time. The cursor only carries the id of the last
+    /// entry returned, its position is looked up when resuming.
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct EntriesByEffectiveCursor {
+        pub id: EntryId,
+    }
+
+    impl From<&Entry> for EntriesByEffectiveCursor {
+        fn from(entry: &Entry) -> Self {
+            Self { id: entry.id() }
+        }
+    }
+
+    #[cfg(feature = "graphql")]
+    impl async_graphql::connection::CursorType for EntriesByEffectiveCursor {
+        type Error = String;
+
+        fn encode_cursor(&self) -> String {
+            use base64::{engine::general_purpose, Engine as _};
+            let json = serde_json::to_string(&self).expect("could not serialize token");
+            general_purpose::STANDARD_NO_PAD.encode(json.as_bytes())
+        }
+
+        fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+            use base64::{engine::general_purpose, Engine as _};
+            let bytes = general_purpose::STANDARD_NO_PAD
+                .decode(s.as_bytes())
+                .map_err(|e| e.to_string())?;
+            let json = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+            serde_json::from_str(&json).map_err(|e| e.to_string())
+        }
+    }
+}
+
+pub(super) enum EntriesScope {
+    Account(AccountId),
+    AccountSet(AccountSetId),
+    Journal(JournalId),
+}
+
 #[derive(EsRepo, Debug, Clone)]
 #[es_repo(
     entity = "Entry",
@@ -12,6 +60,14 @@ use super::{entity::*, error::*};
         account_id(ty = "AccountId", list_for, update(persist = false)),
         journal_id(ty = "JournalId", list_for, update(persist = false)),
         transaction_id(ty = "TransactionId", list_for, update(persist = false)),
+        entry_type(ty = "String", update(persist = false)),
+        layer(ty = "Layer", update(persist = false)),
+        currency(
+            ty = "String",
+            create(accessor = "currency_code()"),
+            update(persist = false)
+        ),
+        direction(ty = "DebitOrCredit", update(persist = false)),
         data_source_id(
             ty = "DataSourceId",
             create(accessor = "data_source().into()"),
@@ -102,6 +158,98 @@ impl EntryRepo {
         })
     }
 
+    pub(super) async fn list_filtered(
+        &self,
+        scope: EntriesScope,
+        filter: EntriesFilter,
+        query: es_entity::PaginatedQueryArgs<effective_cursor::EntriesByEffectiveCursor>,
+        direction: es_entity::ListDirection,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<Entry, effective_cursor::EntriesByEffectiveCursor>,
+        EntryError,
+    > {
+        let (account_id, account_set_id, journal_id) = match scope {
+            EntriesScope::Account(id) => (Some(id), None, None),
+            EntriesScope::AccountSet(id) => (None, Some(id), None),
+            EntriesScope::Journal(id) => (None, None, Some(id)),
+        };
+        let after = query.after.map(|c| c.id);
+        let currency = filter.currency.map(|c| c.code());
+
+        let ascending = matches!(direction, es_entity::ListDirection::Ascending);
+
+        let rows = sqlx::query_as!(
+            entry_repo_types::Repo__DbEvent,
+            r#"
+            WITH entries AS (
+              SELECT e.id, t.effective, e.created_at
+              FROM cala_entries e
+              JOIN cala_transactions t ON t.id = e.transaction_id
+              WHERE ($2::uuid IS NULL OR e.account_id = $2)
+              AND ($3::uuid IS NULL OR EXISTS (
+                SELECT 1 FROM cala_balance_history h
+                WHERE h.latest_entry_id = e.id AND h.account_id = $3
+              ))
+              AND ($4::uuid IS NULL OR e.journal_id = $4)
+              AND ($5::date IS NULL OR t.effective >= $5)
+              AND ($6::date IS NULL OR t.effective <= $6)
+              AND ($7::text IS NULL OR e.entry_type = $7)
+              AND ($8::Layer IS NULL OR e.layer = $8)
+              AND ($9::text IS NULL OR e.currency = $9)
+              AND ($10::DebitOrCredit IS NULL OR e.direction = $10)
+              AND ($1::uuid IS NULL OR EXISTS (
+                SELECT 1
+                FROM cala_entries ce
+                JOIN cala_transactions ct ON ct.id = ce.transaction_id
+                WHERE ce.id = $1
+                AND CASE WHEN $12
+                  THEN (t.effective, e.created_at, e.id) > (ct.effective, ce.created_at, ce.id)
+                  ELSE (t.effective, e.created_at, e.id) < (ct.effective, ce.created_at, ce.id)
+                END
+              ))
+              ORDER BY
+                CASE WHEN $12 THEN t.effective END,
+                CASE WHEN $12 THEN e.created_at END,
+                CASE WHEN $12 THEN e.id END,
+                t.effective DESC, e.created_at DESC, e.id DESC
+              LIMIT $11
+            )
+            SELECT e.id AS "entity_id!: EntryId", ev.sequence, ev.event, ev.recorded_at
+            FROM entries e
+            JOIN cala_entry_events ev ON e.id = ev.id
+            ORDER BY
+              CASE WHEN $12 THEN e.effective END,
+              CASE WHEN $12 THEN e.created_at END,
+              CASE WHEN $12 THEN e.id END,
+              e.effective DESC, e.created_at DESC, e.id DESC, ev.sequence
+            "#,
+            after as Option<EntryId>,
+            account_id as Option<AccountId>,
+            account_set_id as Option<AccountSetId>,
+            journal_id as Option<JournalId>,
+            filter.effective_from,
+            filter.effective_until,
+            filter.entry_type,
+            filter.layer as Option<Layer>,
+            currency,
+            filter.direction as Option<DebitOrCredit>,
+            query.first as i64 + 1,
+            ascending
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        let (entities, has_next_page) = EntityEvents::load_n::<Entry>(rows, query.first)?;
+        let end_cursor = entities
+            .last()
+            .map(effective_cursor::EntriesByEffectiveCursor::from);
+        Ok(es_entity::PaginatedQueryRet {
+            entities,
+            has_next_page,
+            end_cursor,
+        })
+    }
+
     #[cfg(feature = "import")]
     pub(super) async fn import(
         &self,
@@ -111,12 +259,17 @@ impl EntryRepo {
     ) -> Result<(), EntryError> {
         let recorded_at = op.now();
         sqlx::query!(
-            r#"INSERT INTO cala_entries (data_source_id, id, journal_id, account_id, created_at)
-            VALUES ($1, $2, $3, $4, $5)"#,
+            r#"INSERT INTO cala_entries (data_source_id, id, journal_id, account_id, transaction_id, entry_type, layer, currency, direction, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
             origin as DataSourceId,
             entry.values().id as EntryId,
             entry.values().journal_id as JournalId,
             entry.values().account_id as AccountId,
+            entry.values().transaction_id as TransactionId,
+            entry.values().entry_type,
+            entry.values().layer as Layer,
+            entry.values().currency.code(),
+            entry.values().direction as DebitOrCredit,
             recorded_at,
         )
         .execute(&mut **op.tx())
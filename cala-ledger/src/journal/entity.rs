@@ -180,7 +180,7 @@ pub struct NewJournal {
     #[builder(setter(strip_option, into), default)]
     pub(super) code: Option<String>,
     #[builder(setter(into), default)]
-    status: Status,
+    pub(super) status: Status,
     #[builder(setter(strip_option, into), default)]
     description: Option<String>,
     #[builder(default)]
@@ -10,11 +10,16 @@ use std::collections::HashMap;
 
 #[cfg(feature = "import")]
 use crate::primitives::DataSourceId;
-use crate::{ledger_operation::*, outbox::*, primitives::DataSource};
+use crate::{
+    ledger_operation::*,
+    outbox::*,
+    primitives::{DataSource, Status},
+};
 
 pub use entity::*;
 use error::*;
 use repo::*;
+pub use repo::{journal_cursor::*, JournalsSortBy};
 
 /// Service for working with `Journal` entities.
 #[derive(Clone)]
@@ -82,6 +87,22 @@ impl Journals {
         Ok(())
     }
 
+    /// Lists journals sorted by name, created_at or id, optionally restricted to a
+    /// single status.
+    #[instrument(name = "cala_ledger.journals.list", skip(self), err)]
+    pub async fn list(
+        &self,
+        status: Option<Status>,
+        sort: es_entity::Sort<JournalsSortBy>,
+        args: es_entity::PaginatedQueryArgs<JournalsCursor>,
+    ) -> Result<es_entity::PaginatedQueryRet<Journal, JournalsCursor>, JournalError> {
+        let filter = match status {
+            Some(status) => FindManyJournals::WithStatus(status),
+            None => FindManyJournals::NoFilter,
+        };
+        self.repo.find_many(filter, sort, args).await
+    }
+
     #[instrument(name = "cala_ledger.journal.find_by_code", skip(self), err)]
     pub async fn find_by_code(&self, code: String) -> Result<Journal, JournalError> {
         self.repo.find_by_code(Some(code)).await
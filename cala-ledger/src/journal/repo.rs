@@ -1,7 +1,7 @@
 use es_entity::*;
 use sqlx::PgPool;
 
-use crate::primitives::DataSourceId;
+use crate::primitives::{DataSourceId, Status};
 
 use super::{entity::*, error::JournalError};
 
@@ -10,8 +10,9 @@ use super::{entity::*, error::JournalError};
     entity = "Journal",
     err = "JournalError",
     columns(
-        name(ty = "String", update(accessor = "values().name")),
+        name(ty = "String", update(accessor = "values().name"), list_by),
         code(ty = "Option<String>", update(accessor = "values().code")),
+        status(ty = "Status", update(accessor = "values().status"), list_for),
         data_source_id(
             ty = "DataSourceId",
             create(accessor = "data_source().into()"),
@@ -38,11 +39,12 @@ impl JournalRepo {
     ) -> Result<(), JournalError> {
         let recorded_at = op.now();
         sqlx::query!(
-            r#"INSERT INTO cala_journals (data_source_id, id, name, created_at)
-            VALUES ($1, $2, $3, $4)"#,
+            r#"INSERT INTO cala_journals (data_source_id, id, name, status, created_at)
+            VALUES ($1, $2, $3, $4, $5)"#,
             origin as DataSourceId,
             journal.values().id as JournalId,
             journal.values().name,
+            journal.values().status as Status,
             recorded_at
         )
         .execute(&mut **op.tx())
@@ -1,6 +1,7 @@
+use cel_interpreter::CelExtensions;
 use derive_builder::Builder;
 
-pub use crate::outbox::server::OutboxServerConfig;
+pub use crate::outbox::{server::OutboxServerConfig, OutboxRetentionConfig};
 
 #[derive(Builder, Debug)]
 #[builder(build_fn(validate = "Self::validate"))]
@@ -15,6 +16,12 @@ pub struct CalaLedgerConfig {
     pub(super) pool: Option<sqlx::PgPool>,
     #[builder(setter(strip_option), default)]
     pub(super) outbox: Option<OutboxServerConfig>,
+    /// Prunes the outbox periodically when set, until the ledger is dropped.
+    #[builder(setter(strip_option), default)]
+    pub(super) outbox_retention: Option<OutboxRetentionConfig>,
+    /// Functions and packages available to tx template and velocity expressions.
+    #[builder(default)]
+    pub(super) cel_extensions: CelExtensions,
 }
 
 impl CalaLedgerConfig {
@@ -2,10 +2,15 @@ use sqlx::error::DatabaseError;
 use thiserror::Error;
 
 use crate::{
-    account::error::AccountError, account_set::error::AccountSetError,
-    balance::error::BalanceError, entry::error::EntryError, journal::error::JournalError,
-    outbox::server::error::OutboxServerError, transaction::error::TransactionError,
-    tx_template::error::TxTemplateError, velocity::error::VelocityError,
+    account::error::AccountError,
+    account_set::error::AccountSetError,
+    balance::error::BalanceError,
+    entry::error::EntryError,
+    journal::error::JournalError,
+    outbox::{error::OutboxError, server::error::OutboxServerError},
+    transaction::error::TransactionError,
+    tx_template::error::TxTemplateError,
+    velocity::error::VelocityError,
 };
 
 #[derive(Error, Debug)]
@@ -20,6 +25,8 @@ pub enum LedgerError {
     ConfigError(String),
     #[error("LedgerError - OutboxServer: {0}")]
     OutboxServer(#[from] OutboxServerError),
+    #[error("LedgerError - OutboxError: {0}")]
+    OutboxError(#[from] OutboxError),
     #[error("LedgerError - AccountError: {0}")]
     AccountError(#[from] AccountError),
     #[error("LedgerError - AccountSetError: {0}")]
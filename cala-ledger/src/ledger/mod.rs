@@ -15,7 +15,10 @@ use crate::{
     entry::Entries,
     journal::Journals,
     ledger_operation::*,
-    outbox::{server, EventSequence, Outbox, OutboxListener},
+    outbox::{
+        server, EventSequence, Outbox, OutboxConsumer, OutboxEventFilter, OutboxListener,
+        OutboxPrunerHandle,
+    },
     primitives::TransactionId,
     transaction::{Transaction, Transactions},
     tx_template::{Params, TxTemplates},
@@ -43,6 +46,7 @@ pub struct CalaLedger {
     outbox: Outbox,
     #[allow(clippy::type_complexity)]
     outbox_handle: Arc<Mutex<Option<tokio::task::JoinHandle<Result<(), LedgerError>>>>>,
+    outbox_pruner: Arc<Mutex<Option<OutboxPrunerHandle>>>,
 }
 
 impl CalaLedger {
@@ -71,14 +75,27 @@ impl CalaLedger {
         if let Some(outbox_config) = config.outbox {
             outbox_handle = Some(Self::start_outbox_server(outbox_config, outbox.clone()));
         }
+        let outbox_pruner = config
+            .outbox_retention
+            .map(|retention_config| outbox.spawn_pruner(retention_config));
+
+        let (cel_context, cel_types) = crate::cel_context::initialize_with(&config.cel_extensions)
+            .map_err(|e| {
+                LedgerError::ConfigError(format!("Could not register cel extensions: {e}"))
+            })?;
 
         let accounts = Accounts::new(&pool, outbox.clone());
         let journals = Journals::new(&pool, outbox.clone());
-        let tx_templates = TxTemplates::new(&pool, outbox.clone());
+        let tx_templates = TxTemplates::new(
+            &pool,
+            outbox.clone(),
+            cel_context.clone(),
+            cel_types.clone(),
+        );
         let transactions = Transactions::new(&pool, outbox.clone());
         let entries = Entries::new(&pool, outbox.clone());
         let balances = Balances::new(&pool, outbox.clone(), &journals);
-        let velocities = Velocities::new(&pool, outbox.clone());
+        let velocities = Velocities::new(&pool, outbox.clone(), cel_context, cel_types);
         let account_sets = AccountSets::new(&pool, outbox.clone(), &accounts, &entries, &balances);
         Ok(Self {
             accounts,
@@ -91,6 +108,7 @@ impl CalaLedger {
             balances,
             velocities,
             outbox_handle: Arc::new(Mutex::new(outbox_handle)),
+            outbox_pruner: Arc::new(Mutex::new(outbox_pruner)),
             pool,
         })
     }
@@ -226,7 +244,76 @@ impl CalaLedger {
         &self,
         start_after: Option<EventSequence>,
     ) -> Result<OutboxListener, LedgerError> {
-        Ok(self.outbox.register_listener(start_after).await?)
+        self.register_filtered_outbox_listener(start_after, OutboxEventFilter::default())
+            .await
+    }
+
+    /// Like [register_outbox_listener](Self::register_outbox_listener) but only
+    /// returns the events matching the filter.
+    pub async fn register_filtered_outbox_listener(
+        &self,
+        start_after: Option<EventSequence>,
+        filter: OutboxEventFilter,
+    ) -> Result<OutboxListener, LedgerError> {
+        Ok(self.outbox.register_listener(start_after, filter).await?)
+    }
+
+    /// Registers a listener for a durable, named consumer. The listener resumes
+    /// after the last sequence acknowledged via
+    /// [ack_outbox_consumer](Self::ack_outbox_consumer). A new consumer starts
+    /// at the latest known sequence.
+    pub async fn register_outbox_consumer(
+        &self,
+        name: &str,
+        filter: OutboxEventFilter,
+    ) -> Result<OutboxListener, LedgerError> {
+        Ok(self.outbox.register_consumer(name, None, filter).await?)
+    }
+
+    /// Creates a durable consumer at the latest known sequence within `op`.
+    /// Listeners registered via [register_outbox_consumer](Self::register_outbox_consumer)
+    /// after the operation has been committed resume from there.
+    pub async fn create_outbox_consumer_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        name: &str,
+    ) -> Result<(), LedgerError> {
+        Ok(self.outbox.create_consumer_in_tx(op.tx(), name).await?)
+    }
+
+    pub async fn ack_outbox_consumer(
+        &self,
+        name: &str,
+        sequence: EventSequence,
+    ) -> Result<OutboxConsumer, LedgerError> {
+        Ok(self.outbox.ack_consumer(name, sequence).await?)
+    }
+
+    pub async fn find_outbox_consumer(&self, name: &str) -> Result<OutboxConsumer, LedgerError> {
+        Ok(self.outbox.find_consumer(name).await?)
+    }
+
+    pub async fn list_outbox_consumers(&self) -> Result<Vec<OutboxConsumer>, LedgerError> {
+        Ok(self.outbox.list_consumers().await?)
+    }
+
+    pub async fn delete_outbox_consumer(&self, name: &str) -> Result<(), LedgerError> {
+        Ok(self.outbox.delete_consumer(name).await?)
+    }
+
+    pub async fn delete_outbox_consumer_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        name: &str,
+    ) -> Result<(), LedgerError> {
+        Ok(self.outbox.delete_consumer_in_tx(op.tx(), name).await?)
+    }
+
+    /// Prunes the outbox once, independently of the configured retention.
+    /// Returns the number of deleted events.
+    #[instrument(name = "cala_ledger.prune_outbox", skip(self), err)]
+    pub async fn prune_outbox(&self, config: &OutboxRetentionConfig) -> Result<u64, LedgerError> {
+        Ok(self.outbox.prune(config).await?)
     }
 
     #[cfg(feature = "import")]
@@ -271,6 +358,12 @@ impl CalaLedger {
                     .sync_account_set_update(op, account_set, fields)
                     .await?
             }
+            AccountSetArchived { account_set, .. } => {
+                let op = es_entity::DbOp::new(db, event.recorded_at);
+                self.account_sets
+                    .sync_account_set_archival(op, account_set)
+                    .await?
+            }
             AccountSetMemberCreated {
                 account_set_id,
                 member_id,
@@ -291,6 +384,26 @@ impl CalaLedger {
                     .sync_account_set_member_removal(op, origin, account_set_id, member_id)
                     .await?
             }
+            AccountSetMembersCreated {
+                account_set_id,
+                member_ids,
+                ..
+            } => {
+                let op = es_entity::DbOp::new(db, event.recorded_at);
+                self.account_sets
+                    .sync_account_set_members_creation(op, origin, account_set_id, member_ids)
+                    .await?
+            }
+            AccountSetMembersRemoved {
+                account_set_id,
+                member_ids,
+                ..
+            } => {
+                let op = es_entity::DbOp::new(db, event.recorded_at);
+                self.account_sets
+                    .sync_account_set_members_removal(op, origin, account_set_id, member_ids)
+                    .await?
+            }
             JournalCreated { journal, .. } => {
                 let op = es_entity::DbOp::new(db, event.recorded_at);
                 self.journals
@@ -311,6 +424,16 @@ impl CalaLedger {
                     .sync_transaction_creation(op, origin, transaction)
                     .await?
             }
+            TransactionAnnotated {
+                transaction,
+                annotation,
+                ..
+            } => {
+                let op = es_entity::DbOp::new(db, event.recorded_at);
+                self.transactions
+                    .sync_transaction_annotation(op, transaction.id, annotation)
+                    .await?
+            }
             TxTemplateCreated { tx_template, .. } => {
                 let op = es_entity::DbOp::new(db, event.recorded_at);
                 self.tx_templates
@@ -347,6 +470,7 @@ impl CalaLedger {
         if let Some(handle) = self.outbox_handle.lock().expect("poisened mutex").take() {
             handle.abort();
         }
+        self.outbox_pruner.lock().expect("poisened mutex").take();
         Ok(())
     }
 
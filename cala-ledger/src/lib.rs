@@ -148,6 +148,12 @@ pub mod primitives {
     pub use cala_types::primitives::*;
 }
 
+pub mod cel {
+    pub use cel_interpreter::{
+        CelError, CelExtensions, CelPackage, CelSignature, CelType, CelValue,
+    };
+}
+
 pub use es_entity;
 
 pub mod query {
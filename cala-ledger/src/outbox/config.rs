@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use std::time::Duration;
+
+/// Controls which events are pruned from `cala_outbox_events`.
+/// An event is only pruned once it is outside of every configured limit
+/// and has been acknowledged by all durable consumers.
+/// Without `max_age` or `max_events` nothing is pruned.
+#[serde_with::serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OutboxRetentionConfig {
+    /// Keep events recorded within this period.
+    #[serde_as(as = "Option<serde_with::DurationSeconds<u64>>")]
+    #[serde(default)]
+    pub max_age: Option<Duration>,
+    /// Keep this many of the most recent events.
+    #[serde(default)]
+    pub max_events: Option<u64>,
+    /// Number of events deleted per transaction.
+    #[serde(default = "default_batch_size")]
+    pub batch_size: u32,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[serde(default = "default_interval")]
+    pub interval: Duration,
+    /// Copy pruned events to `cala_outbox_events_archive` before deleting them.
+    #[serde(default)]
+    pub archive: bool,
+    /// Stop holding back pruning for durable consumers that haven't
+    /// acknowledged an event within this period.
+    #[serde_as(as = "Option<serde_with::DurationSeconds<u64>>")]
+    #[serde(default)]
+    pub consumer_expiry: Option<Duration>,
+}
+
+impl Default for OutboxRetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age: None,
+            max_events: None,
+            batch_size: default_batch_size(),
+            interval: default_interval(),
+            archive: false,
+            consumer_expiry: None,
+        }
+    }
+}
+
+fn default_batch_size() -> u32 {
+    1000
+}
+
+fn default_interval() -> Duration {
+    Duration::from_secs(60)
+}
@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+use super::EventSequence;
+
+#[derive(Error, Debug)]
+pub enum OutboxError {
+    #[error("OutboxError - Sqlx: {0}")]
+    Sqlx(#[from] sqlx::Error),
+    #[error("OutboxError - InvalidConsumerName: '{0}'")]
+    InvalidConsumerName(String),
+    #[error("OutboxError - ConsumerNotFound: {0}")]
+    ConsumerNotFound(String),
+    #[error("OutboxError - SequenceNoLongerAvailable: cannot resume after {start_after}, events through {pruned_through} have been pruned")]
+    SequenceNoLongerAvailable {
+        start_after: EventSequence,
+        pruned_through: EventSequence,
+    },
+    #[error("OutboxError - SequenceNotYetKnown: cannot ack {sequence}, the highest known sequence is {highest_known}")]
+    SequenceNotYetKnown {
+        sequence: EventSequence,
+        highest_known: EventSequence,
+    },
+}
@@ -4,7 +4,7 @@ use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
 use std::{collections::BTreeMap, pin::Pin, task::Poll};
 
-use super::{event::*, repo::*};
+use super::{error::OutboxError, event::*, repo::*};
 
 pub struct OutboxListener {
     repo: OutboxRepo,
@@ -12,8 +12,10 @@ pub struct OutboxListener {
     latest_known: EventSequence,
     event_receiver: Pin<Box<BroadcastStream<OutboxEvent>>>,
     buffer_size: usize,
+    filter: OutboxEventFilter,
     cache: BTreeMap<EventSequence, OutboxEvent>,
-    next_page_handle: Option<JoinHandle<Result<Vec<OutboxEvent>, sqlx::Error>>>,
+    next_page_handle: Option<JoinHandle<Result<Vec<OutboxEvent>, OutboxError>>>,
+    terminated: bool,
 }
 
 impl OutboxListener {
@@ -23,6 +25,7 @@ impl OutboxListener {
         start_after: EventSequence,
         latest_known: EventSequence,
         buffer: usize,
+        filter: OutboxEventFilter,
     ) -> Self {
         Self {
             repo,
@@ -32,6 +35,8 @@ impl OutboxListener {
             cache: BTreeMap::new(),
             next_page_handle: None,
             buffer_size: buffer,
+            filter,
+            terminated: false,
         }
     }
 
@@ -48,12 +53,15 @@ impl OutboxListener {
 }
 
 impl Stream for OutboxListener {
-    type Item = OutboxEvent;
+    type Item = Result<OutboxEvent, OutboxError>;
 
     fn poll_next(
         mut self: Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
         // Poll page if present
         if let Some(fetch) = self.next_page_handle.as_mut() {
             match fetch.poll_unpin(cx) {
@@ -63,6 +71,11 @@ impl Stream for OutboxListener {
                     }
                     self.next_page_handle = None;
                 }
+                Poll::Ready(Ok(Err(e @ OutboxError::SequenceNoLongerAvailable { .. }))) => {
+                    self.next_page_handle = None;
+                    self.terminated = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
                 Poll::Ready(_) => {
                     self.next_page_handle = None;
                 }
@@ -95,7 +108,12 @@ impl Stream for OutboxListener {
                 if let Some(handle) = self.next_page_handle.take() {
                     handle.abort();
                 }
-                return Poll::Ready(Some(event));
+                // Filtered events still advance the sequence so that
+                // the listener keeps waiting for the next one in order
+                if !self.filter.matches(&event.payload) {
+                    continue;
+                }
+                return Poll::Ready(Some(Ok(event)));
             }
             self.cache.insert(seq, event);
             break;
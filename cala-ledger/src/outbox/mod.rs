@@ -1,3 +1,5 @@
+mod config;
+pub mod error;
 mod listener;
 mod repo;
 pub mod server;
@@ -15,6 +17,8 @@ use std::sync::{
 };
 use tokio::sync::broadcast;
 
+pub use config::*;
+use error::*;
 pub use event::*;
 pub use listener::*;
 use repo::*;
@@ -85,19 +89,166 @@ impl Outbox {
     pub async fn register_listener(
         &self,
         start_after: Option<EventSequence>,
-    ) -> Result<OutboxListener, sqlx::Error> {
+        filter: OutboxEventFilter,
+    ) -> Result<OutboxListener, OutboxError> {
         let sub = self.event_receiver.resubscribe();
         let latest_known = EventSequence::from(self.highest_known_sequence.load(Ordering::Relaxed));
         let start = start_after.unwrap_or(latest_known);
+        let pruned_through = self.repo.pruned_through().await?;
+        if start < pruned_through {
+            return Err(OutboxError::SequenceNoLongerAvailable {
+                start_after: start,
+                pruned_through,
+            });
+        }
         Ok(OutboxListener::new(
             self.repo.clone(),
             sub,
             start,
             latest_known,
             self.buffer_size,
+            filter,
         ))
     }
 
+    /// Registers a listener for a named consumer. Unless `start_after` is given
+    /// the listener resumes after the last sequence acknowledged by the consumer.
+    /// A consumer that doesn't exist yet is created at `start_after` or,
+    /// if not given, at the latest known sequence.
+    pub async fn register_consumer(
+        &self,
+        name: &str,
+        start_after: Option<EventSequence>,
+        filter: OutboxEventFilter,
+    ) -> Result<OutboxListener, OutboxError> {
+        validate_consumer_name(name)?;
+        let latest_known = EventSequence::from(self.highest_known_sequence.load(Ordering::Relaxed));
+        let last_acked = self
+            .repo
+            .create_consumer_if_not_exists(name, start_after.unwrap_or(latest_known))
+            .await?;
+        self.register_listener(Some(start_after.unwrap_or(last_acked)), filter)
+            .await
+    }
+
+    /// Creates a consumer at the latest known sequence as part of `db`
+    /// so that it only exists once the transaction commits.
+    pub(crate) async fn create_consumer_in_tx(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        name: &str,
+    ) -> Result<(), OutboxError> {
+        validate_consumer_name(name)?;
+        let latest_known = EventSequence::from(self.highest_known_sequence.load(Ordering::Relaxed));
+        self.repo
+            .create_consumer_in_tx(db, name, latest_known)
+            .await?;
+        Ok(())
+    }
+
+    /// Acknowledges all events up to and including `sequence`.
+    /// Acknowledging a sequence before the current position is a no-op,
+    /// acknowledging one that hasn't been persisted yet is an error.
+    pub async fn ack_consumer(
+        &self,
+        name: &str,
+        sequence: EventSequence,
+    ) -> Result<OutboxConsumer, OutboxError> {
+        let latest_known = EventSequence::from(self.highest_known_sequence.load(Ordering::Relaxed));
+        if sequence > latest_known {
+            // Events persisted by other instances may not have been received yet
+            let highest_known = self.repo.highest_known_sequence().await?;
+            if sequence > highest_known {
+                return Err(OutboxError::SequenceNotYetKnown {
+                    sequence,
+                    highest_known,
+                });
+            }
+        }
+        self.repo
+            .ack_consumer(name, sequence)
+            .await?
+            .ok_or_else(|| OutboxError::ConsumerNotFound(name.to_string()))
+    }
+
+    pub async fn find_consumer(&self, name: &str) -> Result<OutboxConsumer, OutboxError> {
+        self.repo
+            .list_consumers(Some(name))
+            .await?
+            .pop()
+            .ok_or_else(|| OutboxError::ConsumerNotFound(name.to_string()))
+    }
+
+    pub async fn list_consumers(&self) -> Result<Vec<OutboxConsumer>, OutboxError> {
+        Ok(self.repo.list_consumers(None).await?)
+    }
+
+    pub async fn delete_consumer(&self, name: &str) -> Result<(), OutboxError> {
+        if !self.repo.delete_consumer(name).await? {
+            return Err(OutboxError::ConsumerNotFound(name.to_string()));
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn delete_consumer_in_tx(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        name: &str,
+    ) -> Result<(), OutboxError> {
+        if !self.repo.delete_consumer_in_tx(db, name).await? {
+            return Err(OutboxError::ConsumerNotFound(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Deletes the events that are no longer retained in batches.
+    /// Listeners positioned before the pruned events will fail with
+    /// [OutboxError::SequenceNoLongerAvailable].
+    pub async fn prune(&self, config: &OutboxRetentionConfig) -> Result<u64, OutboxError> {
+        let since = |period: std::time::Duration| {
+            chrono::Duration::from_std(period)
+                .ok()
+                .and_then(|period| Utc::now().checked_sub_signed(period))
+                .unwrap_or(DateTime::<Utc>::MIN_UTC)
+        };
+        let Some(cutoff) = self
+            .repo
+            .prune_cutoff(
+                config.max_age.map(since),
+                config.max_events,
+                config.consumer_expiry.map(since),
+            )
+            .await?
+        else {
+            return Ok(0);
+        };
+        self.repo.advance_pruned_through(cutoff).await?;
+        let mut pruned = 0;
+        loop {
+            let n = self
+                .repo
+                .prune_batch(cutoff, config.batch_size, config.archive)
+                .await?;
+            pruned += n;
+            if n < u64::from(config.batch_size) {
+                break;
+            }
+        }
+        Ok(pruned)
+    }
+
+    pub(crate) fn spawn_pruner(&self, config: OutboxRetentionConfig) -> OutboxPrunerHandle {
+        let outbox = self.clone();
+        OutboxPrunerHandle(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(config.interval).await;
+                if let Err(e) = outbox.prune(&config).await {
+                    tracing::error!(error = %e, "Error pruning outbox");
+                }
+            }
+        }))
+    }
+
     async fn spawn_pg_listener(
         pool: &PgPool,
         sender: broadcast::Sender<OutboxEvent>,
@@ -121,3 +272,19 @@ impl Outbox {
         Ok(())
     }
 }
+
+/// Stops the pruning loop when dropped.
+pub(crate) struct OutboxPrunerHandle(tokio::task::JoinHandle<()>);
+
+impl Drop for OutboxPrunerHandle {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn validate_consumer_name(name: &str) -> Result<(), OutboxError> {
+    if name.trim().is_empty() || name.trim() != name {
+        return Err(OutboxError::InvalidConsumerName(name.to_string()));
+    }
+    Ok(())
+}
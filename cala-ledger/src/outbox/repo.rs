@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use sqlx::{PgPool, Postgres, QueryBuilder, Row, Transaction};
 
-use super::event::*;
+use super::{error::OutboxError, event::*};
 
 #[derive(Clone)]
 pub(super) struct OutboxRepo {
@@ -14,13 +14,121 @@ impl OutboxRepo {
     }
 
     pub async fn highest_known_sequence(&self) -> Result<EventSequence, sqlx::Error> {
-        let row =
-            sqlx::query!(r#"SELECT COALESCE(MAX(sequence), 0) AS "max!" FROM cala_outbox_events"#)
-                .fetch_one(&self.pool)
-                .await?;
+        let row = sqlx::query!(
+            r#"SELECT GREATEST(
+                 (SELECT COALESCE(MAX(sequence), 0) FROM cala_outbox_events),
+                 (SELECT pruned_through FROM cala_outbox_retention)
+               ) AS "max!""#
+        )
+        .fetch_one(&self.pool)
+        .await?;
         Ok(EventSequence::from(row.max as u64))
     }
 
+    pub async fn pruned_through(&self) -> Result<EventSequence, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT pruned_through AS "pruned_through: EventSequence" FROM cala_outbox_retention"#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.pruned_through)
+    }
+
+    /// The highest sequence that may be pruned given the retention limits
+    /// and the position of the slowest durable consumer that acknowledged
+    /// after `consumers_acked_after`.
+    pub async fn prune_cutoff(
+        &self,
+        retain_recorded_after: Option<DateTime<Utc>>,
+        retain_events: Option<u64>,
+        consumers_acked_after: Option<DateTime<Utc>>,
+    ) -> Result<Option<EventSequence>, sqlx::Error> {
+        if retain_recorded_after.is_none() && retain_events.is_none() {
+            return Ok(None);
+        }
+        let row = sqlx::query!(
+            r#"
+            SELECT
+              GREATEST(
+                (SELECT COALESCE(MAX(sequence), 0) FROM cala_outbox_events),
+                r.pruned_through
+              ) AS "max!",
+              (SELECT MIN(sequence) FROM cala_outbox_events WHERE recorded_at >= $1) AS first_retained,
+              (
+                SELECT MIN(last_acked_sequence) FROM cala_outbox_consumers
+                WHERE $2::timestamptz IS NULL OR modified_at >= $2
+              ) AS slowest_consumer
+            FROM cala_outbox_retention r"#,
+            retain_recorded_after,
+            consumers_acked_after,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        let mut cutoff = row.max;
+        if let Some(retain_events) = retain_events {
+            cutoff = cutoff.min(row.max.saturating_sub(retain_events as i64));
+        }
+        if retain_recorded_after.is_some() {
+            if let Some(first_retained) = row.first_retained {
+                cutoff = cutoff.min(first_retained - 1);
+            }
+        }
+        if let Some(slowest_consumer) = row.slowest_consumer {
+            cutoff = cutoff.min(slowest_consumer);
+        }
+        Ok(Some(EventSequence::from(cutoff.max(0) as u64)))
+    }
+
+    pub async fn advance_pruned_through(&self, cutoff: EventSequence) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE cala_outbox_retention
+            SET pruned_through = $1, modified_at = NOW()
+            WHERE pruned_through < $1"#,
+            cutoff as EventSequence,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Deletes (and optionally archives) up to `batch_size` events at or below the cutoff.
+    /// Rows locked by a concurrent prune are skipped.
+    pub async fn prune_batch(
+        &self,
+        cutoff: EventSequence,
+        batch_size: u32,
+        archive: bool,
+    ) -> Result<u64, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            WITH batch AS (
+              SELECT sequence FROM cala_outbox_events
+              WHERE sequence <= $1
+              ORDER BY sequence
+              LIMIT $2
+              FOR UPDATE SKIP LOCKED
+            ), deleted AS (
+              DELETE FROM cala_outbox_events e
+              USING batch b
+              WHERE e.sequence = b.sequence
+              RETURNING e.id, e.sequence, e.payload, e.recorded_at, e.seen_at
+            ), archived AS (
+              INSERT INTO cala_outbox_events_archive (id, sequence, payload, recorded_at, seen_at)
+              SELECT id, sequence, payload, recorded_at, seen_at FROM deleted
+              WHERE $3
+              ON CONFLICT (sequence) DO NOTHING
+            )
+            SELECT COUNT(*) AS "count!" FROM deleted"#,
+            cutoff as EventSequence,
+            batch_size as i64,
+            archive,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.count as u64)
+    }
+
     pub async fn persist_events(
         &self,
         db: &mut Transaction<'_, Postgres>,
@@ -48,7 +156,7 @@ impl OutboxRepo {
         let rows = query.fetch_all(&mut **db).await?;
         let events = rows
             .into_iter()
-            .zip(payloads.into_iter())
+            .zip(payloads)
             .map(|(row, payload)| OutboxEvent {
                 id: row.get::<OutboxEventId, _>("id"),
                 sequence: row.get("sequence"),
@@ -59,11 +167,138 @@ impl OutboxRepo {
         Ok(events)
     }
 
+    pub async fn create_consumer_if_not_exists(
+        &self,
+        name: &str,
+        start_after: EventSequence,
+    ) -> Result<EventSequence, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            WITH inserted AS (
+              INSERT INTO cala_outbox_consumers (name, last_acked_sequence)
+              VALUES ($1, $2)
+              ON CONFLICT (name) DO NOTHING
+              RETURNING last_acked_sequence
+            )
+            SELECT last_acked_sequence AS "last_acked_sequence!: EventSequence" FROM inserted
+            UNION ALL
+            SELECT last_acked_sequence FROM cala_outbox_consumers WHERE name = $1
+            LIMIT 1"#,
+            name,
+            start_after as EventSequence,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.last_acked_sequence)
+    }
+
+    pub async fn create_consumer_in_tx(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        name: &str,
+        start_after: EventSequence,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO cala_outbox_consumers (name, last_acked_sequence)
+            VALUES ($1, $2)
+            ON CONFLICT (name) DO NOTHING"#,
+            name,
+            start_after as EventSequence,
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn ack_consumer(
+        &self,
+        name: &str,
+        sequence: EventSequence,
+    ) -> Result<Option<OutboxConsumer>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"
+            WITH updated AS (
+              UPDATE cala_outbox_consumers
+              SET last_acked_sequence = GREATEST(last_acked_sequence, $2), modified_at = NOW()
+              WHERE name = $1
+              RETURNING name, last_acked_sequence, created_at, modified_at
+            )
+            SELECT
+              u.name,
+              u.last_acked_sequence AS "last_acked_sequence!: EventSequence",
+              u.created_at,
+              u.modified_at,
+              (SELECT COALESCE(MAX(sequence), 0) FROM cala_outbox_events) AS "max!"
+            FROM updated u"#,
+            name,
+            sequence as EventSequence,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|row| OutboxConsumer {
+            lag: lag(row.max, row.last_acked_sequence),
+            name: row.name,
+            last_acked_sequence: row.last_acked_sequence,
+            created_at: row.created_at,
+            modified_at: row.modified_at,
+        }))
+    }
+
+    pub async fn list_consumers(
+        &self,
+        name: Option<&str>,
+    ) -> Result<Vec<OutboxConsumer>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+              name,
+              last_acked_sequence AS "last_acked_sequence!: EventSequence",
+              created_at,
+              modified_at,
+              (SELECT COALESCE(MAX(sequence), 0) FROM cala_outbox_events) AS "max!"
+            FROM cala_outbox_consumers
+            WHERE $1::VARCHAR IS NULL OR name = $1
+            ORDER BY name"#,
+            name,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| OutboxConsumer {
+                lag: lag(row.max, row.last_acked_sequence),
+                name: row.name,
+                last_acked_sequence: row.last_acked_sequence,
+                created_at: row.created_at,
+                modified_at: row.modified_at,
+            })
+            .collect())
+    }
+
+    pub async fn delete_consumer(&self, name: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM cala_outbox_consumers WHERE name = $1", name)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete_consumer_in_tx(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        name: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM cala_outbox_consumers WHERE name = $1", name)
+            .execute(&mut **db)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn load_next_page(
         &self,
         from_sequence: EventSequence,
         buffer_size: usize,
-    ) -> Result<Vec<OutboxEvent>, sqlx::Error> {
+    ) -> Result<Vec<OutboxEvent>, OutboxError> {
         let rows = sqlx::query!(
             r#"
             WITH max_sequence AS (
@@ -90,6 +325,15 @@ impl OutboxRepo {
         )
         .fetch_all(&self.pool)
         .await?;
+        // Checked after fetching as the watermark is advanced before a prune
+        // deletes rows - a page read concurrently with a prune is never served
+        let pruned_through = self.pruned_through().await?;
+        if from_sequence < pruned_through {
+            return Err(OutboxError::SequenceNoLongerAvailable {
+                start_after: from_sequence,
+                pruned_through,
+            });
+        }
         let mut events = Vec::new();
         let mut empty_ids = Vec::new();
         for row in rows {
@@ -112,7 +356,8 @@ impl OutboxRepo {
             let rows = sqlx::query!(
                 r#"
                 INSERT INTO cala_outbox_events (sequence)
-                SELECT unnest($1::bigint[]) AS sequence
+                SELECT seq FROM unnest($1::bigint[]) AS seq
+                WHERE seq > (SELECT pruned_through FROM cala_outbox_retention)
                 ON CONFLICT (sequence) DO UPDATE
                 SET sequence = EXCLUDED.sequence
                 RETURNING id, sequence AS "sequence!: EventSequence", payload, recorded_at
@@ -132,9 +377,13 @@ impl OutboxRepo {
                     recorded_at: row.recorded_at,
                 });
             }
-            events.sort_by(|a, b| a.sequence.cmp(&b.sequence));
+            events.sort_by_key(|a| a.sequence);
         }
 
         Ok(events)
     }
 }
+
+fn lag(max: i64, last_acked_sequence: EventSequence) -> u64 {
+    (max as u64).saturating_sub(u64::from(last_acked_sequence))
+}
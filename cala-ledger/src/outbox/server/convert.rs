@@ -9,8 +9,8 @@ use crate::{
     account_set::*,
     entry::*,
     journal::*,
-    outbox::event::{OutboxEvent, OutboxEventPayload},
-    transaction::TransactionValues,
+    outbox::event::{OutboxConsumer, OutboxEvent, OutboxEventFilter, OutboxEventPayload},
+    transaction::{TransactionAnnotation, TransactionValues},
     tx_template::*,
 };
 
@@ -57,6 +57,13 @@ impl From<OutboxEvent> for proto::CalaLedgerEvent {
                 account_set: Some(proto::AccountSet::from(account_set)),
                 fields,
             }),
+            OutboxEventPayload::AccountSetArchived {
+                source,
+                account_set,
+            } => proto::cala_ledger_event::Payload::AccountSetArchived(proto::AccountSetArchived {
+                data_source_id: source.to_string(),
+                account_set: Some(proto::AccountSet::from(account_set)),
+            }),
             OutboxEventPayload::AccountSetMemberCreated {
                 source,
                 account_set_id,
@@ -134,6 +141,17 @@ impl From<OutboxEvent> for proto::CalaLedgerEvent {
                 data_source_id: source.to_string(),
                 transaction: Some(proto::Transaction::from(transaction)),
             }),
+            OutboxEventPayload::TransactionAnnotated {
+                source,
+                transaction,
+                annotation,
+            } => proto::cala_ledger_event::Payload::TransactionAnnotated(
+                proto::TransactionAnnotated {
+                    data_source_id: source.to_string(),
+                    transaction: Some(proto::Transaction::from(transaction)),
+                    annotation: Some(proto::TransactionAnnotation::from(annotation)),
+                },
+            ),
             OutboxEventPayload::EntryCreated { source, entry } => {
                 proto::cala_ledger_event::Payload::EntryCreated(proto::EntryCreated {
                     data_source_id: source.to_string(),
@@ -152,6 +170,36 @@ impl From<OutboxEvent> for proto::CalaLedgerEvent {
                     balance: Some(proto::Balance::from(balance)),
                 })
             }
+            OutboxEventPayload::AccountSetMembersCreated {
+                source,
+                account_set_id,
+                member_ids,
+            } => {
+                let (member_account_ids, member_account_set_ids) = split_member_ids(member_ids);
+                proto::cala_ledger_event::Payload::AccountSetMembersCreated(
+                    proto::AccountSetMembersCreated {
+                        data_source_id: source.to_string(),
+                        account_set_id: account_set_id.to_string(),
+                        member_account_ids,
+                        member_account_set_ids,
+                    },
+                )
+            }
+            OutboxEventPayload::AccountSetMembersRemoved {
+                source,
+                account_set_id,
+                member_ids,
+            } => {
+                let (member_account_ids, member_account_set_ids) = split_member_ids(member_ids);
+                proto::cala_ledger_event::Payload::AccountSetMembersRemoved(
+                    proto::AccountSetMembersRemoved {
+                        data_source_id: source.to_string(),
+                        account_set_id: account_set_id.to_string(),
+                        member_account_ids,
+                        member_account_set_ids,
+                    },
+                )
+            }
             OutboxEventPayload::Empty => proto::cala_ledger_event::Payload::Empty(true),
         };
         proto::CalaLedgerEvent {
@@ -163,6 +211,22 @@ impl From<OutboxEvent> for proto::CalaLedgerEvent {
     }
 }
 
+fn split_member_ids(member_ids: Vec<AccountSetMemberId>) -> (Vec<String>, Vec<String>) {
+    let mut member_account_ids = Vec::new();
+    let mut member_account_set_ids = Vec::new();
+    for member_id in member_ids {
+        match member_id {
+            AccountSetMemberId::Account(account_id) => {
+                member_account_ids.push(account_id.to_string())
+            }
+            AccountSetMemberId::AccountSet(account_set_id) => {
+                member_account_set_ids.push(account_set_id.to_string())
+            }
+        }
+    }
+    (member_account_ids, member_account_set_ids)
+}
+
 impl From<AccountValues> for proto::Account {
     fn from(
         AccountValues {
@@ -330,6 +394,8 @@ impl From<TxTemplateEntry> for proto::TxTemplateEntry {
             units,
             description,
             metadata,
+            condition,
+            for_each,
         }: TxTemplateEntry,
     ) -> Self {
         proto::TxTemplateEntry {
@@ -341,6 +407,13 @@ impl From<TxTemplateEntry> for proto::TxTemplateEntry {
             units: String::from(units),
             description: description.map(String::from),
             metadata: metadata.map(String::from),
+            condition: condition.map(String::from),
+            for_each: for_each.map(|TxTemplateEntryForEach { list, var }| {
+                proto::TxTemplateEntryForEach {
+                    list: String::from(list),
+                    var,
+                }
+            }),
         }
     }
 }
@@ -352,6 +425,7 @@ impl From<ParamDefinition> for proto::ParamDefinition {
             r#type,
             default,
             description,
+            constraints,
         }: ParamDefinition,
     ) -> Self {
         let data_type: proto::ParamDataType = r#type.into();
@@ -360,6 +434,31 @@ impl From<ParamDefinition> for proto::ParamDefinition {
             data_type: data_type as i32,
             default: default.map(String::from),
             description,
+            constraints: Some(constraints.into()),
+        }
+    }
+}
+
+impl From<ParamConstraints> for proto::ParamConstraints {
+    fn from(
+        ParamConstraints {
+            required,
+            allowed_values,
+            min,
+            max,
+            scale,
+            regex,
+            predicate,
+        }: ParamConstraints,
+    ) -> Self {
+        proto::ParamConstraints {
+            required,
+            allowed_values: allowed_values.into_iter().map(String::from).collect(),
+            min: min.map(|m| m.to_string()),
+            max: max.map(|m| m.to_string()),
+            scale,
+            regex: regex.map(String::from),
+            predicate: predicate.map(String::from),
         }
     }
 }
@@ -375,6 +474,7 @@ impl From<ParamDataType> for proto::ParamDataType {
             ParamDataType::Date => proto::ParamDataType::Date,
             ParamDataType::Timestamp => proto::ParamDataType::Timestamp,
             ParamDataType::Json => proto::ParamDataType::Json,
+            ParamDataType::List => proto::ParamDataType::List,
         }
     }
 }
@@ -415,6 +515,7 @@ impl From<TransactionValues> for proto::Transaction {
             effective,
             description,
             metadata,
+            tags,
             entry_ids,
         }: TransactionValues,
     ) -> Self {
@@ -433,6 +534,27 @@ impl From<TransactionValues> for proto::Transaction {
             metadata: metadata.map(|json| {
                 serde_json::from_value(json).expect("Could not transfer json -> struct")
             }),
+            tags,
+        }
+    }
+}
+
+impl From<TransactionAnnotation> for proto::TransactionAnnotation {
+    fn from(
+        TransactionAnnotation {
+            actor,
+            annotated_at,
+            metadata,
+            tags,
+        }: TransactionAnnotation,
+    ) -> Self {
+        proto::TransactionAnnotation {
+            actor,
+            annotated_at: Some(annotated_at.into()),
+            metadata: metadata.map(|json| {
+                serde_json::from_value(json).expect("Could not transfer json -> struct")
+            }),
+            tags,
         }
     }
 }
@@ -535,3 +657,58 @@ impl From<Layer> for proto::Layer {
         }
     }
 }
+
+impl TryFrom<proto::EventFilter> for OutboxEventFilter {
+    type Error = tonic::Status;
+
+    fn try_from(
+        proto::EventFilter {
+            payload_types,
+            journal_ids,
+            account_ids,
+            data_source_ids,
+        }: proto::EventFilter,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            payload_types: parse_all("payload_types", payload_types)?,
+            journal_ids: parse_all("journal_ids", journal_ids)?,
+            account_ids: parse_all("account_ids", account_ids)?,
+            data_source_ids: parse_all("data_source_ids", data_source_ids)?,
+        })
+    }
+}
+
+impl From<OutboxConsumer> for proto::Consumer {
+    fn from(
+        OutboxConsumer {
+            name,
+            last_acked_sequence,
+            lag,
+            created_at,
+            modified_at,
+        }: OutboxConsumer,
+    ) -> Self {
+        Self {
+            name,
+            last_acked_sequence: u64::from(last_acked_sequence),
+            lag,
+            created_at: Some(created_at.into()),
+            modified_at: Some(modified_at.into()),
+        }
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn parse_all<T: std::str::FromStr>(
+    field: &str,
+    values: Vec<String>,
+) -> Result<Vec<T>, tonic::Status> {
+    values
+        .into_iter()
+        .map(|value| {
+            value.parse().map_err(|_| {
+                tonic::Status::invalid_argument(format!("invalid value '{value}' in {field}"))
+            })
+        })
+        .collect()
+}
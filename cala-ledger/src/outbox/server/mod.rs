@@ -13,7 +13,7 @@ use proto::{outbox_service_server::OutboxService, *};
 use tonic::{transport::Server, Request, Response, Status};
 use tracing::instrument;
 
-use super::{EventSequence, Outbox};
+use super::{error::OutboxError, EventSequence, Outbox, OutboxEventFilter};
 pub use config::*;
 use error::*;
 
@@ -22,6 +22,7 @@ pub struct OutboxServer {
 }
 
 #[tonic::async_trait]
+#[allow(clippy::result_large_err)]
 impl OutboxService for OutboxServer {
     type SubscribeStream = std::pin::Pin<
         Box<dyn futures::Stream<Item = Result<CalaLedgerEvent, Status>> + Send + Sync + 'static>,
@@ -34,19 +35,88 @@ impl OutboxService for OutboxServer {
     ) -> Result<Response<Self::SubscribeStream>, Status> {
         cala_tracing::grpc::extract_tracing(&request);
 
-        let SubscribeRequest { after_sequence } = request.into_inner();
+        let SubscribeRequest {
+            after_sequence,
+            filter,
+            consumer,
+        } = request.into_inner();
+        let filter = match filter {
+            Some(filter) => OutboxEventFilter::try_from(filter)?,
+            None => OutboxEventFilter::default(),
+        };
 
-        let outbox_listener = self
-            .outbox
-            .register_listener(after_sequence.map(EventSequence::from))
-            .await
-            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let after_sequence = after_sequence.map(EventSequence::from);
+        let outbox_listener = match consumer {
+            Some(name) => {
+                self.outbox
+                    .register_consumer(&name, after_sequence, filter)
+                    .await?
+            }
+            None => {
+                self.outbox
+                    .register_listener(after_sequence, filter)
+                    .await?
+            }
+        };
         Ok(Response::new(Box::pin(
             outbox_listener
-                .map(|event| Ok(proto::CalaLedgerEvent::from(event)))
+                .map(|event| {
+                    event
+                        .map(proto::CalaLedgerEvent::from)
+                        .map_err(Status::from)
+                })
                 .fuse(),
         )))
     }
+
+    #[instrument(name = "cala_ledger.ack", skip_all, fields(error, error.level, error.message), err)]
+    async fn ack(&self, request: Request<AckRequest>) -> Result<Response<Consumer>, Status> {
+        cala_tracing::grpc::extract_tracing(&request);
+
+        let AckRequest { consumer, sequence } = request.into_inner();
+        let consumer = self
+            .outbox
+            .ack_consumer(&consumer, EventSequence::from(sequence))
+            .await?;
+        Ok(Response::new(Consumer::from(consumer)))
+    }
+
+    #[instrument(name = "cala_ledger.get_consumer", skip_all, fields(error, error.level, error.message), err)]
+    async fn get_consumer(
+        &self,
+        request: Request<GetConsumerRequest>,
+    ) -> Result<Response<Consumer>, Status> {
+        cala_tracing::grpc::extract_tracing(&request);
+
+        let GetConsumerRequest { name } = request.into_inner();
+        let consumer = self.outbox.find_consumer(&name).await?;
+        Ok(Response::new(Consumer::from(consumer)))
+    }
+
+    #[instrument(name = "cala_ledger.list_consumers", skip_all, fields(error, error.level, error.message), err)]
+    async fn list_consumers(
+        &self,
+        request: Request<ListConsumersRequest>,
+    ) -> Result<Response<ListConsumersResponse>, Status> {
+        cala_tracing::grpc::extract_tracing(&request);
+
+        let consumers = self.outbox.list_consumers().await?;
+        Ok(Response::new(ListConsumersResponse {
+            consumers: consumers.into_iter().map(Consumer::from).collect(),
+        }))
+    }
+}
+
+impl From<OutboxError> for Status {
+    fn from(err: OutboxError) -> Self {
+        match err {
+            OutboxError::InvalidConsumerName(_) => Status::invalid_argument(err.to_string()),
+            OutboxError::ConsumerNotFound(_) => Status::not_found(err.to_string()),
+            OutboxError::SequenceNoLongerAvailable { .. } => Status::out_of_range(err.to_string()),
+            OutboxError::SequenceNotYetKnown { .. } => Status::out_of_range(err.to_string()),
+            OutboxError::Sqlx(_) => Status::internal(err.to_string()),
+        }
+    }
 }
 
 pub(crate) async fn start(
@@ -1,8 +1,11 @@
 use derive_builder::Builder;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 pub use cala_types::param::*;
-use cel_interpreter::CelExpression;
+use cel_interpreter::{CelContext, CelExpression, CelType, CelTypeContext, CelTypeDecl};
+
+use super::error::ParamError;
 
 #[derive(Clone, Debug, Deserialize, Serialize, Builder)]
 #[builder(build_fn(validate = "Self::validate"))]
@@ -14,6 +17,20 @@ pub struct NewParamDefinition {
     pub(crate) default: Option<String>,
     #[builder(setter(strip_option, into), default)]
     pub(crate) description: Option<String>,
+    #[builder(default)]
+    pub(crate) required: bool,
+    #[builder(setter(each(name = "allowed_value", into)), default)]
+    pub(crate) allowed_values: Vec<String>,
+    #[builder(setter(strip_option), default)]
+    pub(crate) min: Option<Decimal>,
+    #[builder(setter(strip_option), default)]
+    pub(crate) max: Option<Decimal>,
+    #[builder(setter(strip_option), default)]
+    pub(crate) scale: Option<u32>,
+    #[builder(setter(strip_option, into), default)]
+    pub(crate) regex: Option<String>,
+    #[builder(setter(strip_option, into), default)]
+    pub(crate) predicate: Option<String>,
 }
 
 impl NewParamDefinition {
@@ -26,23 +43,101 @@ impl NewParamDefinition {
             .as_ref()
             .map(|v| v.parse().expect("Couldn't create default_expr"))
     }
+
+    /// Evaluates the default and allowed values and type checks the predicate
+    /// within the contexts of the ledger, which include the host extensions.
+    pub(crate) fn check_exprs(
+        &self,
+        ctx: &CelContext,
+        types: &CelTypeContext,
+    ) -> Result<(), ParamError> {
+        let invalid = |reason| ParamError::InvalidDefinition(self.name.clone(), reason);
+        if let Some(expr) = self.default.as_ref() {
+            let param_type = evaluate_type(expr, ctx).map_err(invalid)?;
+            if param_type != self.r#type {
+                return Err(invalid(format!(
+                    "default expression type {param_type:?} does not match parameter type {:?}",
+                    self.r#type
+                )));
+            }
+        }
+        for expr in self.allowed_values.iter() {
+            let param_type = evaluate_type(expr, ctx).map_err(invalid)?;
+            if param_type != self.r#type {
+                return Err(invalid(format!(
+                    "allowed value '{expr}' type {param_type:?} does not match parameter type {:?}",
+                    self.r#type
+                )));
+            }
+        }
+        if let Some(expr) = self.predicate.as_ref() {
+            check_predicate(expr, types.clone()).map_err(invalid)?;
+        }
+        Ok(())
+    }
+}
+
+fn evaluate_type(expr: &str, ctx: &CelContext) -> Result<ParamDataType, String> {
+    let expr = CelExpression::try_from(expr).map_err(|e| e.to_string())?;
+    ParamDataType::try_from(&expr.evaluate(ctx).map_err(|e| format!("{e}"))?)
+}
+
+/// The other params aren't known here so `params` is only checked as `Dyn`.
+fn check_predicate(expr: &str, mut ctx: CelTypeContext) -> Result<(), String> {
+    ctx.add_variable("params", CelTypeDecl::Dyn);
+    let decl = CelExpression::try_from(expr)
+        .and_then(|expr| expr.check(&ctx))
+        .map_err(|e| e.to_string())?;
+    if !decl.is_assignable_to(CelType::Bool) {
+        return Err(format!(
+            "predicate '{expr}' evaluates to {decl} instead of Bool"
+        ));
+    }
+    Ok(())
 }
 
 impl NewParamDefinitionBuilder {
     fn validate(&self) -> Result<(), String> {
-        if let Some(Some(expr)) = self.default.as_ref() {
-            let expr = CelExpression::try_from(expr.as_str()).map_err(|e| e.to_string())?;
-            let param_type = ParamDataType::try_from(
-                &expr
-                    .evaluate(&crate::cel_context::initialize())
-                    .map_err(|e| format!("{e}"))?,
-            )?;
-            let specified_type = self.r#type.as_ref().unwrap();
-            if &param_type != specified_type {
+        let specified_type = self.r#type.as_ref().unwrap();
+        // Evaluating the expressions requires the contexts of the ledger, see `check_exprs`
+        let exprs = self
+            .default
+            .iter()
+            .flatten()
+            .chain(self.allowed_values.iter().flatten())
+            .chain(self.predicate.iter().flatten());
+        for expr in exprs {
+            CelExpression::try_from(expr.as_str()).map_err(|e| e.to_string())?;
+        }
+        let min = self.min.flatten();
+        let max = self.max.flatten();
+        if (min.is_some() || max.is_some())
+            && !matches!(
+                specified_type,
+                ParamDataType::Integer | ParamDataType::Decimal
+            )
+        {
+            return Err(format!(
+                "min / max are not supported for parameter type {specified_type:?}"
+            ));
+        }
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Err(format!("min {min} is greater than max {max}"));
+            }
+        }
+        if self.scale.flatten().is_some() && specified_type != &ParamDataType::Decimal {
+            return Err(format!(
+                "scale is not supported for parameter type {specified_type:?}"
+            ));
+        }
+        if let Some(Some(regex)) = self.regex.as_ref() {
+            if specified_type != &ParamDataType::String {
                 return Err(format!(
-                    "Default expression type {param_type:?} does not match parameter type {specified_type:?}"
+                    "regex is not supported for parameter type {specified_type:?}"
                 ));
             }
+            regex::Regex::new(regex).map_err(|e| e.to_string())?;
         }
         Ok(())
     }
@@ -56,6 +151,23 @@ impl From<NewParamDefinition> for cala_types::tx_template::ParamDefinition {
             r#type: param.r#type,
             default,
             description: param.description,
+            constraints: ParamConstraints {
+                required: param.required,
+                allowed_values: param
+                    .allowed_values
+                    .into_iter()
+                    .map(|v| v.parse().expect("Couldn't create allowed value"))
+                    .collect(),
+                min: param.min,
+                max: param.max,
+                scale: param.scale,
+                regex: param
+                    .regex
+                    .map(|r| r.parse().expect("Couldn't create regex")),
+                predicate: param
+                    .predicate
+                    .map(|p| p.parse().expect("Couldn't create predicate")),
+            },
         }
     }
 }
@@ -73,4 +185,96 @@ mod tests {
             .unwrap();
         assert_eq!(definition.name, "name");
     }
+
+    #[test]
+    fn build_param_definition_with_constraints() {
+        let definition = NewParamDefinition::builder()
+            .name("amount")
+            .r#type(ParamDataType::Decimal)
+            .required(true)
+            .min(Decimal::ZERO)
+            .max(Decimal::from(1000))
+            .scale(2)
+            .allowed_value("decimal('10')")
+            .build()
+            .unwrap();
+        assert_eq!(definition.allowed_values.len(), 1);
+    }
+
+    #[test]
+    fn constraints_must_fit_param_type() {
+        let res = NewParamDefinition::builder()
+            .name("code")
+            .r#type(ParamDataType::String)
+            .min(Decimal::ZERO)
+            .build();
+        assert!(res.is_err());
+
+        let res = NewParamDefinition::builder()
+            .name("code")
+            .r#type(ParamDataType::String)
+            .regex("[a-z")
+            .build();
+        assert!(res.is_err());
+
+        let res = NewParamDefinition::builder()
+            .name("amount")
+            .r#type(ParamDataType::Decimal)
+            .predicate("params.amount >")
+            .build();
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn exprs_must_fit_param_type() {
+        let ctx = crate::cel_context::initialize();
+        let types = crate::cel_context::initialize_types();
+        let check = |builder: &mut NewParamDefinitionBuilder| {
+            builder
+                .name("amount")
+                .r#type(ParamDataType::Decimal)
+                .build()
+                .unwrap()
+                .check_exprs(&ctx, &types)
+        };
+
+        assert!(check(NewParamDefinition::builder().allowed_value("'ten'")).is_err());
+        assert!(check(NewParamDefinition::builder().default_expr("'ten'")).is_err());
+        assert!(check(NewParamDefinition::builder().default_expr("decimal('10')")).is_ok());
+        assert!(
+            check(NewParamDefinition::builder().predicate("params.amount + decimal('1')")).is_err()
+        );
+        assert!(
+            check(NewParamDefinition::builder().predicate("date('2024-01-01') > 'x'")).is_err()
+        );
+        assert!(
+            check(NewParamDefinition::builder().predicate("params.amount > decimal('1')")).is_ok()
+        );
+        assert!(check(NewParamDefinition::builder().default_expr("fx.rate('EUR')")).is_err());
+    }
+
+    #[test]
+    fn exprs_may_use_extensions() {
+        use cel_interpreter::{CelExtensions, CelPackage, CelSignature, CelValue};
+
+        let mut fx = CelPackage::default();
+        fx.add_function(
+            "rate",
+            CelSignature::new([CelType::String], CelType::Decimal),
+            |_| Ok(CelValue::Decimal(Decimal::ONE)),
+        )
+        .unwrap();
+        let mut extensions = CelExtensions::new();
+        extensions.add_package("fx", fx);
+        let (ctx, types) = crate::cel_context::initialize_with(&extensions).unwrap();
+
+        let definition = NewParamDefinition::builder()
+            .name("rate")
+            .r#type(ParamDataType::Decimal)
+            .default_expr("fx.rate('EUR')")
+            .predicate("params.rate < fx.rate('USD')")
+            .build()
+            .unwrap();
+        assert!(definition.check_exprs(&ctx, &types).is_ok());
+    }
 }
@@ -4,8 +4,34 @@ use cel_interpreter::CelError;
 
 #[derive(Error, Debug)]
 pub enum ParamError {
+    #[deprecated(note = "type mismatches are reported as `InvalidParams`")]
     #[error("ParamError - ParamTypeMismatch: {0}")]
     ParamTypeMismatch(String),
+    #[error("ParamError - InvalidParams: {}", display_violations(.0))]
+    InvalidParams(Vec<ParamViolation>),
+    #[error("ParamError - InvalidDefinition: param '{0}' {1}")]
+    InvalidDefinition(String, String),
     #[error("ParamError - CelError: {0}")]
     CelError(#[from] CelError),
 }
+
+/// A single param that failed validation and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamViolation {
+    pub name: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ParamViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' {}", self.name, self.reason)
+    }
+}
+
+fn display_violations(violations: &[ParamViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
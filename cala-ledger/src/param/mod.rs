@@ -2,11 +2,12 @@ pub mod definition;
 pub mod error;
 
 use cel_interpreter::{CelContext, CelMap, CelValue};
+use rust_decimal::Decimal;
 use std::collections::HashMap;
 
 pub use cala_types::param::*;
 
-use error::*;
+pub use error::*;
 
 #[derive(Clone, Debug)]
 pub struct Params {
@@ -27,29 +28,138 @@ impl Params {
     pub(crate) fn into_context(
         mut self,
         defs: Option<&Vec<ParamDefinition>>,
+        mut ctx: CelContext,
     ) -> Result<CelContext, ParamError> {
-        let mut ctx = crate::cel_context::initialize();
         if let Some(defs) = defs {
+            let mut violations = Vec::new();
             let mut cel_map = CelMap::new();
             for d in defs {
-                if let Some(v) = self.values.remove(&d.name) {
-                    cel_map.insert(
-                        d.name.clone(),
-                        d.r#type
-                            .coerce_value(v)
-                            .map_err(ParamError::ParamTypeMismatch)?,
-                    );
+                let value = if let Some(v) = self.values.remove(&d.name) {
+                    match d.r#type.coerce_value(v) {
+                        Ok(v) => v,
+                        Err(reason) => {
+                            violations.push(ParamViolation {
+                                name: d.name.clone(),
+                                reason,
+                            });
+                            continue;
+                        }
+                    }
                 } else if let Some(expr) = d.default.as_ref() {
-                    cel_map.insert(d.name.clone(), expr.evaluate(&ctx)?);
-                }
+                    expr.evaluate(&ctx)?
+                } else {
+                    if d.constraints.required {
+                        violations.push(ParamViolation {
+                            name: d.name.clone(),
+                            reason: "is required".to_string(),
+                        });
+                    }
+                    continue;
+                };
+                violations.extend(
+                    check_constraints(&d.constraints, &value, &ctx)?
+                        .into_iter()
+                        .map(|reason| ParamViolation {
+                            name: d.name.clone(),
+                            reason,
+                        }),
+                );
+                cel_map.insert(d.name.clone(), value);
             }
+            // Predicates of optional params that weren't supplied don't apply
+            let predicates: Vec<_> = defs
+                .iter()
+                .filter(|d| cel_map.contains_key(d.name.as_str()))
+                .filter_map(|d| d.constraints.predicate.as_ref().map(|p| (d, p)))
+                .collect();
             ctx.add_variable("params", cel_map);
+
+            for (d, predicate) in predicates {
+                let reason = match predicate.try_evaluate::<bool>(&ctx) {
+                    Ok(true) => continue,
+                    Ok(false) => format!("does not satisfy '{predicate}'"),
+                    Err(e) => format!("could not evaluate '{predicate}' - {e}"),
+                };
+                violations.push(ParamViolation {
+                    name: d.name.clone(),
+                    reason,
+                });
+            }
+
+            if !violations.is_empty() {
+                return Err(ParamError::InvalidParams(violations));
+            }
         }
 
         Ok(ctx)
     }
 }
 
+fn check_constraints(
+    constraints: &ParamConstraints,
+    value: &CelValue,
+    ctx: &CelContext,
+) -> Result<Vec<String>, ParamError> {
+    let mut reasons = Vec::new();
+    if !constraints.allowed_values.is_empty() {
+        let mut allowed = false;
+        for expr in constraints.allowed_values.iter() {
+            if values_equal(&expr.evaluate(ctx)?, value) {
+                allowed = true;
+                break;
+            }
+        }
+        if !allowed {
+            let allowed_values = constraints
+                .allowed_values
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            reasons.push(format!("must be one of [{allowed_values}]"));
+        }
+    }
+    if let Some(n) = as_decimal(value) {
+        if let Some(min) = constraints.min {
+            if n < min {
+                reasons.push(format!("must be at least {min}"));
+            }
+        }
+        if let Some(max) = constraints.max {
+            if n > max {
+                reasons.push(format!("must be at most {max}"));
+            }
+        }
+        if let Some(scale) = constraints.scale {
+            if n.normalize().scale() > scale {
+                reasons.push(format!("must have at most {scale} decimal places"));
+            }
+        }
+    }
+    if let (Some(regex), CelValue::String(s)) = (constraints.regex.as_ref(), value) {
+        if !regex.is_match(s) {
+            reasons.push(format!("must match '{regex}'"));
+        }
+    }
+    Ok(reasons)
+}
+
+fn as_decimal(value: &CelValue) -> Option<Decimal> {
+    match value {
+        CelValue::Int(i) => Some(Decimal::from(*i)),
+        CelValue::UInt(u) => Some(Decimal::from(*u)),
+        CelValue::Decimal(d) => Some(*d),
+        _ => None,
+    }
+}
+
+fn values_equal(a: &CelValue, b: &CelValue) -> bool {
+    match (as_decimal(a), as_decimal(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
 impl Default for Params {
     fn default() -> Self {
         Self::new()
@@ -5,6 +5,8 @@ use crate::primitives::*;
 pub use cala_types::{primitives::TransactionId, transaction::*};
 use es_entity::*;
 
+use super::error::TransactionError;
+
 #[derive(EsEvent, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[es_event(id = "TransactionId")]
@@ -17,6 +19,10 @@ pub enum TransactionEvent {
     Initialized {
         values: TransactionValues,
     },
+    Annotated {
+        values: TransactionValues,
+        annotation: TransactionAnnotation,
+    },
 }
 
 #[derive(EsEntity, Builder)]
@@ -71,6 +77,51 @@ impl Transaction {
             .entity_last_modified_at()
             .expect("Entity not persisted")
     }
+
+    pub fn annotations(&self) -> impl Iterator<Item = &TransactionAnnotation> {
+        self.events.iter_all().filter_map(|event| match event {
+            TransactionEvent::Annotated { annotation, .. } => Some(annotation),
+            _ => None,
+        })
+    }
+
+    pub(super) fn annotate(
+        &mut self,
+        annotation: TransactionAnnotation,
+    ) -> Result<(), TransactionError> {
+        let metadata = annotation.metadata.as_ref().filter(|m| !m.is_null());
+        if metadata.is_none() && annotation.tags.is_empty() {
+            return Err(TransactionError::EmptyAnnotation);
+        }
+        if let Some(metadata) = metadata {
+            let new_fields = metadata
+                .as_object()
+                .ok_or(TransactionError::AnnotationMetadataNotAnObject)?;
+            match self.values.metadata.as_mut() {
+                Some(serde_json::Value::Object(existing)) => {
+                    existing.extend(new_fields.clone());
+                }
+                None | Some(serde_json::Value::Null) => {
+                    self.values.metadata = Some(metadata.clone());
+                }
+                Some(_) => {
+                    return Err(TransactionError::CannotMergeMetadata(self.values.id));
+                }
+            }
+        }
+        for tag in annotation.tags.iter() {
+            if !self.values.tags.contains(tag) {
+                self.values.tags.push(tag.clone());
+            }
+        }
+        self.values.version += 1;
+        self.values.modified_at = annotation.annotated_at;
+        self.events.push(TransactionEvent::Annotated {
+            values: self.values.clone(),
+            annotation,
+        });
+        Ok(())
+    }
 }
 
 impl TryFromEvents<TransactionEvent> for Transaction {
@@ -85,12 +136,30 @@ impl TryFromEvents<TransactionEvent> for Transaction {
                 TransactionEvent::Initialized { values } => {
                     builder = builder.id(values.id).values(values.clone());
                 }
+                TransactionEvent::Annotated { values, .. } => {
+                    builder = builder.values(values.clone());
+                }
             }
         }
         builder.events(events).build()
     }
 }
 
+/// Criteria for [Transactions::list_for_journal_id](crate::transaction::Transactions::list_for_journal_id).
+/// Every criterion that is set must match.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionsFilter {
+    pub tx_template_id: Option<TxTemplateId>,
+    /// Inclusive lower bound on when the transaction was recorded.
+    pub created_from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Exclusive upper bound on when the transaction was recorded.
+    pub created_until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Inclusive lower bound on the effective date.
+    pub effective_from: Option<chrono::NaiveDate>,
+    /// Inclusive upper bound on the effective date.
+    pub effective_until: Option<chrono::NaiveDate>,
+}
+
 #[derive(Builder, Debug)]
 #[allow(dead_code)]
 pub struct NewTransaction {
@@ -140,6 +209,7 @@ impl IntoEvents<TransactionEvent> for NewTransaction {
                     external_id: self.external_id,
                     description: self.description,
                     metadata: self.metadata,
+                    tags: Vec::new(),
                     entry_ids: self.entry_ids,
                 },
             }],
@@ -147,6 +217,44 @@ impl IntoEvents<TransactionEvent> for NewTransaction {
     }
 }
 
+#[derive(Builder, Debug)]
+pub struct NewTransactionAnnotation {
+    #[builder(setter(into))]
+    pub(super) actor: String,
+    #[builder(setter(custom), default)]
+    pub(super) metadata: Option<serde_json::Value>,
+    #[builder(setter(into), default)]
+    pub(super) tags: Vec<String>,
+}
+
+impl NewTransactionAnnotation {
+    pub fn builder() -> NewTransactionAnnotationBuilder {
+        NewTransactionAnnotationBuilder::default()
+    }
+
+    pub(super) fn into_annotation(
+        self,
+        annotated_at: chrono::DateTime<chrono::Utc>,
+    ) -> TransactionAnnotation {
+        TransactionAnnotation {
+            actor: self.actor,
+            annotated_at,
+            metadata: self.metadata,
+            tags: self.tags,
+        }
+    }
+}
+
+impl NewTransactionAnnotationBuilder {
+    pub fn metadata<T: serde::Serialize>(
+        &mut self,
+        metadata: T,
+    ) -> Result<&mut Self, serde_json::Error> {
+        self.metadata = Some(Some(serde_json::to_value(metadata)?));
+        Ok(self)
+    }
+}
+
 impl NewTransactionBuilder {
     pub fn id(&mut self, id: impl Into<TransactionId>) -> &mut Self {
         self.id = Some(id.into());
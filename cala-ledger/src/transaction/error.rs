@@ -19,6 +19,12 @@ pub enum TransactionError {
     DuplicateExternalId(String),
     #[error("TransactionError - DuplicateId: id '{0}' already exists")]
     DuplicateId(String),
+    #[error("TransactionError - EmptyAnnotation: annotation must have metadata or tags")]
+    EmptyAnnotation,
+    #[error("TransactionError - AnnotationMetadataNotAnObject: annotation metadata must be a JSON object")]
+    AnnotationMetadataNotAnObject,
+    #[error("TransactionError - CannotMergeMetadata: metadata of transaction '{0}' is not a JSON object")]
+    CannotMergeMetadata(TransactionId),
 }
 
 impl From<sqlx::Error> for TransactionError {
@@ -11,7 +11,7 @@ use std::collections::HashMap;
 
 #[cfg(feature = "import")]
 use crate::primitives::DataSourceId;
-use crate::primitives::TxTemplateId;
+use crate::primitives::{JournalId, TxTemplateId};
 use crate::{ledger_operation::*, outbox::*, primitives::DataSource};
 
 pub use entity::*;
@@ -23,7 +23,7 @@ use repo::*;
 pub struct Transactions {
     repo: TransactionRepo,
     outbox: Outbox,
-    _pool: PgPool,
+    pool: PgPool,
 }
 
 impl Transactions {
@@ -31,7 +31,7 @@ impl Transactions {
         Self {
             repo: TransactionRepo::new(pool),
             outbox,
-            _pool: pool.clone(),
+            pool: pool.clone(),
         }
     }
 
@@ -80,6 +80,70 @@ impl Transactions {
             .await
     }
 
+    #[instrument(
+        name = "cala_ledger.transactions.list_for_correlation_id",
+        skip(self),
+        err
+    )]
+    pub async fn list_for_correlation_id(
+        &self,
+        correlation_id: String,
+        query: es_entity::PaginatedQueryArgs<TransactionsByCreatedAtCursor>,
+        direction: es_entity::ListDirection,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<Transaction, TransactionsByCreatedAtCursor>,
+        TransactionError,
+    > {
+        self.repo
+            .list_for_correlation_id_by_created_at(correlation_id, query, direction)
+            .await
+    }
+
+    /// Lists the transactions recorded in a journal, most recent first.
+    #[instrument(name = "cala_ledger.transactions.list_for_journal_id", skip(self), err)]
+    pub async fn list_for_journal_id(
+        &self,
+        journal_id: JournalId,
+        filter: TransactionsFilter,
+        query: es_entity::PaginatedQueryArgs<TransactionsByCreatedAtCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<Transaction, TransactionsByCreatedAtCursor>,
+        TransactionError,
+    > {
+        self.repo
+            .list_for_journal_id_filtered(journal_id, filter, query)
+            .await
+    }
+
+    #[instrument(name = "cala_ledger.transactions.annotate", skip(self), err)]
+    pub async fn annotate(
+        &self,
+        transaction_id: TransactionId,
+        annotation: NewTransactionAnnotation,
+    ) -> Result<Transaction, TransactionError> {
+        let mut op = LedgerOperation::init(&self.pool, &self.outbox).await?;
+        let transaction = self
+            .annotate_in_op(&mut op, transaction_id, annotation)
+            .await?;
+        op.commit().await?;
+        Ok(transaction)
+    }
+
+    #[instrument(name = "cala_ledger.transactions.annotate_in_op", skip(self, db), err)]
+    pub async fn annotate_in_op(
+        &self,
+        db: &mut LedgerOperation<'_>,
+        transaction_id: TransactionId,
+        annotation: NewTransactionAnnotation,
+    ) -> Result<Transaction, TransactionError> {
+        let mut transaction = self.repo.find_by_id_in_tx(db.tx(), transaction_id).await?;
+        let annotated_at = db.op().now();
+        transaction.annotate(annotation.into_annotation(annotated_at))?;
+        let n_events = self.repo.update_in_op(db.op(), &mut transaction).await?;
+        db.accumulate(transaction.last_persisted(n_events).map(|p| &p.event));
+        Ok(transaction)
+    }
+
     #[instrument(name = "cala_ledger.transactions.find_all", skip(self), err)]
     pub async fn find_all<T: From<Transaction>>(
         &self,
@@ -109,6 +173,27 @@ impl Transactions {
             .await?;
         Ok(())
     }
+
+    #[cfg(feature = "import")]
+    pub async fn sync_transaction_annotation(
+        &self,
+        mut db: es_entity::DbOp<'_>,
+        transaction_id: TransactionId,
+        annotation: TransactionAnnotation,
+    ) -> Result<(), TransactionError> {
+        let mut transaction = self.repo.find_by_id_in_tx(db.tx(), transaction_id).await?;
+        transaction.annotate(annotation)?;
+        let n_events = self.repo.update_in_op(&mut db, &mut transaction).await?;
+        let recorded_at = db.now();
+        let outbox_events: Vec<_> = transaction
+            .last_persisted(n_events)
+            .map(|p| OutboxEventPayload::from(&p.event))
+            .collect();
+        self.outbox
+            .persist_events_at(db.into_tx(), outbox_events, recorded_at)
+            .await?;
+        Ok(())
+    }
 }
 
 impl From<&TransactionEvent> for OutboxEventPayload {
@@ -128,6 +213,14 @@ impl From<&TransactionEvent> for OutboxEventPayload {
                 source: DataSource::Local,
                 transaction: transaction.clone(),
             },
+            TransactionEvent::Annotated {
+                values: transaction,
+                annotation,
+            } => OutboxEventPayload::TransactionAnnotated {
+                source: DataSource::Local,
+                transaction: transaction.clone(),
+                annotation: annotation.clone(),
+            },
         }
     }
 }
@@ -14,7 +14,7 @@ use super::{entity::*, error::TransactionError};
     err = "TransactionError",
     columns(
         external_id(ty = "Option<String>", update(persist = false)),
-        correlation_id(ty = "String", update(persist = false)),
+        correlation_id(ty = "String", update(persist = false), list_for),
         journal_id(ty = "JournalId", update(persist = false)),
         tx_template_id(ty = "TxTemplateId", update(persist = false), list_for),
         data_source_id(
@@ -35,6 +35,67 @@ impl TransactionRepo {
         Self { pool: pool.clone() }
     }
 
+    pub async fn list_for_journal_id_filtered(
+        &self,
+        journal_id: JournalId,
+        filter: TransactionsFilter,
+        query: es_entity::PaginatedQueryArgs<transaction_cursor::TransactionsByCreatedAtCursor>,
+    ) -> Result<
+        es_entity::PaginatedQueryRet<
+            Transaction,
+            transaction_cursor::TransactionsByCreatedAtCursor,
+        >,
+        TransactionError,
+    > {
+        let (after_id, after_created_at) = match query.after {
+            Some(cursor) => (Some(cursor.id), Some(cursor.created_at)),
+            None => (None, None),
+        };
+        let rows = sqlx::query_as!(
+            transaction_repo_types::Repo__DbEvent,
+            r#"
+            WITH transactions AS (
+              SELECT t.id, t.created_at
+              FROM cala_transactions t
+              WHERE t.journal_id = $1
+              AND ((t.created_at, t.id) < ($3, $2) OR ($2 IS NULL AND $3 IS NULL))
+              AND ($4::uuid IS NULL OR t.tx_template_id = $4)
+              AND ($5::timestamptz IS NULL OR t.created_at >= $5)
+              AND ($6::timestamptz IS NULL OR t.created_at < $6)
+              AND ($7::date IS NULL OR t.effective >= $7)
+              AND ($8::date IS NULL OR t.effective <= $8)
+              ORDER BY t.created_at DESC, t.id DESC
+              LIMIT $9
+            )
+            SELECT t.id AS "entity_id!: TransactionId", e.sequence, e.event, e.recorded_at
+              FROM transactions t
+              JOIN cala_transaction_events e ON t.id = e.id
+              ORDER BY t.created_at DESC, t.id DESC, e.sequence
+            "#,
+            journal_id as JournalId,
+            after_id as Option<TransactionId>,
+            after_created_at,
+            filter.tx_template_id as Option<TxTemplateId>,
+            filter.created_from,
+            filter.created_until,
+            filter.effective_from,
+            filter.effective_until,
+            query.first as i64 + 1
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        let (entities, has_next_page) = EntityEvents::load_n::<Transaction>(rows, query.first)?;
+        let end_cursor = entities
+            .last()
+            .map(transaction_cursor::TransactionsByCreatedAtCursor::from);
+        Ok(es_entity::PaginatedQueryRet {
+            entities,
+            has_next_page,
+            end_cursor,
+        })
+    }
+
     #[cfg(feature = "import")]
     pub async fn import_in_op(
         &self,
@@ -4,9 +4,11 @@ use serde::{Deserialize, Serialize};
 pub use crate::param::definition::*;
 use crate::primitives::*;
 pub use cala_types::{primitives::TxTemplateId, tx_template::*};
-use cel_interpreter::CelExpression;
+use cel_interpreter::{CelContext, CelError, CelExpression, CelType, CelTypeContext, CelTypeDecl};
 use es_entity::*;
 
+use super::error::TxTemplateError;
+
 #[derive(EsEvent, Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[es_event(id = "TxTemplateId")]
@@ -119,6 +121,39 @@ impl NewTxTemplate {
     pub(super) fn data_source(&self) -> DataSource {
         DataSource::Local
     }
+
+    /// Rejects `for_each` variables that would replace an identifier of the context
+    /// (eg. the `decimal` package) while the entry is evaluated.
+    pub(super) fn check_for_each_vars(&self, ctx: &CelContext) -> Result<(), TxTemplateError> {
+        for (idx, entry) in self.entries.iter().enumerate() {
+            if let Some((_, var)) = entry.for_each.as_ref() {
+                if ctx.contains_ident(var) {
+                    return Err(TxTemplateError::ForEachVariableShadows(
+                        format!("entries[{idx}].for_each.var"),
+                        var.clone(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Infers the types of all expressions against the declared params
+    /// so that mistakes surface on creation rather than when posting.
+    pub(super) fn check_types(&self, mut ctx: CelTypeContext) -> Result<(), TxTemplateError> {
+        if let Some(params) = self.params.as_ref() {
+            let params = params
+                .iter()
+                .map(|p| (p.name.clone(), p.r#type.type_decl()))
+                .collect();
+            ctx.add_variable("params", CelTypeDecl::Struct(params));
+        }
+        self.transaction.check_types(&ctx)?;
+        for (idx, entry) in self.entries.iter().enumerate() {
+            entry.check_types(&format!("entries[{idx}]"), &mut ctx)?;
+        }
+        Ok(())
+    }
 }
 
 impl IntoEvents<TxTemplateEvent> for NewTxTemplate {
@@ -172,6 +207,10 @@ pub struct NewTxTemplateEntry {
     description: Option<String>,
     #[builder(setter(strip_option, into), default)]
     metadata: Option<String>,
+    #[builder(setter(strip_option, into), default)]
+    condition: Option<String>,
+    #[builder(setter(custom), default)]
+    for_each: Option<(String, String)>,
 }
 
 impl NewTxTemplateEntry {
@@ -180,6 +219,13 @@ impl NewTxTemplateEntry {
     }
 }
 impl NewTxTemplateEntryBuilder {
+    /// Repeats the entry once per element of the list that `list` evaluates to,
+    /// binding the element to `var` for the other expressions of the entry.
+    pub fn for_each(&mut self, list: impl Into<String>, var: impl Into<String>) -> &mut Self {
+        self.for_each = Some(Some((list.into(), var.into())));
+        self
+    }
+
     fn validate(&self) -> Result<(), String> {
         validate_expression(
             self.entry_type
@@ -212,7 +258,76 @@ impl NewTxTemplateEntryBuilder {
                 .expect("Mandatory field 'currency' not set"),
         )?;
         validate_optional_expression(&self.description)?;
-        validate_optional_expression(&self.metadata)
+        validate_optional_expression(&self.metadata)?;
+        validate_optional_expression(&self.condition)?;
+        if let Some(Some((list, var))) = self.for_each.as_ref() {
+            validate_expression(list)?;
+            validate_variable_name(var)?;
+        }
+        Ok(())
+    }
+}
+
+impl NewTxTemplateEntry {
+    fn check_types(&self, path: &str, ctx: &mut CelTypeContext) -> Result<(), TxTemplateError> {
+        let field = |name: &str| format!("{path}.{name}");
+        if let Some((list, var)) = self.for_each.as_ref() {
+            check_expression(&field("for_each.list"), list, Some(CelType::List), ctx)?;
+            ctx.add_variable(var.clone(), CelTypeDecl::Dyn);
+        }
+        let res = self.check_entry_types(&field, ctx);
+        if let Some((_, var)) = self.for_each.as_ref() {
+            ctx.remove_variable(var);
+        }
+        res
+    }
+
+    fn check_entry_types(
+        &self,
+        field: &impl Fn(&str) -> String,
+        ctx: &CelTypeContext,
+    ) -> Result<(), TxTemplateError> {
+        check_expression(
+            &field("entry_type"),
+            &self.entry_type,
+            Some(CelType::String),
+            ctx,
+        )?;
+        check_expression(
+            &field("account_id"),
+            &self.account_id,
+            Some(CelType::Uuid),
+            ctx,
+        )?;
+        check_expression(&field("layer"), &self.layer, Some(CelType::String), ctx)?;
+        check_expression(
+            &field("direction"),
+            &self.direction,
+            Some(CelType::String),
+            ctx,
+        )?;
+        check_expression(&field("units"), &self.units, Some(CelType::Decimal), ctx)?;
+        check_expression(
+            &field("currency"),
+            &self.currency,
+            Some(CelType::String),
+            ctx,
+        )?;
+        if let Some(description) = self.description.as_ref() {
+            check_expression(
+                &field("description"),
+                description,
+                Some(CelType::String),
+                ctx,
+            )?;
+        }
+        if let Some(metadata) = self.metadata.as_ref() {
+            check_expression(&field("metadata"), metadata, None, ctx)?;
+        }
+        if let Some(condition) = self.condition.as_ref() {
+            check_expression(&field("condition"), condition, Some(CelType::Bool), ctx)?;
+        }
+        Ok(())
     }
 }
 
@@ -233,6 +348,15 @@ impl From<NewTxTemplateEntry> for cala_types::tx_template::TxTemplateEntry {
             metadata: input
                 .metadata
                 .map(|m| CelExpression::try_from(m).expect("always a valid metadata")),
+            condition: input
+                .condition
+                .map(|c| CelExpression::try_from(c).expect("always a valid condition")),
+            for_each: input.for_each.map(|(list, var)| {
+                cala_types::tx_template::TxTemplateEntryForEach {
+                    list: CelExpression::try_from(list).expect("always a valid list"),
+                    var,
+                }
+            }),
         }
     }
 }
@@ -280,6 +404,51 @@ impl NewTxTemplateTransactionBuilder {
     }
 }
 
+impl NewTxTemplateTransaction {
+    fn check_types(&self, ctx: &CelTypeContext) -> Result<(), TxTemplateError> {
+        check_expression(
+            "transaction.effective",
+            &self.effective,
+            Some(CelType::Date),
+            ctx,
+        )?;
+        check_expression(
+            "transaction.journal_id",
+            &self.journal_id,
+            Some(CelType::Uuid),
+            ctx,
+        )?;
+        if let Some(correlation_id) = self.correlation_id.as_ref() {
+            check_expression(
+                "transaction.correlation_id",
+                correlation_id,
+                Some(CelType::String),
+                ctx,
+            )?;
+        }
+        if let Some(external_id) = self.external_id.as_ref() {
+            check_expression(
+                "transaction.external_id",
+                external_id,
+                Some(CelType::String),
+                ctx,
+            )?;
+        }
+        if let Some(description) = self.description.as_ref() {
+            check_expression(
+                "transaction.description",
+                description,
+                Some(CelType::String),
+                ctx,
+            )?;
+        }
+        if let Some(metadata) = self.metadata.as_ref() {
+            check_expression("transaction.metadata", metadata, None, ctx)?;
+        }
+        Ok(())
+    }
+}
+
 impl From<NewTxTemplateTransaction> for cala_types::tx_template::TxTemplateTransaction {
     fn from(
         NewTxTemplateTransaction {
@@ -310,6 +479,37 @@ fn validate_expression(expr: &str) -> Result<(), String> {
     CelExpression::try_from(expr).map_err(|e| e.to_string())?;
     Ok(())
 }
+fn check_expression(
+    field: &str,
+    expr: &str,
+    expected: Option<CelType>,
+    ctx: &CelTypeContext,
+) -> Result<(), TxTemplateError> {
+    let type_error =
+        |e: CelError| TxTemplateError::TypeCheck(field.to_string(), expr.to_string(), Box::new(e));
+    CelExpression::try_from(expr)
+        .and_then(|expression| match expected {
+            Some(expected) => expression.check_expecting(ctx, expected),
+            None => expression.check(ctx),
+        })
+        .map_err(type_error)?;
+    Ok(())
+}
+
+fn validate_variable_name(var: &str) -> Result<(), String> {
+    let mut chars = var.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!("'{var}' is not a valid variable name"));
+    }
+    if var == "params" {
+        return Err("'params' is reserved and cannot be used as a variable name".to_string());
+    }
+    Ok(())
+}
 fn validate_optional_expression(expr: &Option<Option<String>>) -> Result<(), String> {
     if let Some(Some(expr)) = expr.as_ref() {
         CelExpression::try_from(expr.as_str()).map_err(|e| e.to_string())?;
@@ -351,6 +551,42 @@ mod tests {
         assert_eq!(new_tx_template.description, None);
     }
 
+    #[test]
+    fn rejects_for_each_vars_shadowing_the_context() {
+        let template = |var: &str| {
+            NewTxTemplate::builder()
+                .id(TxTemplateId::new())
+                .code("CODE")
+                .transaction(
+                    NewTxTemplateTransaction::builder()
+                        .effective("date('2022-11-01')")
+                        .journal_id("params.journal_id")
+                        .build()
+                        .unwrap(),
+                )
+                .entries(vec![NewTxTemplateEntry::builder()
+                    .entry_type("'TEST_DR'")
+                    .account_id("params.recipient")
+                    .layer("SETTLED")
+                    .direction("DEBIT")
+                    .units(format!("{var}.amount"))
+                    .currency("'BTC'")
+                    .for_each("params.payouts", var)
+                    .build()
+                    .unwrap()])
+                .build()
+                .unwrap()
+        };
+        let ctx = crate::cel_context::initialize();
+        assert!(template("payout").check_for_each_vars(&ctx).is_ok());
+        for var in ["decimal", "list", "SETTLED"] {
+            assert!(matches!(
+                template(var).check_for_each_vars(&ctx),
+                Err(TxTemplateError::ForEachVariableShadows(_, v)) if v == var
+            ));
+        }
+    }
+
     #[test]
     fn fails_when_mandatory_fields_are_missing() {
         let new_tx_template = NewTxTemplate::builder().build();
@@ -18,6 +18,10 @@ pub enum TxTemplateError {
     NotFound,
     #[error("TxTemplateError - SerdeJson: {0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("TxTemplateError - TypeCheck: field '{0}' expression '{1}' - {2}")]
+    TypeCheck(String, String, Box<CelError>),
+    #[error("TxTemplateError - ForEachVariableShadows: field '{0}' variable '{1}' is already a variable, function or package")]
+    ForEachVariableShadows(String, String),
     #[error("TxTemplateError - UnbalancedTransaction: currency {0}, layer {1:?}, amount {2}")]
     UnbalancedTransaction(Currency, Layer, Decimal),
     #[error("TxTemplateError - NotFound: code '{0}' not found")]
@@ -3,6 +3,7 @@ mod repo;
 
 pub mod error;
 
+use cel_interpreter::{CelContext, CelTypeContext};
 use chrono::{DateTime, NaiveDate, Utc};
 use es_entity::EsEntity;
 use rust_decimal::Decimal;
@@ -35,14 +36,23 @@ pub struct TxTemplates {
     repo: TxTemplateRepo,
     outbox: Outbox,
     pool: PgPool,
+    cel_context: CelContext,
+    cel_types: CelTypeContext,
 }
 
 impl TxTemplates {
-    pub(crate) fn new(pool: &PgPool, outbox: Outbox) -> Self {
+    pub(crate) fn new(
+        pool: &PgPool,
+        outbox: Outbox,
+        cel_context: CelContext,
+        cel_types: CelTypeContext,
+    ) -> Self {
         Self {
             repo: TxTemplateRepo::new(pool),
             outbox,
             pool: pool.clone(),
+            cel_context,
+            cel_types,
         }
     }
 
@@ -62,6 +72,11 @@ impl TxTemplates {
         db: &mut LedgerOperation<'_>,
         new_tx_template: NewTxTemplate,
     ) -> Result<TxTemplate, TxTemplateError> {
+        for param in new_tx_template.params.iter().flatten() {
+            param.check_exprs(&self.cel_context, &self.cel_types)?;
+        }
+        new_tx_template.check_for_each_vars(&self.cel_context)?;
+        new_tx_template.check_types(self.cel_types.clone())?;
         let tx_template = self.repo.create_in_op(db.op(), new_tx_template).await?;
         db.accumulate(tx_template.last_persisted(1).map(|p| &p.event));
         Ok(tx_template)
@@ -103,11 +118,11 @@ impl TxTemplates {
     ) -> Result<PreparedTransaction, TxTemplateError> {
         let tmpl = self.repo.find_latest_version(code).await?;
 
-        let ctx = params.into_context(tmpl.params.as_ref())?;
+        let mut ctx = params.into_context(tmpl.params.as_ref(), self.cel_context.clone())?;
 
         let journal_id: Uuid = tmpl.transaction.journal_id.try_evaluate(&ctx)?;
 
-        let entries = self.prep_entries(&tmpl, tx_id, JournalId::from(journal_id), &ctx)?;
+        let entries = self.prep_entries(&tmpl, tx_id, JournalId::from(journal_id), &mut ctx)?;
 
         let mut tx_builder = NewTransaction::builder();
         tx_builder
@@ -154,50 +169,43 @@ impl TxTemplates {
         tmpl: &TxTemplateValues,
         transaction_id: TransactionId,
         journal_id: JournalId,
-        ctx: &cel_interpreter::CelContext,
+        ctx: &mut CelContext,
     ) -> Result<Vec<NewEntry>, TxTemplateError> {
         let mut new_entries = Vec::new();
         let mut totals = HashMap::new();
-        for (zero_based_sequence, entry) in tmpl.entries.iter().enumerate() {
-            let mut builder = NewEntry::builder();
-            builder
-                .id(EntryId::new())
-                .transaction_id(transaction_id)
-                .journal_id(journal_id)
-                .sequence(zero_based_sequence as u32 + 1);
-            let account_id: Uuid = entry.account_id.try_evaluate(ctx)?;
-            builder.account_id(account_id);
-
-            let entry_type: String = entry.entry_type.try_evaluate(ctx)?;
-            builder.entry_type(entry_type);
-
-            let layer: Layer = entry.layer.try_evaluate(ctx)?;
-            builder.layer(layer);
-
-            let units: Decimal = entry.units.try_evaluate(ctx)?;
-            let currency: Currency = entry.currency.try_evaluate(ctx)?;
-            let direction: DebitOrCredit = entry.direction.try_evaluate(ctx)?;
-
-            let total = totals.entry((currency, layer)).or_insert(Decimal::ZERO);
-            match direction {
-                DebitOrCredit::Debit => *total -= units,
-                DebitOrCredit::Credit => *total += units,
-            };
-            builder.units(units);
-            builder.currency(currency);
-            builder.direction(direction);
-
-            if let Some(description) = entry.description.as_ref() {
-                let description: String = description.try_evaluate(ctx)?;
-                builder.description(description);
-            }
-
-            if let Some(metadata) = entry.metadata.as_ref() {
-                let metadata: serde_json::Value = metadata.try_evaluate(ctx)?;
-                builder.metadata(metadata);
+        for entry in tmpl.entries.iter() {
+            if let Some(for_each) = entry.for_each.as_ref() {
+                let elements: Vec<cel_interpreter::CelValue> = for_each.list.try_evaluate(ctx)?;
+                // Templates created before for_each variables were checked on creation
+                if ctx.contains_ident(&for_each.var) {
+                    return Err(TxTemplateError::ForEachVariableShadows(
+                        "for_each.var".to_string(),
+                        for_each.var.clone(),
+                    ));
+                }
+                for element in elements {
+                    ctx.add_variable(for_each.var.clone(), element);
+                    let res = Self::prep_entry(
+                        entry,
+                        transaction_id,
+                        journal_id,
+                        ctx,
+                        &mut new_entries,
+                        &mut totals,
+                    );
+                    ctx.remove_variable(&for_each.var);
+                    res?;
+                }
+            } else {
+                Self::prep_entry(
+                    entry,
+                    transaction_id,
+                    journal_id,
+                    ctx,
+                    &mut new_entries,
+                    &mut totals,
+                )?;
             }
-
-            new_entries.push(builder.build().expect("Couldn't build entry"));
         }
 
         for ((c, l), v) in totals {
@@ -209,6 +217,62 @@ impl TxTemplates {
         Ok(new_entries)
     }
 
+    fn prep_entry(
+        entry: &TxTemplateEntry,
+        transaction_id: TransactionId,
+        journal_id: JournalId,
+        ctx: &CelContext,
+        new_entries: &mut Vec<NewEntry>,
+        totals: &mut HashMap<(Currency, Layer), Decimal>,
+    ) -> Result<(), TxTemplateError> {
+        if let Some(condition) = entry.condition.as_ref() {
+            let emit: bool = condition.try_evaluate(ctx)?;
+            if !emit {
+                return Ok(());
+            }
+        }
+        let mut builder = NewEntry::builder();
+        builder
+            .id(EntryId::new())
+            .transaction_id(transaction_id)
+            .journal_id(journal_id)
+            .sequence(new_entries.len() as u32 + 1);
+        let account_id: Uuid = entry.account_id.try_evaluate(ctx)?;
+        builder.account_id(account_id);
+
+        let entry_type: String = entry.entry_type.try_evaluate(ctx)?;
+        builder.entry_type(entry_type);
+
+        let layer: Layer = entry.layer.try_evaluate(ctx)?;
+        builder.layer(layer);
+
+        let units: Decimal = entry.units.try_evaluate(ctx)?;
+        let currency: Currency = entry.currency.try_evaluate(ctx)?;
+        let direction: DebitOrCredit = entry.direction.try_evaluate(ctx)?;
+
+        let total = totals.entry((currency, layer)).or_insert(Decimal::ZERO);
+        match direction {
+            DebitOrCredit::Debit => *total -= units,
+            DebitOrCredit::Credit => *total += units,
+        };
+        builder.units(units);
+        builder.currency(currency);
+        builder.direction(direction);
+
+        if let Some(description) = entry.description.as_ref() {
+            let description: String = description.try_evaluate(ctx)?;
+            builder.description(description);
+        }
+
+        if let Some(metadata) = entry.metadata.as_ref() {
+            let metadata: serde_json::Value = metadata.try_evaluate(ctx)?;
+            builder.metadata(metadata);
+        }
+
+        new_entries.push(builder.build().expect("Couldn't build entry"));
+        Ok(())
+    }
+
     #[cfg(feature = "import")]
     pub async fn sync_tx_template_creation(
         &self,
@@ -82,6 +82,8 @@ impl TxTemplateRepo {
     }
 }
 
+// Templates are immutable per version so the values - including the compiled
+// programs of their expressions - can be reused across postings.
 #[cached(
     key = "(TxTemplateId, i32)",
     convert = "{ (id, version) }",
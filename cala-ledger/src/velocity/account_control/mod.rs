@@ -5,7 +5,9 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use sqlx::PgPool;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
+
+use cel_interpreter::CelContext;
 
 use cala_types::{
     account::AccountValues,
@@ -27,13 +29,15 @@ pub(super) use value::*;
 pub struct AccountControls {
     _pool: PgPool,
     repo: AccountControlRepo,
+    cel_context: CelContext,
 }
 
 impl AccountControls {
-    pub fn new(pool: &PgPool) -> Self {
+    pub fn new(pool: &PgPool, cel_context: CelContext) -> Self {
         Self {
             repo: AccountControlRepo::new(pool),
             _pool: pool.clone(),
+            cel_context,
         }
     }
 
@@ -51,7 +55,9 @@ impl AccountControls {
         let mut velocity_limits = Vec::new();
         for velocity in limits {
             let defs = velocity.params;
-            let ctx = params.clone().into_context(defs.as_ref())?;
+            let ctx = params
+                .clone()
+                .into_context(defs.as_ref(), self.cel_context.clone())?;
             let mut limits = Vec::new();
             for limit in velocity.limit.balance {
                 let layer: Layer = limit.layer.try_evaluate(&ctx)?;
@@ -105,7 +111,7 @@ impl AccountControls {
         &self,
         db: &mut LedgerOperation<'_>,
         account_ids: &[AccountId],
-    ) -> Result<HashMap<AccountId, (AccountValues, Vec<AccountVelocityControl>)>, VelocityError>
+    ) -> Result<HashMap<AccountId, (AccountValues, Vec<Arc<AccountVelocityControl>>)>, VelocityError>
     {
         self.repo.find_for_enforcement(db.op(), account_ids).await
     }
@@ -1,7 +1,9 @@
+use cached::proc_macro::cached;
+use chrono::{DateTime, Utc};
 use es_entity::DbOp;
 use sqlx::PgPool;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use cala_types::account::AccountValues;
 
@@ -42,10 +44,12 @@ impl AccountControlRepo {
         &self,
         op: &mut DbOp<'_>,
         account_ids: &[AccountId],
-    ) -> Result<HashMap<AccountId, (AccountValues, Vec<AccountVelocityControl>)>, VelocityError>
+    ) -> Result<HashMap<AccountId, (AccountValues, Vec<Arc<AccountVelocityControl>>)>, VelocityError>
     {
         let rows = sqlx::query!(
-            r#"SELECT values, latest_values
+            r#"SELECT account_id AS "account_id: AccountId",
+                velocity_control_id AS "velocity_control_id: VelocityControlId",
+                v.created_at, values, latest_values
             FROM cala_velocity_account_controls v
             JOIN cala_accounts a
             ON v.account_id = a.id
@@ -58,9 +62,11 @@ impl AccountControlRepo {
         let mut res: HashMap<AccountId, (AccountValues, Vec<_>)> = HashMap::new();
 
         for row in rows {
-            let values: AccountVelocityControl =
-                serde_json::from_value(row.values).expect("Failed to deserialize control values");
-            res.entry(values.account_id)
+            let control = deserialize_control_cached(
+                (row.account_id, row.velocity_control_id, row.created_at),
+                row.values,
+            );
+            res.entry(row.account_id)
                 .or_insert_with(|| {
                     (
                         serde_json::from_value(row.latest_values)
@@ -69,9 +75,24 @@ impl AccountControlRepo {
                     )
                 })
                 .1
-                .push(values);
+                .push(control);
         }
 
         Ok(res)
     }
 }
+
+// A control is never updated once attached to an account so the deserialized
+// values - including the compiled programs of their expressions - can be reused
+// across postings. The attachment time versions the entry in case it gets re-attached.
+#[cached(
+    key = "(AccountId, VelocityControlId, DateTime<Utc>)",
+    convert = "{ _key }",
+    sync_writes = "default"
+)]
+fn deserialize_control_cached(
+    _key: (AccountId, VelocityControlId, DateTime<Utc>),
+    values: serde_json::Value,
+) -> Arc<AccountVelocityControl> {
+    Arc::new(serde_json::from_value(values).expect("Failed to deserialize control values"))
+}
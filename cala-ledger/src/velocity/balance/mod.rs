@@ -3,13 +3,15 @@ mod repo;
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use cala_types::{
     account::AccountValues, balance::BalanceSnapshot, entry::EntryValues,
     transaction::TransactionValues,
 };
 
+use cel_interpreter::CelContext;
+
 use crate::{ledger_operation::*, primitives::AccountId};
 
 use super::{account_control::*, error::*};
@@ -19,12 +21,14 @@ use repo::*;
 #[derive(Clone)]
 pub(super) struct VelocityBalances {
     repo: VelocityBalanceRepo,
+    cel_context: CelContext,
 }
 
 impl VelocityBalances {
-    pub fn new(pool: &PgPool) -> Self {
+    pub fn new(pool: &PgPool, cel_context: CelContext) -> Self {
         Self {
             repo: VelocityBalanceRepo::new(pool),
+            cel_context,
         }
     }
 
@@ -34,10 +38,13 @@ impl VelocityBalances {
         created_at: DateTime<Utc>,
         transaction: &TransactionValues,
         entries: &[EntryValues],
-        controls: HashMap<AccountId, (AccountValues, Vec<AccountVelocityControl>)>,
+        controls: HashMap<AccountId, (AccountValues, Vec<Arc<AccountVelocityControl>>)>,
     ) -> Result<(), VelocityError> {
-        let mut context =
-            super::context::EvalContext::new(transaction, controls.values().map(|v| &v.0));
+        let mut context = super::context::EvalContext::new(
+            self.cel_context.clone(),
+            transaction,
+            controls.values().map(|v| &v.0),
+        );
 
         let entries_to_enforce = Self::balances_to_check(&mut context, entries, &controls)?;
 
@@ -64,7 +71,7 @@ impl VelocityBalances {
     fn balances_to_check<'a>(
         context: &mut super::context::EvalContext,
         entries: &'a [EntryValues],
-        controls: &'a HashMap<AccountId, (AccountValues, Vec<AccountVelocityControl>)>,
+        controls: &'a HashMap<AccountId, (AccountValues, Vec<Arc<AccountVelocityControl>>)>,
     ) -> Result<
         HashMap<VelocityBalanceKey, Vec<(&'a AccountVelocityLimit, &'a EntryValues)>>,
         VelocityError,
@@ -9,6 +9,7 @@ use crate::{
 };
 
 pub struct EvalContext {
+    base: CelContext,
     transaction: CelValue,
     entry_values: HashMap<EntryId, CelValue>,
     account_values: HashMap<AccountId, CelValue>,
@@ -16,11 +17,13 @@ pub struct EvalContext {
 
 impl EvalContext {
     pub fn new<'a>(
+        base: CelContext,
         transaction: &TransactionValues,
         accounts: impl Iterator<Item = &'a AccountValues>,
     ) -> Self {
         let account_values = accounts.map(|a| (a.id, a.into())).collect();
         Self {
+            base,
             transaction: transaction.into(),
             entry_values: HashMap::new(),
             account_values,
@@ -47,7 +50,7 @@ impl EvalContext {
         let mut context = CelMap::new();
         context.insert("vars", vars);
 
-        let mut ctx = initialize();
+        let mut ctx = self.base.clone();
         ctx.add_variable("context", context);
 
         ctx
@@ -83,6 +86,7 @@ mod tests {
                 "tx": "metadata",
                 "test": true,
             })),
+            tags: vec![],
         }
     }
 
@@ -130,7 +134,7 @@ mod tests {
         let account = account();
         let tx = transaction();
         let entry = entry(account.id, &tx);
-        let mut context = EvalContext::new(&tx, std::iter::once(&account));
+        let mut context = EvalContext::new(initialize(), &tx, std::iter::once(&account));
         let ctx = context.context_for_entry(&entry);
 
         let expr: CelExpression = "context.vars.transaction.id".parse().unwrap();
@@ -146,5 +150,22 @@ mod tests {
             .unwrap();
         let result: bool = expr.try_evaluate(&ctx).unwrap();
         assert!(result);
+
+        let expr: CelExpression = "has(context.vars.transaction.metadata.tx)".parse().unwrap();
+        let result: bool = expr.try_evaluate(&ctx).unwrap();
+        assert!(result);
+
+        let expr: CelExpression = "has(context.vars.transaction.metadata.missing)"
+            .parse()
+            .unwrap();
+        let result: bool = expr.try_evaluate(&ctx).unwrap();
+        assert!(!result);
+
+        let expr: CelExpression =
+            "context.vars.account.code.startsWith('co') && context.vars.account.metadata.account.contains('meta')"
+                .parse()
+                .unwrap();
+        let result: bool = expr.try_evaluate(&ctx).unwrap();
+        assert!(result);
     }
 }
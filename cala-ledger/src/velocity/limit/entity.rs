@@ -78,6 +78,10 @@ impl NewVelocityLimit {
     pub(super) fn data_source(&self) -> DataSource {
         DataSource::Local
     }
+
+    pub(in crate::velocity) fn params(&self) -> &[NewParamDefinition] {
+        self.params.as_deref().unwrap_or_default()
+    }
 }
 
 impl IntoEvents<VelocityLimitEvent> for NewVelocityLimit {
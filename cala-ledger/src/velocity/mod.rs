@@ -11,6 +11,7 @@ use sqlx::PgPool;
 use std::collections::HashMap;
 
 use cala_types::{entry::EntryValues, transaction::TransactionValues};
+use cel_interpreter::{CelContext, CelTypeContext};
 
 pub use crate::param::Params;
 use crate::{ledger_operation::*, outbox::*};
@@ -29,15 +30,24 @@ pub struct Velocities {
     controls: VelocityControlRepo,
     account_controls: AccountControls,
     balances: VelocityBalances,
+    cel_context: CelContext,
+    cel_types: CelTypeContext,
 }
 
 impl Velocities {
-    pub(crate) fn new(pool: &PgPool, outbox: Outbox) -> Self {
+    pub(crate) fn new(
+        pool: &PgPool,
+        outbox: Outbox,
+        cel_context: CelContext,
+        cel_types: CelTypeContext,
+    ) -> Self {
         Self {
             limits: VelocityLimitRepo::new(pool),
             controls: VelocityControlRepo::new(pool),
-            account_controls: AccountControls::new(pool),
-            balances: VelocityBalances::new(pool),
+            account_controls: AccountControls::new(pool, cel_context.clone()),
+            balances: VelocityBalances::new(pool, cel_context.clone()),
+            cel_context,
+            cel_types,
             pool: pool.clone(),
             outbox,
         }
@@ -58,6 +68,9 @@ impl Velocities {
         db: &mut LedgerOperation<'_>,
         new_limit: NewVelocityLimit,
     ) -> Result<VelocityLimit, VelocityError> {
+        for param in new_limit.params() {
+            param.check_exprs(&self.cel_context, &self.cel_types)?;
+        }
         let res = self.limits.create_in_op(db.op(), new_limit).await?;
         Ok(res)
     }
@@ -0,0 +1,168 @@
+mod helpers;
+
+use rand::distr::{Alphanumeric, SampleString};
+
+use cala_ledger::{account::*, account_set::*, primitives::*, *};
+
+#[tokio::test]
+async fn list_filtered() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let prefix = Alphanumeric.sample_string(&mut rand::rng(), 16);
+    let new_account = |suffix: &str, status: Status, region: &str| {
+        NewAccount::builder()
+            .id(AccountId::new())
+            .code(format!("{prefix}-{suffix}"))
+            .name(format!("{prefix}-{suffix}"))
+            .normal_balance_type(DebitOrCredit::Credit)
+            .status(status)
+            .metadata(serde_json::json!({ "region": region, "tier": 1 }))
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+    let locked_eu = cala
+        .accounts()
+        .create(new_account("a", Status::Locked, "EU"))
+        .await?;
+    cala.accounts()
+        .create(new_account("b", Status::Active, "EU"))
+        .await?;
+    let locked_us = cala
+        .accounts()
+        .create(new_account("c", Status::Locked, "US"))
+        .await?;
+
+    let query_args = es_entity::PaginatedQueryArgs {
+        first: 10,
+        after: None,
+    };
+    let ret = cala
+        .accounts()
+        .list_filtered(
+            AccountsFilter {
+                status: Some(Status::Locked),
+                normal_balance_type: Some(DebitOrCredit::Credit),
+                code_prefix: Some(prefix.clone()),
+                metadata: Some(serde_json::json!({ "region": "EU" })),
+                ..Default::default()
+            },
+            query_args,
+        )
+        .await?;
+    assert_eq!(ret.entities.len(), 1);
+    assert_eq!(ret.entities[0].id(), locked_eu.id());
+
+    let ret = cala
+        .accounts()
+        .list_filtered(
+            AccountsFilter {
+                code_prefix: Some(format!("{}_", &prefix[..15])),
+                ..Default::default()
+            },
+            Default::default(),
+        )
+        .await?;
+    assert!(ret.entities.is_empty());
+
+    let query_args = es_entity::PaginatedQueryArgs {
+        first: 1,
+        after: None,
+    };
+    let filter = AccountsFilter {
+        status: Some(Status::Locked),
+        code_prefix: Some(prefix.clone()),
+        ..Default::default()
+    };
+    let first_page = cala
+        .accounts()
+        .list_filtered(filter.clone(), query_args)
+        .await?;
+    assert!(first_page.has_next_page);
+    let query_args = es_entity::PaginatedQueryArgs {
+        first: 1,
+        after: first_page.end_cursor,
+    };
+    let second_page = cala.accounts().list_filtered(filter, query_args).await?;
+    assert!(!second_page.has_next_page);
+    assert_eq!(second_page.entities[0].id(), locked_us.id());
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name(format!("{prefix}-set"))
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let set = cala.account_sets().create(set).await?;
+    cala.account_sets()
+        .add_member(set.id(), locked_us.id())
+        .await?;
+
+    let query_args = es_entity::PaginatedQueryArgs {
+        first: 10,
+        after: None,
+    };
+    let ret = cala
+        .accounts()
+        .list_filtered(
+            AccountsFilter {
+                member_of: Some(set.id()),
+                ..Default::default()
+            },
+            query_args,
+        )
+        .await?;
+    assert_eq!(ret.entities.len(), 1);
+    assert_eq!(ret.entities[0].id(), locked_us.id());
+
+    let query_args = es_entity::PaginatedQueryArgs {
+        first: 10,
+        after: None,
+    };
+    let ret = cala
+        .accounts()
+        .list_filtered(
+            AccountsFilter {
+                code_prefix: Some(prefix),
+                is_account_set: Some(false),
+                ..Default::default()
+            },
+            query_args,
+        )
+        .await?;
+    assert_eq!(ret.entities.len(), 3);
+
+    let mut locked = Vec::new();
+    let mut after = None;
+    loop {
+        let ret = cala
+            .accounts()
+            .list_filtered(
+                AccountsFilter {
+                    status: Some(Status::Locked),
+                    ..Default::default()
+                },
+                es_entity::PaginatedQueryArgs { first: 100, after },
+            )
+            .await?;
+        assert!(ret
+            .entities
+            .iter()
+            .all(|a| a.values().status == Status::Locked));
+        locked.extend(ret.entities.iter().map(|a| a.id()));
+        if !ret.has_next_page {
+            break;
+        }
+        after = ret.end_cursor;
+    }
+    assert!(locked.contains(&locked_eu.id()));
+    assert!(locked.contains(&locked_us.id()));
+
+    Ok(())
+}
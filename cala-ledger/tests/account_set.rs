@@ -490,3 +490,508 @@ async fn list_members_by_external_id() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn hierarchy_traversal() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+    let new_journal = helpers::test_journal();
+    let journal = cala.journals().create(new_journal).await.unwrap();
+
+    let (one, two) = helpers::test_accounts();
+    let account_one = cala.accounts().create(one).await.unwrap();
+    let account_two = cala.accounts().create(two).await.unwrap();
+
+    let root = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("ROOT")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let root = cala.account_sets().create(root).await.unwrap();
+    let child = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("CHILD")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let child = cala.account_sets().create(child).await.unwrap();
+    let grandchild = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("GRANDCHILD")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let grandchild = cala.account_sets().create(grandchild).await.unwrap();
+
+    cala.account_sets()
+        .add_member(root.id(), account_one.id())
+        .await?;
+    cala.account_sets()
+        .add_member(root.id(), child.id())
+        .await?;
+    cala.account_sets()
+        .add_member(child.id(), grandchild.id())
+        .await?;
+    cala.account_sets()
+        .add_member(grandchild.id(), account_two.id())
+        .await?;
+
+    let ret = cala
+        .account_sets()
+        .list_descendants(root.id(), false, Default::default())
+        .await?;
+    assert_eq!(ret.entities.len(), 4);
+    assert!(!ret.has_next_page);
+    let deepest = ret
+        .entities
+        .iter()
+        .find(|node| node.id == AccountSetMemberId::from(account_two.id()))
+        .expect("account two is a descendant");
+    assert_eq!(deepest.depth, 3);
+    assert_eq!(deepest.path, vec![child.id(), grandchild.id()]);
+
+    let ret = cala
+        .account_sets()
+        .list_descendants(root.id(), true, Default::default())
+        .await?;
+    let mut leaves: Vec<_> = ret.entities.iter().map(|node| node.id).collect();
+    leaves.sort_by_key(|id| format!("{id:?}"));
+    let mut expected = vec![
+        AccountSetMemberId::from(account_one.id()),
+        AccountSetMemberId::from(account_two.id()),
+    ];
+    expected.sort_by_key(|id| format!("{id:?}"));
+    assert_eq!(leaves, expected);
+
+    let mut all = Vec::new();
+    let mut query_args = es_entity::PaginatedQueryArgs {
+        first: 1,
+        after: None,
+    };
+    loop {
+        let ret = cala
+            .account_sets()
+            .list_descendants(root.id(), false, query_args)
+            .await?;
+        all.extend(ret.entities.iter().map(|node| node.id));
+        match ret.into_next_query() {
+            Some(next) => query_args = next,
+            None => break,
+        }
+    }
+    assert_eq!(all.len(), 4);
+
+    let ret = cala
+        .account_sets()
+        .list_ancestors(account_two.id(), Default::default())
+        .await?;
+    assert_eq!(ret.entities.len(), 3);
+    let top = ret
+        .entities
+        .iter()
+        .find(|node| node.id == AccountSetMemberId::from(root.id()))
+        .expect("root is an ancestor");
+    assert_eq!(top.depth, 3);
+    assert_eq!(top.path, vec![grandchild.id(), child.id()]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hierarchy_too_deep() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+    let new_journal = helpers::test_journal();
+    let journal = cala.journals().create(new_journal).await.unwrap();
+
+    let mut chain = Vec::new();
+    for depth in 0..=account_set::MAX_HIERARCHY_DEPTH + 1 {
+        let set = NewAccountSet::builder()
+            .id(AccountSetId::new())
+            .name(format!("DEPTH-{depth}"))
+            .journal_id(journal.id())
+            .build()
+            .unwrap();
+        let set = cala.account_sets().create(set).await?;
+        if let Some(parent) = chain.last() {
+            cala.account_sets().add_member(*parent, set.id()).await?;
+        }
+        chain.push(set.id());
+    }
+
+    let res = cala
+        .account_sets()
+        .list_descendants(chain[0], false, Default::default())
+        .await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::HierarchyTooDeep(_))
+    ));
+    let res = cala
+        .account_sets()
+        .list_ancestors(*chain.last().unwrap(), Default::default())
+        .await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::HierarchyTooDeep(_))
+    ));
+
+    let ret = cala
+        .account_sets()
+        .list_descendants(chain[1], false, Default::default())
+        .await?;
+    assert_eq!(
+        ret.entities.len(),
+        account_set::MAX_HIERARCHY_DEPTH as usize
+    );
+    let ret = cala
+        .account_sets()
+        .list_ancestors(chain[chain.len() - 2], Default::default())
+        .await?;
+    assert_eq!(
+        ret.entities.len(),
+        account_set::MAX_HIERARCHY_DEPTH as usize
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_membership() -> anyhow::Result<()> {
+    let btc: Currency = "BTC".parse().unwrap();
+
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let new_journal = helpers::test_journal();
+    let journal = cala.journals().create(new_journal).await.unwrap();
+
+    let (sender, receiver) = helpers::test_accounts();
+    let sender_account = cala.accounts().create(sender).await.unwrap();
+    let recipient_account = cala.accounts().create(receiver).await.unwrap();
+
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let new_template = helpers::currency_conversion_template(&tx_code);
+    cala.tx_templates().create(new_template).await.unwrap();
+
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id().to_string());
+    params.insert("sender", sender_account.id());
+    params.insert("recipient", recipient_account.id());
+    cala.post_transaction(TransactionId::new(), &tx_code, params)
+        .await
+        .unwrap();
+
+    let child_set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("Child")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let child_set = cala.account_sets().create(child_set).await.unwrap();
+    let parent_set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("Parent")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let parent_set = cala.account_sets().create(parent_set).await.unwrap();
+    cala.account_sets()
+        .add_member(parent_set.id(), child_set.id())
+        .await
+        .unwrap();
+
+    let other_journal = cala.journals().create(helpers::test_journal()).await?;
+    let other_set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("Other")
+        .journal_id(other_journal.id())
+        .build()
+        .unwrap();
+    let other_set = cala.account_sets().create(other_set).await.unwrap();
+    let res = cala
+        .account_sets()
+        .add_members(
+            child_set.id(),
+            vec![
+                AccountSetMemberId::from(recipient_account.id()),
+                AccountSetMemberId::from(other_set.id()),
+            ],
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::JournalIdMismatch)
+    ));
+    let res = cala
+        .account_sets()
+        .add_members(
+            child_set.id(),
+            vec![AccountSetMemberId::from(AccountId::from(other_set.id()))],
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::JournalIdMismatch)
+    ));
+    let res = cala
+        .account_sets()
+        .add_members(
+            child_set.id(),
+            vec![AccountSetMemberId::from(AccountId::new())],
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::AccountError(
+            account::error::AccountError::CouldNotFindById(_)
+        ))
+    ));
+
+    let empty_set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("Empty")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let empty_set = cala.account_sets().create(empty_set).await.unwrap();
+    let members = vec![
+        AccountSetMemberId::from(recipient_account.id()),
+        AccountSetMemberId::from(empty_set.id()),
+    ];
+    let with_duplicates = members.iter().chain(members.iter()).copied().collect();
+    cala.account_sets()
+        .add_members(child_set.id(), with_duplicates)
+        .await
+        .unwrap();
+    let res = cala
+        .account_sets()
+        .add_members(
+            child_set.id(),
+            vec![AccountSetMemberId::from(recipient_account.id())],
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::MemberAlreadyAdded)
+    ));
+
+    let recipient_balance = cala
+        .balances()
+        .find(journal.id(), recipient_account.id(), btc)
+        .await?;
+    let child_balance = cala
+        .balances()
+        .find(journal.id(), child_set.id(), btc)
+        .await?;
+    let parent_balance = cala
+        .balances()
+        .find(journal.id(), parent_set.id(), btc)
+        .await?;
+    assert_eq!(recipient_balance.settled(), child_balance.settled());
+    assert_eq!(recipient_balance.settled(), parent_balance.settled());
+
+    let query_args = es_entity::PaginatedQueryArgs {
+        first: 5,
+        after: None,
+    };
+    let ret = cala
+        .account_sets()
+        .list_members_by_created_at(child_set.id(), query_args)
+        .await?;
+    assert_eq!(ret.entities.len(), 2);
+
+    cala.account_sets()
+        .remove_members(child_set.id(), members)
+        .await
+        .unwrap();
+    let parent_balance = cala
+        .balances()
+        .find(journal.id(), parent_set.id(), btc)
+        .await?;
+    assert_eq!(parent_balance.settled(), rust_decimal::Decimal::ZERO);
+
+    let query_args = es_entity::PaginatedQueryArgs {
+        first: 5,
+        after: None,
+    };
+    let ret = cala
+        .account_sets()
+        .list_members_by_created_at(child_set.id(), query_args)
+        .await?;
+    assert!(ret.entities.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn archive() -> anyhow::Result<()> {
+    let btc: Currency = "BTC".parse().unwrap();
+
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let new_journal = helpers::test_journal();
+    let journal = cala.journals().create(new_journal).await.unwrap();
+
+    let (sender, receiver) = helpers::test_accounts();
+    let sender_account = cala.accounts().create(sender).await.unwrap();
+    let recipient_account = cala.accounts().create(receiver).await.unwrap();
+
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let new_template = helpers::currency_conversion_template(&tx_code);
+    cala.tx_templates().create(new_template).await.unwrap();
+
+    let name = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let child_set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name(name.clone())
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let child_set = cala.account_sets().create(child_set).await.unwrap();
+    let parent_set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("Parent")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let parent_set = cala.account_sets().create(parent_set).await.unwrap();
+    let other_parent_set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("Other Parent")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let other_parent_set = cala.account_sets().create(other_parent_set).await.unwrap();
+    let grand_parent_set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("Grand Parent")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let grand_parent_set = cala.account_sets().create(grand_parent_set).await.unwrap();
+    cala.account_sets()
+        .add_member(child_set.id(), recipient_account.id())
+        .await
+        .unwrap();
+    cala.account_sets()
+        .add_member(parent_set.id(), child_set.id())
+        .await
+        .unwrap();
+    cala.account_sets()
+        .add_member(other_parent_set.id(), child_set.id())
+        .await
+        .unwrap();
+    cala.account_sets()
+        .add_member(grand_parent_set.id(), parent_set.id())
+        .await
+        .unwrap();
+
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id().to_string());
+    params.insert("sender", sender_account.id());
+    params.insert("recipient", recipient_account.id());
+    cala.post_transaction(TransactionId::new(), &tx_code, params.clone())
+        .await
+        .unwrap();
+
+    let res = cala.account_sets().archive(child_set.id(), false).await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::ArchiveWithMembers(_))
+    ));
+    assert!(!cala
+        .account_sets()
+        .find(child_set.id())
+        .await?
+        .is_archived());
+
+    cala.account_sets().archive(child_set.id(), true).await?;
+
+    for set_id in [
+        parent_set.id(),
+        other_parent_set.id(),
+        grand_parent_set.id(),
+    ] {
+        let balance = cala.balances().find(journal.id(), set_id, btc).await?;
+        assert_eq!(balance.settled(), rust_decimal::Decimal::ZERO);
+    }
+    let child_balance = cala
+        .balances()
+        .find(journal.id(), child_set.id(), btc)
+        .await?;
+    assert_eq!(child_balance.settled(), rust_decimal::Decimal::ZERO);
+
+    cala.post_transaction(TransactionId::new(), &tx_code, params)
+        .await
+        .unwrap();
+    let after_balance = cala
+        .balances()
+        .find(journal.id(), child_set.id(), btc)
+        .await?;
+    assert_eq!(after_balance.details.version, child_balance.details.version);
+
+    let query_args = es_entity::PaginatedQueryArgs {
+        first: 5,
+        after: None,
+    };
+    let ret = cala.account_sets().list_for_name(name, query_args).await?;
+    assert!(ret.entities.is_empty());
+
+    let res = cala
+        .account_sets()
+        .add_member(parent_set.id(), child_set.id())
+        .await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::Archived(_))
+    ));
+
+    let res = cala
+        .account_sets()
+        .add_member(child_set.id(), sender_account.id())
+        .await;
+    assert!(matches!(
+        res,
+        Err(account_set::error::AccountSetError::Archived(_))
+    ));
+
+    let archived = cala.account_sets().find(child_set.id()).await?;
+    assert!(archived.is_archived());
+
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id().to_string());
+    params.insert("sender", sender_account.id());
+    params.insert("recipient", AccountId::from(child_set.id()));
+    let res = cala
+        .post_transaction(TransactionId::new(), &tx_code, params)
+        .await;
+    assert!(matches!(
+        res,
+        Err(cala_ledger::error::LedgerError::AccountSetError(
+            account_set::error::AccountSetError::Archived(_)
+        ))
+    ));
+
+    cala.account_sets().archive(child_set.id(), false).await?;
+
+    Ok(())
+}
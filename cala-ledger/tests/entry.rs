@@ -0,0 +1,151 @@
+mod helpers;
+
+use chrono::NaiveDate;
+use rand::distr::{Alphanumeric, SampleString};
+
+use cala_ledger::{account_set::*, entry::*, tx_template::*, *};
+
+#[tokio::test]
+async fn list_filtered() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+    let set = NewAccountSet::builder()
+        .id(AccountSetId::new())
+        .name("Entries Set")
+        .journal_id(journal.id())
+        .build()
+        .unwrap();
+    let set = cala.account_sets().create(set).await?;
+    cala.account_sets()
+        .add_member(set.id(), sender.id())
+        .await?;
+
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    cala.tx_templates()
+        .create(helpers::currency_conversion_template(&tx_code))
+        .await?;
+
+    let dates = [
+        NaiveDate::from_ymd_opt(2024, 8, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 7, 15).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+    ];
+    let mut transactions = Vec::new();
+    for date in dates {
+        let mut params = Params::new();
+        params.insert("journal_id", journal.id());
+        params.insert("sender", sender.id());
+        params.insert("recipient", recipient.id());
+        params.insert("effective", date);
+        transactions.push(
+            cala.post_transaction(TransactionId::new(), &tx_code, params)
+                .await?,
+        );
+    }
+
+    let q3 = EntriesFilter {
+        effective_from: NaiveDate::from_ymd_opt(2024, 7, 1),
+        effective_until: NaiveDate::from_ymd_opt(2024, 9, 30),
+        currency: Some("USD".parse().unwrap()),
+        layer: Some(Layer::Settled),
+        direction: Some(DebitOrCredit::Debit),
+        ..Default::default()
+    };
+    let first_page = cala
+        .entries()
+        .list_filtered_for_account_id(
+            sender.id(),
+            q3.clone(),
+            es_entity::PaginatedQueryArgs {
+                first: 1,
+                after: None,
+            },
+            es_entity::ListDirection::Ascending,
+        )
+        .await?;
+    assert!(first_page.has_next_page);
+    assert_eq!(first_page.entities[0].values().entry_type, "TEST_USD_DR");
+    assert_eq!(
+        first_page.entities[0].values().transaction_id,
+        transactions[1].id()
+    );
+    let second_page = cala
+        .entries()
+        .list_filtered_for_account_id(
+            sender.id(),
+            q3,
+            es_entity::PaginatedQueryArgs {
+                first: 1,
+                after: first_page.end_cursor,
+            },
+            es_entity::ListDirection::Ascending,
+        )
+        .await?;
+    assert!(!second_page.has_next_page);
+    assert_eq!(
+        second_page.entities[0].values().transaction_id,
+        transactions[0].id()
+    );
+
+    let ret = cala
+        .entries()
+        .list_filtered_for_account_set_id(
+            set.id(),
+            EntriesFilter {
+                entry_type: Some("TEST_BTC_DR".to_string()),
+                ..Default::default()
+            },
+            es_entity::PaginatedQueryArgs {
+                first: 10,
+                after: None,
+            },
+            es_entity::ListDirection::Descending,
+        )
+        .await?;
+    let tx_ids: Vec<_> = ret
+        .entities
+        .iter()
+        .map(|e| e.values().transaction_id)
+        .collect();
+    assert_eq!(
+        tx_ids,
+        vec![
+            transactions[2].id(),
+            transactions[0].id(),
+            transactions[1].id()
+        ]
+    );
+
+    let ret = cala
+        .entries()
+        .list_filtered_for_journal_id(
+            journal.id(),
+            EntriesFilter {
+                layer: Some(Layer::Pending),
+                effective_from: NaiveDate::from_ymd_opt(2024, 9, 1),
+                ..Default::default()
+            },
+            es_entity::PaginatedQueryArgs {
+                first: 10,
+                after: None,
+            },
+            es_entity::ListDirection::Descending,
+        )
+        .await?;
+    assert_eq!(ret.entities.len(), 2);
+    assert!(ret
+        .entities
+        .iter()
+        .all(|e| e.values().transaction_id == transactions[2].id()));
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod helpers;
+
+use rand::distr::{Alphanumeric, SampleString};
+
+use cala_ledger::{journal::*, primitives::Status, *};
+
+#[tokio::test]
+async fn list() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    cala.journals().create(helpers::test_journal()).await?;
+    cala.journals().create(helpers::test_journal()).await?;
+    let name = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let locked = NewJournal::builder()
+        .id(JournalId::new())
+        .name(name)
+        .status(Status::Locked)
+        .build()
+        .unwrap();
+    let locked = cala.journals().create(locked).await?;
+
+    let ret = cala
+        .journals()
+        .list(
+            Some(Status::Locked),
+            es_entity::Sort {
+                by: JournalsSortBy::CreatedAt,
+                direction: es_entity::ListDirection::Descending,
+            },
+            es_entity::PaginatedQueryArgs {
+                first: 1,
+                after: None,
+            },
+        )
+        .await?;
+    assert_eq!(ret.entities[0].id(), locked.id());
+
+    let first_page = cala
+        .journals()
+        .list(
+            Some(Status::Active),
+            es_entity::Sort {
+                by: JournalsSortBy::Name,
+                direction: es_entity::ListDirection::Ascending,
+            },
+            es_entity::PaginatedQueryArgs {
+                first: 2,
+                after: None,
+            },
+        )
+        .await?;
+    assert_eq!(first_page.entities.len(), 2);
+    assert!(first_page.has_next_page);
+    assert!(first_page.entities[0].values().name <= first_page.entities[1].values().name);
+
+    let second_page = cala
+        .journals()
+        .list(
+            Some(Status::Active),
+            es_entity::Sort {
+                by: JournalsSortBy::Name,
+                direction: es_entity::ListDirection::Ascending,
+            },
+            es_entity::PaginatedQueryArgs {
+                first: 2,
+                after: first_page.end_cursor,
+            },
+        )
+        .await?;
+    assert!(first_page.entities[1].values().name <= second_page.entities[0].values().name);
+    assert!(second_page
+        .entities
+        .iter()
+        .all(|journal| journal.values().status == Status::Active));
+
+    Ok(())
+}
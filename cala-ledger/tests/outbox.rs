@@ -0,0 +1,181 @@
+mod helpers;
+
+use futures::StreamExt;
+use rand::distr::{Alphanumeric, SampleString};
+
+use cala_ledger::{outbox::*, tx_template::*, *};
+
+#[tokio::test]
+async fn filtered_listener() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let watched = cala.journals().create(helpers::test_journal()).await?;
+    let other = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    cala.tx_templates()
+        .create(helpers::currency_conversion_template(&tx_code))
+        .await?;
+
+    let filter = OutboxEventFilter {
+        payload_types: vec![OutboxEventPayloadType::TransactionCreated],
+        journal_ids: vec![watched.id()],
+        ..Default::default()
+    };
+    let mut listener = cala
+        .register_filtered_outbox_listener(None, filter.clone())
+        .await?;
+
+    let post = |journal_id: JournalId| {
+        let mut params = Params::new();
+        params.insert("journal_id", journal_id);
+        params.insert("sender", sender.id());
+        params.insert("recipient", recipient.id());
+        cala.post_transaction(TransactionId::new(), &tx_code, params)
+    };
+    let skipped = post(other.id()).await?;
+    let first = post(watched.id()).await?;
+    let second = post(watched.id()).await?;
+
+    let mut received = Vec::new();
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while let Some(event) = listener.next().await {
+            let event = event.expect("outbox event");
+            assert!(filter.matches(&event.payload));
+            received.push(event);
+            if received.len() == 2 {
+                break;
+            }
+        }
+    })
+    .await?;
+
+    let ids: Vec<_> = received
+        .iter()
+        .map(|event| match &event.payload {
+            OutboxEventPayload::TransactionCreated { transaction, .. } => transaction.id,
+            payload => panic!("unexpected payload {payload:?}"),
+        })
+        .collect();
+    assert_eq!(ids, vec![first.id(), second.id()]);
+    assert!(!ids.contains(&skipped.id()));
+    // Sequences are those of the unfiltered outbox - so there are gaps to resume from
+    assert!(received[1].sequence > received[0].sequence.next());
+
+    let mut resumed = cala
+        .register_filtered_outbox_listener(Some(received[0].sequence), filter)
+        .await?;
+    let event = tokio::time::timeout(std::time::Duration::from_secs(5), resumed.next())
+        .await?
+        .expect("resumed event")?;
+    assert_eq!(event.sequence, received[1].sequence);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn durable_consumer() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    cala.tx_templates()
+        .create(helpers::currency_conversion_template(&tx_code))
+        .await?;
+
+    let name = format!(
+        "consumer-{}",
+        Alphanumeric.sample_string(&mut rand::rng(), 16)
+    );
+    let filter = OutboxEventFilter {
+        payload_types: vec![OutboxEventPayloadType::TransactionCreated],
+        journal_ids: vec![journal.id()],
+        ..Default::default()
+    };
+    let mut listener = cala.register_outbox_consumer(&name, filter.clone()).await?;
+
+    let post = || {
+        let mut params = Params::new();
+        params.insert("journal_id", journal.id());
+        params.insert("sender", sender.id());
+        params.insert("recipient", recipient.id());
+        cala.post_transaction(TransactionId::new(), &tx_code, params)
+    };
+    post().await?;
+    let second = post().await?;
+
+    let first_event = tokio::time::timeout(std::time::Duration::from_secs(5), listener.next())
+        .await?
+        .expect("first event")?;
+    let consumer = cala
+        .ack_outbox_consumer(&name, first_event.sequence)
+        .await?;
+    assert_eq!(consumer.last_acked_sequence, first_event.sequence);
+    assert!(consumer.lag > 0);
+    drop(listener);
+
+    // Acks never move the position backwards
+    let consumer = cala
+        .ack_outbox_consumer(&name, EventSequence::BEGIN)
+        .await?;
+    assert_eq!(consumer.last_acked_sequence, first_event.sequence);
+
+    // Acks can't skip past events that don't exist yet
+    let res = cala
+        .ack_outbox_consumer(
+            &name,
+            EventSequence::from(u64::from(first_event.sequence) + 1_000_000),
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(cala_ledger::error::LedgerError::OutboxError(
+            cala_ledger::outbox::error::OutboxError::SequenceNotYetKnown { .. }
+        ))
+    ));
+
+    let mut resumed = cala.register_outbox_consumer(&name, filter).await?;
+    let event = tokio::time::timeout(std::time::Duration::from_secs(5), resumed.next())
+        .await?
+        .expect("resumed event")?;
+    match event.payload {
+        OutboxEventPayload::TransactionCreated { transaction, .. } => {
+            assert_eq!(transaction.id, second.id())
+        }
+        payload => panic!("unexpected payload {payload:?}"),
+    }
+
+    assert!(cala
+        .list_outbox_consumers()
+        .await?
+        .iter()
+        .any(|consumer| consumer.name == name));
+    cala.delete_outbox_consumer(&name).await?;
+    assert!(matches!(
+        cala.find_outbox_consumer(&name).await,
+        Err(cala_ledger::error::LedgerError::OutboxError(
+            cala_ledger::outbox::error::OutboxError::ConsumerNotFound(_)
+        ))
+    ));
+    assert!(cala
+        .ack_outbox_consumer(&name, event.sequence)
+        .await
+        .is_err());
+
+    Ok(())
+}
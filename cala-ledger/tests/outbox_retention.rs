@@ -0,0 +1,125 @@
+mod helpers;
+
+use futures::StreamExt;
+use rand::distr::{Alphanumeric, SampleString};
+
+use cala_ledger::{outbox::*, tx_template::*, *};
+
+// Pruning affects the whole outbox so this test lives in its own binary
+// to avoid interfering with listeners of concurrently running tests.
+#[tokio::test]
+async fn prune_outbox() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool.clone())
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+    for consumer in cala.list_outbox_consumers().await? {
+        cala.delete_outbox_consumer(&consumer.name).await?;
+    }
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    cala.tx_templates()
+        .create(helpers::currency_conversion_template(&tx_code))
+        .await?;
+
+    let name = format!(
+        "consumer-{}",
+        Alphanumeric.sample_string(&mut rand::rng(), 16)
+    );
+    let filter = OutboxEventFilter {
+        payload_types: vec![OutboxEventPayloadType::TransactionCreated],
+        journal_ids: vec![journal.id()],
+        ..Default::default()
+    };
+    drop(cala.register_outbox_consumer(&name, filter.clone()).await?);
+    let checkpoint = cala.find_outbox_consumer(&name).await?.last_acked_sequence;
+
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id());
+    params.insert("sender", sender.id());
+    params.insert("recipient", recipient.id());
+    let tx = cala
+        .post_transaction(TransactionId::new(), &tx_code, params)
+        .await?;
+
+    let retention = OutboxRetentionConfig {
+        max_events: Some(0),
+        batch_size: 2,
+        archive: true,
+        ..Default::default()
+    };
+    cala.prune_outbox(&retention).await?;
+
+    // Events not acknowledged by the consumer are retained
+    let mut listener = cala.register_outbox_consumer(&name, filter.clone()).await?;
+    let event = tokio::time::timeout(std::time::Duration::from_secs(5), listener.next())
+        .await?
+        .expect("retained event")?;
+    match &event.payload {
+        OutboxEventPayload::TransactionCreated { transaction, .. } => {
+            assert_eq!(transaction.id, tx.id())
+        }
+        payload => panic!("unexpected payload {payload:?}"),
+    }
+    let consumer = cala.ack_outbox_consumer(&name, event.sequence).await?;
+    assert!(consumer.last_acked_sequence > checkpoint);
+
+    cala.prune_outbox(&retention).await?;
+    let archived: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM cala_outbox_events_archive WHERE sequence = $1")
+            .bind(event.sequence)
+            .fetch_one(&pool)
+            .await?;
+    assert_eq!(archived, 1);
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM cala_outbox_events WHERE sequence <= $1")
+            .bind(event.sequence)
+            .fetch_one(&pool)
+            .await?;
+    assert_eq!(remaining, 0);
+
+    let res = cala.register_outbox_listener(Some(checkpoint)).await;
+    assert!(matches!(
+        res,
+        Err(cala_ledger::error::LedgerError::OutboxError(
+            cala_ledger::outbox::error::OutboxError::SequenceNoLongerAvailable { .. }
+        ))
+    ));
+    // A consumer that resumes from its acknowledged position is unaffected
+    drop(cala.register_outbox_consumer(&name, filter.clone()).await?);
+    cala.delete_outbox_consumer(&name).await?;
+
+    // Consumers that stopped acknowledging don't hold back pruning once expired
+    let stale = format!("stale-{}", Alphanumeric.sample_string(&mut rand::rng(), 16));
+    drop(cala.register_outbox_consumer(&stale, filter).await?);
+    sqlx::query(
+        "UPDATE cala_outbox_consumers SET modified_at = NOW() - INTERVAL '1 day' WHERE name = $1",
+    )
+    .bind(&stale)
+    .execute(&pool)
+    .await?;
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id());
+    params.insert("sender", sender.id());
+    params.insert("recipient", recipient.id());
+    cala.post_transaction(TransactionId::new(), &tx_code, params)
+        .await?;
+    let retention = OutboxRetentionConfig {
+        consumer_expiry: Some(std::time::Duration::from_secs(3600)),
+        ..retention
+    };
+    cala.prune_outbox(&retention).await?;
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cala_outbox_events")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(remaining, 0);
+    cala.delete_outbox_consumer(&stale).await?;
+
+    Ok(())
+}
@@ -81,3 +81,279 @@ async fn transaction_post() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn list_transactions() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let params = vec![
+        NewParamDefinition::builder()
+            .name("journal_id")
+            .r#type(ParamDataType::Uuid)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("correlation_id")
+            .r#type(ParamDataType::String)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("effective")
+            .r#type(ParamDataType::Date)
+            .build()
+            .unwrap(),
+    ];
+    let entries = vec![
+        NewTxTemplateEntry::builder()
+            .entry_type("'TEST_DR'")
+            .account_id(format!("uuid('{}')", sender.id()))
+            .layer("SETTLED")
+            .direction("DEBIT")
+            .units("decimal('10')")
+            .currency("'USD'")
+            .build()
+            .unwrap(),
+        NewTxTemplateEntry::builder()
+            .entry_type("'TEST_CR'")
+            .account_id(format!("uuid('{}')", recipient.id()))
+            .layer("SETTLED")
+            .direction("CREDIT")
+            .units("decimal('10')")
+            .currency("'USD'")
+            .build()
+            .unwrap(),
+    ];
+    let template = NewTxTemplate::builder()
+        .id(uuid::Uuid::new_v4())
+        .code(&tx_code)
+        .params(params)
+        .transaction(
+            NewTxTemplateTransaction::builder()
+                .effective("params.effective")
+                .journal_id("params.journal_id")
+                .correlation_id("params.correlation_id")
+                .build()
+                .unwrap(),
+        )
+        .entries(entries)
+        .build()
+        .unwrap();
+    let template = cala.tx_templates().create(template).await?;
+
+    let correlation_id = Alphanumeric.sample_string(&mut rand::rng(), 16);
+    let mut posted = Vec::new();
+    for (day, correlation_id) in [
+        (1, correlation_id.clone()),
+        (2, correlation_id.clone()),
+        (3, "unrelated".to_string()),
+    ] {
+        let mut params = Params::new();
+        params.insert("journal_id", journal.id());
+        params.insert("correlation_id", correlation_id);
+        params.insert(
+            "effective",
+            chrono::NaiveDate::from_ymd_opt(2024, 3, day).unwrap(),
+        );
+        posted.push(
+            cala.post_transaction(TransactionId::new(), &tx_code, params)
+                .await?,
+        );
+    }
+
+    let ret = cala
+        .transactions()
+        .list_for_correlation_id(
+            correlation_id,
+            es_entity::PaginatedQueryArgs {
+                first: 10,
+                after: None,
+            },
+            es_entity::ListDirection::Ascending,
+        )
+        .await?;
+    let ids: Vec<_> = ret.entities.iter().map(|tx| tx.id()).collect();
+    assert_eq!(ids, vec![posted[0].id(), posted[1].id()]);
+
+    let filter = transaction::TransactionsFilter {
+        tx_template_id: Some(template.id()),
+        effective_from: chrono::NaiveDate::from_ymd_opt(2024, 3, 2),
+        ..Default::default()
+    };
+    let first_page = cala
+        .transactions()
+        .list_for_journal_id(
+            journal.id(),
+            filter.clone(),
+            es_entity::PaginatedQueryArgs {
+                first: 1,
+                after: None,
+            },
+        )
+        .await?;
+    assert!(first_page.has_next_page);
+    assert_eq!(first_page.entities[0].id(), posted[2].id());
+    let second_page = cala
+        .transactions()
+        .list_for_journal_id(
+            journal.id(),
+            filter,
+            es_entity::PaginatedQueryArgs {
+                first: 1,
+                after: first_page.end_cursor,
+            },
+        )
+        .await?;
+    assert!(!second_page.has_next_page);
+    assert_eq!(second_page.entities[0].id(), posted[1].id());
+
+    let ret = cala
+        .transactions()
+        .list_for_journal_id(
+            journal.id(),
+            transaction::TransactionsFilter {
+                created_until: Some(posted[0].created_at()),
+                ..Default::default()
+            },
+            es_entity::PaginatedQueryArgs {
+                first: 10,
+                after: None,
+            },
+        )
+        .await?;
+    assert!(ret.entities.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn annotate_transaction() -> anyhow::Result<()> {
+    use futures::StreamExt;
+
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    cala.tx_templates()
+        .create(helpers::currency_conversion_template(&tx_code))
+        .await?;
+
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id());
+    params.insert("sender", sender.id());
+    params.insert("recipient", recipient.id());
+    let tx = cala
+        .post_transaction(TransactionId::new(), &tx_code, params)
+        .await?;
+    let balance_before = cala
+        .balances()
+        .find(journal.id(), recipient.id(), "BTC".parse().unwrap())
+        .await?;
+
+    let mut listener = cala.register_outbox_listener(None).await?;
+
+    let annotation = transaction::NewTransactionAnnotation::builder()
+        .actor("support@example.com")
+        .metadata(serde_json::json!({ "dispute": true }))?
+        .tags(vec!["disputed".to_string()])
+        .build()?;
+    let annotated = cala.transactions().annotate(tx.id(), annotation).await?;
+    assert_eq!(
+        annotated.values().metadata,
+        Some(serde_json::json!({ "foo": "bar", "dispute": true }))
+    );
+    assert_eq!(annotated.values().tags, vec!["disputed".to_string()]);
+    assert_eq!(annotated.values().version, 2);
+
+    let annotation = transaction::NewTransactionAnnotation::builder()
+        .actor("recon-bot")
+        .tags(vec!["disputed".to_string(), "reconciled".to_string()])
+        .build()?;
+    cala.transactions().annotate(tx.id(), annotation).await?;
+
+    let tx = cala.transactions().find_by_id(tx.id()).await?;
+    assert_eq!(
+        tx.values().tags,
+        vec!["disputed".to_string(), "reconciled".to_string()]
+    );
+    let actors: Vec<_> = tx.annotations().map(|a| a.actor.as_str()).collect();
+    assert_eq!(actors, vec!["support@example.com", "recon-bot"]);
+    assert_eq!(tx.values().entry_ids.len(), 6);
+
+    let balance_after = cala
+        .balances()
+        .find(journal.id(), recipient.id(), "BTC".parse().unwrap())
+        .await?;
+    assert_eq!(
+        balance_before.details.version,
+        balance_after.details.version
+    );
+
+    let event = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        while let Some(event) = listener.next().await {
+            if let outbox::OutboxEventPayload::TransactionAnnotated {
+                transaction,
+                annotation,
+                ..
+            } = event.expect("outbox event").payload
+            {
+                if transaction.id == tx.id() {
+                    return Some(annotation);
+                }
+            }
+        }
+        None
+    })
+    .await?
+    .expect("annotation event");
+    assert_eq!(event.actor, "support@example.com");
+
+    let err = cala
+        .transactions()
+        .annotate(
+            tx.id(),
+            transaction::NewTransactionAnnotation::builder()
+                .actor("support@example.com")
+                .metadata("not an object")?
+                .build()?,
+        )
+        .await;
+    assert!(matches!(
+        err,
+        Err(transaction::error::TransactionError::AnnotationMetadataNotAnObject)
+    ));
+
+    let err = cala
+        .transactions()
+        .annotate(
+            tx.id(),
+            transaction::NewTransactionAnnotation::builder()
+                .actor("support@example.com")
+                .build()?,
+        )
+        .await;
+    assert!(matches!(
+        err,
+        Err(transaction::error::TransactionError::EmptyAnnotation)
+    ));
+
+    Ok(())
+}
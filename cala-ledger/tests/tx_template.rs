@@ -20,3 +20,600 @@ async fn duplicate_code() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn conditional_entries() -> anyhow::Result<()> {
+    use rand::distr::{Alphanumeric, SampleString};
+    use tx_template::*;
+
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let params = vec![
+        NewParamDefinition::builder()
+            .name("journal_id")
+            .r#type(ParamDataType::Uuid)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("sender")
+            .r#type(ParamDataType::Uuid)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("recipient")
+            .r#type(ParamDataType::Uuid)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("fee")
+            .r#type(ParamDataType::Decimal)
+            .build()
+            .unwrap(),
+    ];
+    let entries = vec![
+        NewTxTemplateEntry::builder()
+            .entry_type("'TRANSFER_DR'")
+            .account_id("params.sender")
+            .layer("SETTLED")
+            .direction("DEBIT")
+            .units("decimal('100')")
+            .currency("'USD'")
+            .build()
+            .unwrap(),
+        NewTxTemplateEntry::builder()
+            .entry_type("'TRANSFER_CR'")
+            .account_id("params.recipient")
+            .layer("SETTLED")
+            .direction("CREDIT")
+            .units("decimal('100')")
+            .currency("'USD'")
+            .build()
+            .unwrap(),
+        NewTxTemplateEntry::builder()
+            .entry_type("'FEE_DR'")
+            .account_id("params.recipient")
+            .layer("SETTLED")
+            .direction("DEBIT")
+            .units("params.fee")
+            .currency("'USD'")
+            .condition("params.fee > decimal('0')")
+            .build()
+            .unwrap(),
+        NewTxTemplateEntry::builder()
+            .entry_type("'FEE_CR'")
+            .account_id("params.sender")
+            .layer("SETTLED")
+            .direction("CREDIT")
+            .units("params.fee")
+            .currency("'USD'")
+            .condition("params.fee > decimal('0')")
+            .build()
+            .unwrap(),
+    ];
+    let template = NewTxTemplate::builder()
+        .id(uuid::Uuid::new_v4())
+        .code(&tx_code)
+        .params(params)
+        .transaction(
+            NewTxTemplateTransaction::builder()
+                .effective("date()")
+                .journal_id("params.journal_id")
+                .build()
+                .unwrap(),
+        )
+        .entries(entries)
+        .build()
+        .unwrap();
+    cala.tx_templates().create(template).await?;
+
+    let post = |fee: &str| {
+        let mut params = Params::new();
+        params.insert("journal_id", journal.id());
+        params.insert("sender", sender.id());
+        params.insert("recipient", recipient.id());
+        params.insert("fee", fee.parse::<rust_decimal::Decimal>().unwrap());
+        cala.post_transaction(TransactionId::new(), &tx_code, params)
+    };
+
+    let tx = post("0").await?;
+    let entries = cala.entries().list_for_transaction_id(tx.id()).await?;
+    let types: Vec<_> = entries
+        .iter()
+        .map(|e| (e.values().sequence, e.values().entry_type.as_str()))
+        .collect();
+    assert_eq!(types, vec![(1, "TRANSFER_DR"), (2, "TRANSFER_CR")]);
+
+    let tx = post("2.5").await?;
+    let entries = cala.entries().list_for_transaction_id(tx.id()).await?;
+    let types: Vec<_> = entries
+        .iter()
+        .map(|e| (e.values().sequence, e.values().entry_type.as_str()))
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            (1, "TRANSFER_DR"),
+            (2, "TRANSFER_CR"),
+            (3, "FEE_DR"),
+            (4, "FEE_CR")
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn repeating_entries() -> anyhow::Result<()> {
+    use cel_interpreter::{CelArray, CelMap};
+    use rand::distr::{Alphanumeric, SampleString};
+    use tx_template::*;
+
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let first = cala.accounts().create(receiver).await?;
+    let (_, receiver) = helpers::test_accounts();
+    let second = cala.accounts().create(receiver).await?;
+
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let params = vec![
+        NewParamDefinition::builder()
+            .name("journal_id")
+            .r#type(ParamDataType::Uuid)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("sender")
+            .r#type(ParamDataType::Uuid)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("total")
+            .r#type(ParamDataType::Decimal)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("payouts")
+            .r#type(ParamDataType::List)
+            .build()
+            .unwrap(),
+    ];
+    let entries = vec![
+        NewTxTemplateEntry::builder()
+            .entry_type("'PAYOUT_DR'")
+            .account_id("params.sender")
+            .layer("SETTLED")
+            .direction("DEBIT")
+            .units("params.total")
+            .currency("'USD'")
+            .build()
+            .unwrap(),
+        NewTxTemplateEntry::builder()
+            .entry_type("'PAYOUT_CR'")
+            .account_id("payout.account")
+            .layer("SETTLED")
+            .direction("CREDIT")
+            .units("payout.amount")
+            .currency("'USD'")
+            .for_each("params.payouts", "payout")
+            .build()
+            .unwrap(),
+    ];
+    let template = NewTxTemplate::builder()
+        .id(uuid::Uuid::new_v4())
+        .code(&tx_code)
+        .params(params)
+        .transaction(
+            NewTxTemplateTransaction::builder()
+                .effective("date()")
+                .journal_id("params.journal_id")
+                .build()
+                .unwrap(),
+        )
+        .entries(entries)
+        .build()
+        .unwrap();
+    cala.tx_templates().create(template).await?;
+
+    let mut payouts = CelArray::new();
+    for (account_id, amount) in [(first.id(), "30"), (second.id(), "70")] {
+        let mut payout = CelMap::new();
+        payout.insert("account", account_id);
+        payout.insert("amount", amount.parse::<rust_decimal::Decimal>()?);
+        payouts.push(payout);
+    }
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id());
+    params.insert("sender", sender.id());
+    params.insert("total", rust_decimal::Decimal::from(100));
+    params.insert("payouts", payouts);
+    let tx = cala
+        .post_transaction(TransactionId::new(), &tx_code, params)
+        .await?;
+
+    let entries = cala.entries().list_for_transaction_id(tx.id()).await?;
+    let legs: Vec<_> = entries
+        .iter()
+        .map(|e| {
+            (
+                e.values().sequence,
+                e.values().account_id,
+                e.values().units.to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        legs,
+        vec![
+            (1, sender.id(), "100".to_string()),
+            (2, first.id(), "30".to_string()),
+            (3, second.id(), "70".to_string()),
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn param_constraints() -> anyhow::Result<()> {
+    use rand::distr::{Alphanumeric, SampleString};
+    use tx_template::*;
+
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let params = vec![
+        NewParamDefinition::builder()
+            .name("journal_id")
+            .r#type(ParamDataType::Uuid)
+            .required(true)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("sender")
+            .r#type(ParamDataType::Uuid)
+            .required(true)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("recipient")
+            .r#type(ParamDataType::Uuid)
+            .required(true)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("amount")
+            .r#type(ParamDataType::Decimal)
+            .required(true)
+            .min(rust_decimal::Decimal::ONE)
+            .max(rust_decimal::Decimal::ONE_HUNDRED)
+            .scale(2)
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("fee")
+            .r#type(ParamDataType::Decimal)
+            .default_expr("decimal('0')")
+            .predicate("params.fee < params.amount")
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("currency")
+            .r#type(ParamDataType::String)
+            .allowed_value("'USD'")
+            .allowed_value("'EUR'")
+            .default_expr("'USD'")
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("reference")
+            .r#type(ParamDataType::String)
+            .regex("^[A-Z]{3}-[0-9]+$")
+            .build()
+            .unwrap(),
+        NewParamDefinition::builder()
+            .name("memo")
+            .r#type(ParamDataType::String)
+            .predicate("size(params.memo) <= 10")
+            .build()
+            .unwrap(),
+    ];
+    let entries = vec![
+        NewTxTemplateEntry::builder()
+            .entry_type("'TRANSFER_DR'")
+            .account_id("params.sender")
+            .layer("SETTLED")
+            .direction("DEBIT")
+            .units("params.amount")
+            .currency("params.currency")
+            .build()
+            .unwrap(),
+        NewTxTemplateEntry::builder()
+            .entry_type("'TRANSFER_CR'")
+            .account_id("params.recipient")
+            .layer("SETTLED")
+            .direction("CREDIT")
+            .units("params.amount")
+            .currency("params.currency")
+            .build()
+            .unwrap(),
+    ];
+    let template = NewTxTemplate::builder()
+        .id(uuid::Uuid::new_v4())
+        .code(&tx_code)
+        .params(params)
+        .transaction(
+            NewTxTemplateTransaction::builder()
+                .effective("date()")
+                .journal_id("params.journal_id")
+                .build()
+                .unwrap(),
+        )
+        .entries(entries)
+        .build()
+        .unwrap();
+    cala.tx_templates().create(template).await?;
+
+    let mut params = Params::new();
+    params.insert("sender", sender.id());
+    params.insert("recipient", sender.id());
+    params.insert("amount", "100.005".parse::<rust_decimal::Decimal>()?);
+    params.insert("currency", "GBP");
+    params.insert("reference", "abc");
+    params.insert("fee", rust_decimal::Decimal::from(200));
+    let res = cala
+        .post_transaction(TransactionId::new(), &tx_code, params)
+        .await;
+    let Err(cala_ledger::error::LedgerError::TxTemplateError(TxTemplateError::ParamError(
+        ParamError::InvalidParams(violations),
+    ))) = res
+    else {
+        panic!("expected invalid params");
+    };
+    let mut names: Vec<_> = violations.iter().map(|v| v.name.as_str()).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "amount",
+            "amount",
+            "currency",
+            "fee",
+            "journal_id",
+            "reference"
+        ]
+    );
+
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id());
+    params.insert("sender", sender.id());
+    params.insert("recipient", recipient.id());
+    params.insert("amount", "99.5".parse::<rust_decimal::Decimal>()?);
+    params.insert("reference", "ABC-123");
+    cala.post_transaction(TransactionId::new(), &tx_code, params)
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn type_check_on_create() -> anyhow::Result<()> {
+    use rand::distr::{Alphanumeric, SampleString};
+    use tx_template::*;
+
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let template = |units: &str, account_id: &str| {
+        NewTxTemplate::builder()
+            .id(uuid::Uuid::new_v4())
+            .code(Alphanumeric.sample_string(&mut rand::rng(), 32))
+            .params(vec![
+                NewParamDefinition::builder()
+                    .name("account")
+                    .r#type(ParamDataType::Uuid)
+                    .build()
+                    .unwrap(),
+                NewParamDefinition::builder()
+                    .name("amount")
+                    .r#type(ParamDataType::Decimal)
+                    .build()
+                    .unwrap(),
+            ])
+            .transaction(
+                NewTxTemplateTransaction::builder()
+                    .effective("date()")
+                    .journal_id("uuid('00000000-0000-0000-0000-000000000001')")
+                    .build()
+                    .unwrap(),
+            )
+            .entries(vec![NewTxTemplateEntry::builder()
+                .entry_type("'TEST'")
+                .account_id(account_id)
+                .layer("SETTLED")
+                .direction("DEBIT")
+                .units(units)
+                .currency("'USD'")
+                .build()
+                .unwrap()])
+            .build()
+            .unwrap()
+    };
+
+    let res = cala
+        .tx_templates()
+        .create(template("params.amount + 'x'", "params.account"))
+        .await;
+    assert!(matches!(
+        res,
+        Err(TxTemplateError::TypeCheck(ref field, ref expr, _))
+            if field == "entries[0].units" && expr == "params.amount + 'x'"
+    ));
+
+    let res = cala
+        .tx_templates()
+        .create(template("params.amount", "params.sender"))
+        .await;
+    assert!(matches!(
+        res,
+        Err(TxTemplateError::TypeCheck(ref field, _, _)) if field == "entries[0].account_id"
+    ));
+
+    let res = cala
+        .tx_templates()
+        .create(template("'100'", "params.account"))
+        .await;
+    assert!(matches!(
+        res,
+        Err(TxTemplateError::TypeCheck(ref field, _, ref e))
+            if field == "entries[0].units"
+                && matches!(e.root_cause(), cel_interpreter::CelError::BadType(..))
+                && e.location().is_some()
+    ));
+
+    cala.tx_templates()
+        .create(template("params.amount", "params.account"))
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn host_registered_functions() -> anyhow::Result<()> {
+    use cel::*;
+    use rand::distr::{Alphanumeric, SampleString};
+    use tx_template::*;
+
+    let mut fx = CelPackage::default();
+    fx.add_function(
+        "rate",
+        CelSignature::new([CelType::String], CelType::Decimal),
+        |args| match args[0] {
+            CelValue::String(ref pair) if pair.as_str() == "EURUSD" => {
+                Ok(CelValue::Decimal("1.1".parse().unwrap()))
+            }
+            _ => Err(CelError::Unexpected("unknown pair".to_string())),
+        },
+    )?;
+    let mut extensions = CelExtensions::new();
+    extensions.add_package("fx", fx);
+
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .cel_extensions(extensions)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let journal = cala.journals().create(helpers::test_journal()).await?;
+    let (sender, receiver) = helpers::test_accounts();
+    let sender = cala.accounts().create(sender).await?;
+    let recipient = cala.accounts().create(receiver).await?;
+
+    let template = |code: &str, units: &str| {
+        let params = vec![
+            NewParamDefinition::builder()
+                .name("journal_id")
+                .r#type(ParamDataType::Uuid)
+                .build()
+                .unwrap(),
+            NewParamDefinition::builder()
+                .name("amount")
+                .r#type(ParamDataType::Decimal)
+                .build()
+                .unwrap(),
+            NewParamDefinition::builder()
+                .name("rate")
+                .r#type(ParamDataType::Decimal)
+                .default_expr("fx.rate('EURUSD')")
+                .predicate("params.rate < fx.rate('EURUSD') * decimal('2')")
+                .build()
+                .unwrap(),
+        ];
+        let entries = [
+            ("'FX_DR'", sender.id(), "DEBIT"),
+            ("'FX_CR'", recipient.id(), "CREDIT"),
+        ]
+        .into_iter()
+        .map(|(entry_type, account_id, direction)| {
+            NewTxTemplateEntry::builder()
+                .entry_type(entry_type)
+                .account_id(format!("uuid('{account_id}')"))
+                .layer("SETTLED")
+                .direction(direction)
+                .units(units)
+                .currency("'USD'")
+                .build()
+                .unwrap()
+        })
+        .collect();
+        NewTxTemplate::builder()
+            .id(uuid::Uuid::new_v4())
+            .code(code)
+            .params(params)
+            .transaction(
+                NewTxTemplateTransaction::builder()
+                    .effective("date()")
+                    .journal_id("params.journal_id")
+                    .build()
+                    .unwrap(),
+            )
+            .entries(entries)
+            .build()
+            .unwrap()
+    };
+
+    let tx_code = Alphanumeric.sample_string(&mut rand::rng(), 32);
+    let res = cala
+        .tx_templates()
+        .create(template(&tx_code, "params.amount * fx.rate(1)"))
+        .await;
+    assert!(matches!(res, Err(TxTemplateError::TypeCheck(..))));
+
+    cala.tx_templates()
+        .create(template(&tx_code, "params.amount * params.rate"))
+        .await?;
+    let mut params = Params::new();
+    params.insert("journal_id", journal.id());
+    params.insert("amount", rust_decimal::Decimal::from(10));
+    let tx = cala
+        .post_transaction(TransactionId::new(), &tx_code, params)
+        .await?;
+    let entries = cala.entries().list_for_transaction_id(tx.id()).await?;
+    assert_eq!(entries[0].values().units, "11".parse()?);
+
+    Ok(())
+}
@@ -119,3 +119,80 @@ async fn create_control() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn attach_control_validates_params() -> anyhow::Result<()> {
+    let pool = helpers::init_pool().await?;
+    let cala_config = CalaLedgerConfig::builder()
+        .pool(pool)
+        .exec_migrations(false)
+        .build()?;
+    let cala = CalaLedger::init(cala_config).await?;
+
+    let velocity = cala.velocities();
+
+    let withdrawal_limit = NewVelocityLimit::builder()
+        .id(VelocityLimitId::new())
+        .name("Withdrawal")
+        .description("test")
+        .window(vec![])
+        .limit(
+            NewLimit::builder()
+                .balance(vec![NewBalanceLimit::builder()
+                    .layer("SETTLED")
+                    .amount("params.withdrawal_limit")
+                    .enforcement_direction("DEBIT")
+                    .build()
+                    .expect("limit")])
+                .build()
+                .expect("limit"),
+        )
+        .params(vec![NewParamDefinition::builder()
+            .r#type(ParamDataType::Decimal)
+            .name("withdrawal_limit")
+            .required(true)
+            .min(Decimal::ZERO)
+            .build()
+            .expect("param")])
+        .build()
+        .expect("build limit");
+    let withdrawal_limit = velocity.create_limit(withdrawal_limit).await?;
+
+    let control = NewVelocityControl::builder()
+        .id(VelocityControlId::new())
+        .name("test")
+        .description("test")
+        .build()
+        .expect("build control");
+    let control = velocity.create_control(control).await?;
+    velocity
+        .add_limit_to_control(control.id(), withdrawal_limit.id())
+        .await?;
+
+    let (sender, _) = helpers::test_accounts();
+    let sender_account = cala.accounts().create(sender).await?;
+
+    let res = velocity
+        .attach_control_to_account(control.id(), sender_account.id(), Params::new())
+        .await;
+    assert!(matches!(
+        res,
+        Err(velocity::error::VelocityError::ParamError(tx_template::ParamError::InvalidParams(ref v)))
+            if v[0].name == "withdrawal_limit"
+    ));
+
+    let mut params = Params::new();
+    params.insert("withdrawal_limit", Decimal::NEGATIVE_ONE);
+    let res = velocity
+        .attach_control_to_account(control.id(), sender_account.id(), params)
+        .await;
+    assert!(res.is_err());
+
+    let mut params = Params::new();
+    params.insert("withdrawal_limit", Decimal::ONE_HUNDRED);
+    velocity
+        .attach_control_to_account(control.id(), sender_account.id(), params)
+        .await?;
+
+    Ok(())
+}
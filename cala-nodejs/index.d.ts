@@ -36,6 +36,19 @@ export interface JournalValues {
   externalId?: string
   description?: string
 }
+export const enum JournalStatus {
+  Active = 'Active',
+  Locked = 'Locked'
+}
+export const enum JournalsSortBy {
+  Name = 'Name',
+  CreatedAt = 'CreatedAt'
+}
+export interface PaginatedJournals {
+  journals: Array<JournalValues>
+  hasNextPage: boolean
+  endCursor?: CursorToken
+}
 export interface OutboxServerConfig {
   enabled: boolean
   listenPort?: number
@@ -61,6 +74,7 @@ export class CalaAccount {
 }
 export class CalaJournals {
   create(newJournal: NewJournal): Promise<CalaJournal>
+  list(query: PaginatedQueryArgs, status?: JournalStatus | undefined | null, sortBy?: JournalsSortBy | undefined | null): Promise<PaginatedJournals>
 }
 export class CalaJournal {
   id(): string
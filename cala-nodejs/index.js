@@ -281,10 +281,12 @@ if (!nativeBinding) {
   throw new Error(`Failed to load native binding`)
 }
 
-const { CalaAccounts, CalaAccount, CalaJournals, CalaJournal, CalaLedger } = nativeBinding
+const { CalaAccounts, CalaAccount, JournalStatus, JournalsSortBy, CalaJournals, CalaJournal, CalaLedger } = nativeBinding
 
 module.exports.CalaAccounts = CalaAccounts
 module.exports.CalaAccount = CalaAccount
+module.exports.JournalStatus = JournalStatus
+module.exports.JournalsSortBy = JournalsSortBy
 module.exports.CalaJournals = CalaJournals
 module.exports.CalaJournal = CalaJournal
 module.exports.CalaLedger = CalaLedger
@@ -2,6 +2,8 @@ mod values;
 
 use values::*;
 
+use super::query::*;
+
 #[napi(object)]
 pub struct NewJournal {
   pub id: Option<String>,
@@ -10,6 +12,25 @@ pub struct NewJournal {
   pub description: Option<String>,
 }
 
+#[napi(string_enum)]
+pub enum JournalStatus {
+  Active,
+  Locked,
+}
+
+#[napi(string_enum)]
+pub enum JournalsSortBy {
+  Name,
+  CreatedAt,
+}
+
+#[napi(object)]
+pub struct PaginatedJournals {
+  pub journals: Vec<JournalValues>,
+  pub has_next_page: bool,
+  pub end_cursor: Option<CursorToken>,
+}
+
 #[napi]
 pub struct CalaJournals {
   inner: cala_ledger::journal::Journals,
@@ -62,4 +83,42 @@ impl CalaJournals {
       .map_err(crate::generic_napi_error)?;
     Ok(CalaJournal { inner: journal })
   }
+
+  #[napi]
+  pub async fn list(
+    &self,
+    query: PaginatedQueryArgs,
+    status: Option<JournalStatus>,
+    sort_by: Option<JournalsSortBy>,
+  ) -> napi::Result<PaginatedJournals> {
+    let query = cala_ledger::es_entity::PaginatedQueryArgs {
+      after: query.after.map(|c| c.try_into()).transpose()?,
+      first: usize::try_from(query.first).map_err(crate::generic_napi_error)?,
+    };
+    let status = status.map(|status| match status {
+      JournalStatus::Active => cala_ledger::primitives::Status::Active,
+      JournalStatus::Locked => cala_ledger::primitives::Status::Locked,
+    });
+    let by = match sort_by {
+      Some(JournalsSortBy::CreatedAt) => cala_ledger::journal::JournalsSortBy::CreatedAt,
+      _ => cala_ledger::journal::JournalsSortBy::Name,
+    };
+    let ret = self
+      .inner
+      .list(
+        status,
+        cala_ledger::es_entity::Sort {
+          by,
+          direction: Default::default(),
+        },
+        query,
+      )
+      .await
+      .map_err(crate::generic_napi_error)?;
+    Ok(PaginatedJournals {
+      journals: ret.entities.into_iter().map(JournalValues::from).collect(),
+      has_next_page: ret.has_next_page,
+      end_cursor: ret.end_cursor.map(|c| c.into()),
+    })
+  }
 }
@@ -0,0 +1,19 @@
+CREATE TABLE webhooks (
+  id UUID REFERENCES jobs(id) PRIMARY KEY,
+  url VARCHAR NOT NULL,
+  encrypted_secret BYTEA NOT NULL,
+  filter JSONB NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE webhook_dead_letters (
+  id UUID PRIMARY KEY,
+  webhook_id UUID REFERENCES webhooks(id) NOT NULL,
+  sequence BIGINT NOT NULL,
+  event JSONB NOT NULL,
+  last_error VARCHAR NOT NULL,
+  attempts INT NOT NULL,
+  redelivered_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  UNIQUE(webhook_id, sequence)
+);
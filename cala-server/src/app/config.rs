@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-use crate::job::JobExecutorConfig;
+use crate::{extension::CalaOutboxWebhookConfig, job::JobExecutorConfig};
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 pub struct AppConfig {
     #[serde(default)]
     pub job_execution: JobExecutorConfig,
+    #[serde(default)]
+    pub outbox_webhook: CalaOutboxWebhookConfig,
 }
@@ -5,7 +5,7 @@ use sqlx::PgPool;
 
 use cala_ledger::CalaLedger;
 
-use crate::{integration::*, job::*};
+use crate::{extension::CalaOutboxWebhookConfig, integration::*, job::*};
 pub use config::*;
 pub use error::*;
 
@@ -14,6 +14,7 @@ pub struct CalaApp {
     pool: PgPool,
     ledger: CalaLedger,
     jobs: Jobs,
+    outbox_webhook_config: CalaOutboxWebhookConfig,
 }
 
 impl CalaApp {
@@ -25,13 +26,22 @@ impl CalaApp {
     ) -> Result<Self, ApplicationError> {
         let mut jobs = Jobs::new(&pool, config.job_execution, registry);
         jobs.start_poll().await?;
-        Ok(Self { pool, ledger, jobs })
+        Ok(Self {
+            pool,
+            ledger,
+            jobs,
+            outbox_webhook_config: config.outbox_webhook,
+        })
     }
 
     pub fn integrations(&self) -> Integrations {
         Integrations::new(&self.pool)
     }
 
+    pub(crate) fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
     pub fn ledger(&self) -> &CalaLedger {
         &self.ledger
     }
@@ -39,4 +49,8 @@ impl CalaApp {
     pub fn jobs(&self) -> &Jobs {
         &self.jobs
     }
+
+    pub(crate) fn outbox_webhook_config(&self) -> &CalaOutboxWebhookConfig {
+        &self.outbox_webhook_config
+    }
 }
@@ -6,7 +6,7 @@ use clap::Parser;
 use std::{fs, path::PathBuf};
 
 use self::config::{Config, EnvOverride};
-use crate::{extension::*, job::JobRegistry};
+use crate::{app::AppConfig, extension::*, job::JobRegistry};
 
 #[derive(Parser)]
 #[clap(version, long_about = None)]
@@ -25,7 +25,7 @@ struct Cli {
 }
 
 pub async fn run<Q: QueryExtensionMarker, M: MutationExtensionMarker>(
-    job_registration: impl FnOnce(&mut JobRegistry),
+    job_registration: impl FnOnce(&mut JobRegistry, &AppConfig),
 ) -> anyhow::Result<()> {
     let cli = Cli::parse();
 
@@ -39,7 +39,7 @@ pub async fn run<Q: QueryExtensionMarker, M: MutationExtensionMarker>(
 async fn run_cmd<Q: QueryExtensionMarker, M: MutationExtensionMarker>(
     cala_home: &str,
     config: Config,
-    job_registration: impl FnOnce(&mut JobRegistry),
+    job_registration: impl FnOnce(&mut JobRegistry, &AppConfig),
 ) -> anyhow::Result<()> {
     use cala_ledger::{CalaLedger, CalaLedgerConfig};
     cala_tracing::init_tracer(config.tracing)?;
@@ -48,7 +48,7 @@ async fn run_cmd<Q: QueryExtensionMarker, M: MutationExtensionMarker>(
     let ledger_config = CalaLedgerConfig::builder().pool(pool.clone()).build()?;
     let ledger = CalaLedger::init(ledger_config).await?;
     let mut registry = JobRegistry::new(&ledger);
-    job_registration(&mut registry);
+    job_registration(&mut registry, &config.app);
     let app = crate::app::CalaApp::run(pool, config.app, ledger, registry).await?;
     crate::server::run::<Q, M>(config.server, app).await?;
     Ok(())
@@ -5,7 +5,7 @@ use cala_ledger::{primitives::DataSourceId, CalaLedger};
 use cala_ledger_outbox_client::{
     CalaLedgerOutboxClient as Client, CalaLedgerOutboxClientConfig as ClientConfig,
 };
-use cala_types::outbox::EventSequence;
+use cala_types::outbox::{EventSequence, OutboxEventFilter};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
@@ -14,6 +14,9 @@ use crate::job::*;
 
 pub const CALA_OUTBOX_IMPORT_JOB_TYPE: JobType = JobType::new("cala-outbox-import-job");
 
+/// Minimum time between acknowledgements sent to the source ledger.
+const ACK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 #[derive(Default)]
 pub struct CalaOutboxImportJobInitializer;
 
@@ -58,7 +61,21 @@ impl JobRunner for CalaOutboxImportJob {
             state.endpoint
         );
         let mut client = Client::connect(ClientConfig::new(state.endpoint.clone())).await?;
-        let mut stream = client.subscribe(Some(state.last_synced)).await?;
+        let mut ack_client = client.clone();
+        // Registering as a durable consumer keeps the source ledger from pruning
+        // events that haven't been imported yet. The position to resume from
+        // is still taken from the job state as it is updated atomically with the import.
+        // Consumers of jobs that no longer run are released by the source's
+        // `consumer_expiry` retention setting.
+        let consumer = format!("{}-{}", CALA_OUTBOX_IMPORT_JOB_TYPE, current_job.id());
+        let mut stream = client
+            .subscribe_as(
+                &consumer,
+                Some(state.last_synced),
+                OutboxEventFilter::default(),
+            )
+            .await?;
+        let mut last_ack: Option<std::time::Instant> = None;
         loop {
             match stream.next().await {
                 Some(Ok(message)) => {
@@ -72,6 +89,10 @@ impl JobRunner for CalaOutboxImportJob {
                             message,
                         )
                         .await?;
+                    if last_ack.is_none_or(|at| at.elapsed() >= ACK_INTERVAL) {
+                        ack_client.ack(&consumer, state.last_synced).await?;
+                        last_ack = Some(std::time::Instant::now());
+                    }
                 }
                 Some(Err(err)) => {
                     return Err(Box::new(err));
@@ -0,0 +1,185 @@
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use super::error::CalaOutboxWebhookError;
+
+const NONCE_LEN: usize = 12;
+
+#[derive(Clone, Default, Debug, Deserialize, Serialize)]
+pub struct CalaOutboxWebhookConfig {
+    /// Encrypts the signing secrets of the webhooks at rest.
+    /// Hex encoded 32 byte key - webhooks can't be created without it.
+    #[serde(default)]
+    pub secret_encryption_key: Option<WebhookSecretKey>,
+    /// Hosts that may be targeted even though they are (or resolve to)
+    /// loopback, private, link-local or otherwise non-public addresses.
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// Hosts that may never be targeted.
+    #[serde(default)]
+    pub denied_hosts: Vec<String>,
+}
+
+impl CalaOutboxWebhookConfig {
+    pub(super) fn secret_key(&self) -> Result<&WebhookSecretKey, CalaOutboxWebhookError> {
+        self.secret_encryption_key
+            .as_ref()
+            .ok_or(CalaOutboxWebhookError::MissingSecretEncryptionKey)
+    }
+
+    /// Checks that the url may be targeted. Domains must resolve to at least
+    /// one address that may be targeted.
+    pub(super) async fn check_url(&self, url: &reqwest::Url) -> Result<(), CalaOutboxWebhookError> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(CalaOutboxWebhookError::InvalidUrl(format!(
+                "unsupported scheme '{}'",
+                url.scheme()
+            )));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| CalaOutboxWebhookError::InvalidUrl("missing host".to_string()))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase();
+        if self.is_denied(&host) {
+            return Err(CalaOutboxWebhookError::HostNotAllowed(host));
+        }
+        if self.is_allowed(&host) {
+            return Ok(());
+        }
+        let port = url.port_or_known_default().unwrap_or_default();
+        if self.resolve(&host, port).await?.is_empty() {
+            return Err(CalaOutboxWebhookError::HostNotAllowed(host));
+        }
+        Ok(())
+    }
+
+    /// Resolves the host to the addresses that may be targeted.
+    pub(super) async fn resolve(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> Result<Vec<SocketAddr>, CalaOutboxWebhookError> {
+        if self.is_denied(host) {
+            return Ok(Vec::new());
+        }
+        let addrs = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| CalaOutboxWebhookError::InvalidUrl(e.to_string()))?;
+        if self.is_allowed(host) {
+            return Ok(addrs.collect());
+        }
+        Ok(addrs.filter(|addr| is_public(addr.ip())).collect())
+    }
+
+    fn is_allowed(&self, host: &str) -> bool {
+        self.allowed_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+
+    fn is_denied(&self, host: &str) -> bool {
+        self.denied_hosts
+            .iter()
+            .any(|denied| denied.eq_ignore_ascii_case(host))
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        // Includes the 169.254.169.254 metadata endpoint of the cloud providers
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        // Shared address space (100.64.0.0/10)
+        || (a == 100 && (b & 0b1100_0000) == 64)
+        // Benchmarking (198.18.0.0/15)
+        || (a == 198 && (b & 0b1111_1110) == 18)
+        // Reserved (240.0.0.0/4)
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let [a, b, ..] = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local()
+        // Documentation (2001:db8::/32)
+        || (a == 0x2001 && b == 0x0db8))
+}
+
+#[derive(Clone)]
+pub struct WebhookSecretKey(Key);
+
+impl WebhookSecretKey {
+    /// Returns the nonce followed by the ciphertext.
+    pub(super) fn encrypt(&self, secret: &str) -> Result<Vec<u8>, CalaOutboxWebhookError> {
+        let cipher = ChaCha20Poly1305::new(&self.0);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, secret.as_bytes())
+            .map_err(|_| CalaOutboxWebhookError::SecretEncryption)?;
+        let mut encrypted = nonce.to_vec();
+        encrypted.extend(ciphertext);
+        Ok(encrypted)
+    }
+
+    pub(super) fn decrypt(&self, encrypted: &[u8]) -> Result<String, CalaOutboxWebhookError> {
+        if encrypted.len() < NONCE_LEN {
+            return Err(CalaOutboxWebhookError::SecretEncryption);
+        }
+        let (nonce, ciphertext) = encrypted.split_at(NONCE_LEN);
+        let secret = ChaCha20Poly1305::new(&self.0)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| CalaOutboxWebhookError::SecretEncryption)?;
+        String::from_utf8(secret).map_err(|_| CalaOutboxWebhookError::SecretEncryption)
+    }
+}
+
+impl std::fmt::Debug for WebhookSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("WebhookSecretKey(..)")
+    }
+}
+
+impl Serialize for WebhookSecretKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for WebhookSecretKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key =
+            hex::decode(String::deserialize(deserializer)?).map_err(serde::de::Error::custom)?;
+        if key.len() != 32 {
+            return Err(serde::de::Error::custom(
+                "secret_encryption_key must be 32 bytes",
+            ));
+        }
+        Ok(Self(*Key::from_slice(&key)))
+    }
+}
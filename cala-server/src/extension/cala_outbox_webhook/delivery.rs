@@ -0,0 +1,106 @@
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use sha2::Sha256;
+
+use std::sync::Arc;
+
+use cala_types::outbox::OutboxEvent;
+
+use super::{config::*, error::*, repo::Webhook};
+
+pub const SIGNATURE_HEADER: &str = "X-Cala-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Cala-Timestamp";
+pub const EVENT_ID_HEADER: &str = "X-Cala-Event-Id";
+pub const EVENT_SEQUENCE_HEADER: &str = "X-Cala-Event-Sequence";
+pub const EVENT_TYPE_HEADER: &str = "X-Cala-Event-Type";
+
+const TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Signs `{timestamp}.{body}` with the secret of the webhook.
+/// Receivers should recompute the signature from the raw request body
+/// and the `X-Cala-Timestamp` header and compare it to `X-Cala-Signature`.
+pub fn webhook_signature(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Only resolves hosts to the addresses permitted by the [CalaOutboxWebhookConfig]
+/// so that a target can't be rebound to an internal address after it was checked.
+struct PolicyResolver {
+    config: Arc<CalaOutboxWebhookConfig>,
+}
+
+impl Resolve for PolicyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let config = Arc::clone(&self.config);
+        Box::pin(async move {
+            let addrs = config.resolve(name.as_str(), 0).await?;
+            if addrs.is_empty() {
+                return Err(
+                    CalaOutboxWebhookError::HostNotAllowed(name.as_str().to_string()).into(),
+                );
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct WebhookDelivery {
+    config: Arc<CalaOutboxWebhookConfig>,
+    client: reqwest::Client,
+}
+
+impl WebhookDelivery {
+    pub fn new(config: &CalaOutboxWebhookConfig) -> Result<Self, CalaOutboxWebhookError> {
+        let config = Arc::new(config.clone());
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(TIMEOUT)
+                // Redirects could lead to hosts that aren't allowed
+                .redirect(reqwest::redirect::Policy::none())
+                .dns_resolver(Arc::new(PolicyResolver {
+                    config: Arc::clone(&config),
+                }))
+                .build()?,
+            config,
+        })
+    }
+
+    /// POSTs the event as JSON. Any response other than 2xx counts as a failed delivery.
+    pub async fn deliver(
+        &self,
+        webhook: &Webhook,
+        event: &OutboxEvent,
+    ) -> Result<(), CalaOutboxWebhookError> {
+        let url = reqwest::Url::parse(&webhook.url)
+            .map_err(|e| CalaOutboxWebhookError::InvalidUrl(e.to_string()))?;
+        self.config.check_url(&url).await?;
+        let secret = webhook.secret(self.config.secret_key()?)?;
+        let body = serde_json::to_vec(event)?;
+        let timestamp = chrono::Utc::now().timestamp();
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(
+                SIGNATURE_HEADER,
+                webhook_signature(&secret, timestamp, &body),
+            )
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(EVENT_ID_HEADER, event.id.to_string())
+            .header(EVENT_SEQUENCE_HEADER, event.sequence.to_string())
+            .header(EVENT_TYPE_HEADER, event.payload.payload_type().to_string())
+            .body(body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(CalaOutboxWebhookError::UnexpectedStatus(response.status()));
+        }
+        Ok(())
+    }
+}
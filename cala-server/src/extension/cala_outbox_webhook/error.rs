@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CalaOutboxWebhookError {
+    #[error("CalaOutboxWebhookError - Sqlx: {0}")]
+    Sqlx(#[from] sqlx::Error),
+    #[error("CalaOutboxWebhookError - Serde: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("CalaOutboxWebhookError - Reqwest: {0}")]
+    Reqwest(#[from] reqwest::Error),
+    #[error("CalaOutboxWebhookError - UnexpectedStatus: {0}")]
+    UnexpectedStatus(reqwest::StatusCode),
+    #[error("CalaOutboxWebhookError - InvalidUrl: {0}")]
+    InvalidUrl(String),
+    #[error("CalaOutboxWebhookError - HostNotAllowed: {0}")]
+    HostNotAllowed(String),
+    #[error("CalaOutboxWebhookError - InvalidPayloadType: {0}")]
+    InvalidPayloadType(String),
+    #[error("CalaOutboxWebhookError - MissingSecret")]
+    MissingSecret,
+    #[error("CalaOutboxWebhookError - MissingSecretEncryptionKey")]
+    MissingSecretEncryptionKey,
+    #[error("CalaOutboxWebhookError - SecretEncryption")]
+    SecretEncryption,
+    #[error("CalaOutboxWebhookError - DeadLetterAlreadyRedelivered")]
+    DeadLetterAlreadyRedelivered,
+}
@@ -0,0 +1,126 @@
+#![allow(clippy::blocks_in_conditions)]
+
+use async_trait::async_trait;
+use cala_ledger::{error::LedgerError, outbox::error::OutboxError, CalaLedger};
+use futures::StreamExt;
+use tracing::instrument;
+
+use super::{config::*, delivery::*, repo::*};
+use crate::{job::*, primitives::JobId};
+
+pub const CALA_OUTBOX_WEBHOOK_JOB_TYPE: JobType = JobType::new("cala-outbox-webhook-job");
+
+/// Name of the durable outbox consumer tracking the deliveries of a webhook.
+pub fn webhook_consumer_name(id: JobId) -> String {
+    format!("{}-{}", CALA_OUTBOX_WEBHOOK_JOB_TYPE, id)
+}
+
+#[derive(Default)]
+pub struct CalaOutboxWebhookJobInitializer {
+    config: CalaOutboxWebhookConfig,
+}
+
+impl CalaOutboxWebhookJobInitializer {
+    pub fn new(config: CalaOutboxWebhookConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl JobInitializer for CalaOutboxWebhookJobInitializer {
+    fn job_type() -> JobType {
+        CALA_OUTBOX_WEBHOOK_JOB_TYPE
+    }
+
+    fn retry_on_error_settings() -> RetrySettings {
+        RetrySettings {
+            n_attempts: 5,
+            min_backoff: std::time::Duration::from_secs(1),
+            max_backoff: std::time::Duration::from_secs(60),
+        }
+    }
+
+    fn init(
+        &self,
+        _job: Job,
+        ledger: &CalaLedger,
+    ) -> Result<Box<dyn JobRunner>, Box<dyn std::error::Error>> {
+        Ok(Box::new(CalaOutboxWebhookJob {
+            ledger: ledger.clone(),
+            delivery: WebhookDelivery::new(&self.config)?,
+        }))
+    }
+}
+
+/// Delivers the events of one webhook in order. A failed delivery fails the job
+/// so that it is retried with the backoff of its [RetrySettings]. Once the last
+/// attempt has failed the event is dead-lettered and delivery moves on.
+pub struct CalaOutboxWebhookJob {
+    ledger: CalaLedger,
+    delivery: WebhookDelivery,
+}
+
+#[async_trait]
+impl JobRunner for CalaOutboxWebhookJob {
+    #[instrument(name = "job.cala_outbox_webhook.run", skip(self, current_job), err)]
+    async fn run(
+        &self,
+        current_job: CurrentJob,
+    ) -> Result<JobCompletion, Box<dyn std::error::Error>> {
+        let webhooks = Webhooks::new(current_job.pool());
+        let consumer = webhook_consumer_name(current_job.id());
+        let Some(webhook) = webhooks.try_find_by_id(current_job.id()).await? else {
+            return self.complete_deleted(&consumer).await;
+        };
+        let n_attempts =
+            <CalaOutboxWebhookJobInitializer as JobInitializer>::retry_on_error_settings()
+                .n_attempts;
+        let mut listener = self
+            .ledger
+            .register_outbox_consumer(&consumer, webhook.filter.clone())
+            .await?;
+        while let Some(event) = listener.next().await {
+            let event = event?;
+            if webhooks.try_find_by_id(webhook.id).await?.is_none() {
+                return self.complete_deleted(&consumer).await;
+            }
+            match self.delivery.deliver(&webhook, &event).await {
+                Ok(()) => {
+                    self.ledger
+                        .ack_outbox_consumer(&consumer, event.sequence)
+                        .await?;
+                    if current_job.attempt() > 1 {
+                        // Restart to reset the attempt counter for the next failure
+                        return Ok(JobCompletion::RescheduleAt(chrono::Utc::now()));
+                    }
+                }
+                Err(e) if current_job.attempt() < n_attempts => return Err(Box::new(e)),
+                Err(e) => {
+                    webhooks
+                        .create_dead_letter(webhook.id, &event, e.to_string(), n_attempts)
+                        .await?;
+                    self.ledger
+                        .ack_outbox_consumer(&consumer, event.sequence)
+                        .await?;
+                    return Ok(JobCompletion::RescheduleAt(chrono::Utc::now()));
+                }
+            }
+        }
+        Ok(JobCompletion::RescheduleAt(chrono::Utc::now()))
+    }
+}
+
+impl CalaOutboxWebhookJob {
+    /// The consumer is deleted together with the webhook - this only cleans up
+    /// after a run that registered it again concurrently.
+    async fn complete_deleted(
+        &self,
+        consumer: &str,
+    ) -> Result<JobCompletion, Box<dyn std::error::Error>> {
+        match self.ledger.delete_outbox_consumer(consumer).await {
+            Ok(()) | Err(LedgerError::OutboxError(OutboxError::ConsumerNotFound(_))) => {
+                Ok(JobCompletion::Complete)
+            }
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+mod config;
+mod delivery;
+mod error;
+mod job;
+mod mutation;
+mod query;
+mod repo;
+
+pub use config::*;
+pub use job::*;
+pub use mutation::*;
+pub use query::*;
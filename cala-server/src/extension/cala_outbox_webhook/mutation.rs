@@ -0,0 +1,175 @@
+use async_graphql::*;
+
+use cala_types::outbox::{OutboxEventFilter, OutboxEventPayloadType};
+
+use super::{delivery::*, error::*, job::*, query::*, repo::*};
+use crate::{
+    app::CalaApp,
+    graphql::{primitives::UUID, DbOp, Job},
+};
+
+#[derive(InputObject)]
+pub struct CalaOutboxWebhookCreateInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub url: String,
+    /// Used to sign the deliveries - see the X-Cala-Signature header.
+    pub secret: String,
+    /// Payload types to deliver, eg. "transaction_created". All types if empty.
+    #[graphql(default)]
+    pub payload_types: Vec<String>,
+    #[graphql(default)]
+    pub journal_ids: Vec<UUID>,
+    #[graphql(default)]
+    pub account_ids: Vec<UUID>,
+}
+
+#[derive(SimpleObject)]
+pub struct CalaOutboxWebhookCreatePayload {
+    pub webhook: CalaOutboxWebhook,
+    pub job: Job,
+}
+
+#[derive(InputObject)]
+pub struct CalaOutboxWebhookDeleteInput {
+    pub webhook_id: UUID,
+}
+
+#[derive(SimpleObject)]
+pub struct CalaOutboxWebhookDeletePayload {
+    pub webhook: CalaOutboxWebhook,
+}
+
+#[derive(InputObject)]
+pub struct CalaOutboxWebhookDeadLetterRedeliverInput {
+    pub dead_letter_id: UUID,
+}
+
+#[derive(SimpleObject)]
+pub struct CalaOutboxWebhookDeadLetterRedeliverPayload {
+    pub dead_letter: CalaOutboxWebhookDeadLetter,
+}
+
+#[derive(Default)]
+pub struct Mutation;
+
+#[Object(name = "CalaOutboxWebhookMutation")]
+impl Mutation {
+    async fn cala_outbox_webhook_create(
+        &self,
+        ctx: &Context<'_>,
+        input: CalaOutboxWebhookCreateInput,
+    ) -> async_graphql::Result<CalaOutboxWebhookCreatePayload> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let mut op = ctx
+            .data_unchecked::<DbOp>()
+            .try_lock()
+            .expect("Lock held concurrently");
+        let config = app.outbox_webhook_config();
+        let url = parse_url(&input.url)?;
+        config.check_url(&url).await?;
+        if input.secret.is_empty() {
+            return Err(CalaOutboxWebhookError::MissingSecret.into());
+        }
+        let encrypted_secret = config.secret_key()?.encrypt(&input.secret)?;
+        let filter = OutboxEventFilter::try_from(&input)?;
+        let job = app
+            .jobs()
+            .create_and_spawn_in_op::<CalaOutboxWebhookJobInitializer, _>(
+                &mut op,
+                crate::primitives::JobId::new(),
+                input.name,
+                input.description,
+                (),
+            )
+            .await?;
+        let webhook = Webhooks::new(app.pool())
+            .create_in_op(&mut op, job.id, input.url, encrypted_secret, filter)
+            .await?;
+        // Start tracking the outbox right away so that no event recorded
+        // before the job first runs is missed
+        app.ledger()
+            .create_outbox_consumer_in_op(&mut op, &webhook_consumer_name(job.id))
+            .await?;
+        Ok(CalaOutboxWebhookCreatePayload {
+            webhook: CalaOutboxWebhook::from(webhook),
+            job: Job::from(job),
+        })
+    }
+
+    /// Stops the deliveries of the webhook and deletes it together with its dead letters.
+    async fn cala_outbox_webhook_delete(
+        &self,
+        ctx: &Context<'_>,
+        input: CalaOutboxWebhookDeleteInput,
+    ) -> async_graphql::Result<CalaOutboxWebhookDeletePayload> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let mut op = ctx
+            .data_unchecked::<DbOp>()
+            .try_lock()
+            .expect("Lock held concurrently");
+        let id = input.webhook_id.into();
+        let webhook = Webhooks::new(app.pool()).delete_in_op(&mut op, id).await?;
+        app.jobs().cancel_in_op(&mut op, id).await?;
+        app.ledger()
+            .delete_outbox_consumer_in_op(&mut op, &webhook_consumer_name(id))
+            .await?;
+        Ok(CalaOutboxWebhookDeletePayload {
+            webhook: CalaOutboxWebhook::from(webhook),
+        })
+    }
+
+    /// Delivers a dead-lettered event once more, independently of the ordered delivery.
+    async fn cala_outbox_webhook_dead_letter_redeliver(
+        &self,
+        ctx: &Context<'_>,
+        input: CalaOutboxWebhookDeadLetterRedeliverInput,
+    ) -> async_graphql::Result<CalaOutboxWebhookDeadLetterRedeliverPayload> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let webhooks = Webhooks::new(app.pool());
+        let mut dead_letter = webhooks
+            .find_dead_letter(input.dead_letter_id.into())
+            .await?;
+        if dead_letter.redelivered_at.is_some() {
+            return Err(CalaOutboxWebhookError::DeadLetterAlreadyRedelivered.into());
+        }
+        let webhook = webhooks.find_by_id(dead_letter.webhook_id).await?;
+        let result = WebhookDelivery::new(app.outbox_webhook_config())?
+            .deliver(&webhook, &dead_letter.event)
+            .await;
+        webhooks
+            .record_redelivery(&mut dead_letter, &result)
+            .await?;
+        result?;
+        Ok(CalaOutboxWebhookDeadLetterRedeliverPayload {
+            dead_letter: CalaOutboxWebhookDeadLetter::from(dead_letter),
+        })
+    }
+}
+
+fn parse_url(url: &str) -> Result<reqwest::Url, CalaOutboxWebhookError> {
+    reqwest::Url::parse(url).map_err(|e| CalaOutboxWebhookError::InvalidUrl(e.to_string()))
+}
+
+impl TryFrom<&CalaOutboxWebhookCreateInput> for OutboxEventFilter {
+    type Error = CalaOutboxWebhookError;
+
+    fn try_from(input: &CalaOutboxWebhookCreateInput) -> Result<Self, Self::Error> {
+        let mut payload_types = Vec::new();
+        for payload_type in input.payload_types.iter() {
+            payload_types.push(
+                payload_type
+                    .parse::<OutboxEventPayloadType>()
+                    .map_err(|_| {
+                        CalaOutboxWebhookError::InvalidPayloadType(payload_type.clone())
+                    })?,
+            );
+        }
+        Ok(Self {
+            payload_types,
+            journal_ids: input.journal_ids.iter().map(|&id| id.into()).collect(),
+            account_ids: input.account_ids.iter().map(|&id| id.into()).collect(),
+            data_source_ids: Vec::new(),
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use async_graphql::*;
+
+use super::repo::*;
+use crate::{
+    app::CalaApp,
+    graphql::primitives::{Timestamp, JSON, UUID},
+};
+
+#[derive(SimpleObject)]
+pub struct CalaOutboxWebhook {
+    pub webhook_id: UUID,
+    pub url: String,
+    pub payload_types: Vec<String>,
+    pub journal_ids: Vec<UUID>,
+    pub account_ids: Vec<UUID>,
+    pub created_at: Timestamp,
+}
+
+#[derive(SimpleObject)]
+pub struct CalaOutboxWebhookDeadLetter {
+    pub dead_letter_id: UUID,
+    pub webhook_id: UUID,
+    pub sequence: u64,
+    pub payload_type: String,
+    pub event: JSON,
+    pub last_error: String,
+    pub attempts: u32,
+    pub created_at: Timestamp,
+    pub redelivered_at: Option<Timestamp>,
+}
+
+#[derive(Default)]
+pub struct Query;
+
+#[Object(name = "CalaOutboxWebhookQuery")]
+impl Query {
+    async fn cala_outbox_webhook(
+        &self,
+        ctx: &Context<'_>,
+        webhook_id: UUID,
+    ) -> async_graphql::Result<CalaOutboxWebhook> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let webhook = Webhooks::new(app.pool())
+            .find_by_id(webhook_id.into())
+            .await?;
+        Ok(CalaOutboxWebhook::from(webhook))
+    }
+
+    async fn cala_outbox_webhook_dead_letters(
+        &self,
+        ctx: &Context<'_>,
+        webhook_id: UUID,
+    ) -> async_graphql::Result<Vec<CalaOutboxWebhookDeadLetter>> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let dead_letters = Webhooks::new(app.pool())
+            .list_dead_letters(webhook_id.into())
+            .await?;
+        Ok(dead_letters
+            .into_iter()
+            .map(CalaOutboxWebhookDeadLetter::from)
+            .collect())
+    }
+}
+
+impl From<Webhook> for CalaOutboxWebhook {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            webhook_id: UUID::from(webhook.id),
+            url: webhook.url,
+            payload_types: webhook
+                .filter
+                .payload_types
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            journal_ids: webhook
+                .filter
+                .journal_ids
+                .into_iter()
+                .map(UUID::from)
+                .collect(),
+            account_ids: webhook
+                .filter
+                .account_ids
+                .into_iter()
+                .map(UUID::from)
+                .collect(),
+            created_at: Timestamp::from(webhook.created_at),
+        }
+    }
+}
+
+impl From<WebhookDeadLetter> for CalaOutboxWebhookDeadLetter {
+    fn from(dead_letter: WebhookDeadLetter) -> Self {
+        Self {
+            dead_letter_id: UUID::from(dead_letter.id),
+            webhook_id: UUID::from(dead_letter.webhook_id),
+            sequence: u64::from(dead_letter.sequence),
+            payload_type: dead_letter.event.payload.payload_type().to_string(),
+            event: JSON::from(
+                serde_json::to_value(&dead_letter.event).expect("Could not serialize event"),
+            ),
+            last_error: dead_letter.last_error,
+            attempts: dead_letter.attempts,
+            created_at: Timestamp::from(dead_letter.created_at),
+            redelivered_at: dead_letter.redelivered_at.map(Timestamp::from),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use cala_ledger::LedgerOperation;
+use cala_types::outbox::{EventSequence, OutboxEvent, OutboxEventFilter};
+
+use super::{config::WebhookSecretKey, error::CalaOutboxWebhookError};
+use crate::primitives::{JobId, WebhookDeadLetterId};
+
+pub struct Webhook {
+    pub id: JobId,
+    pub url: String,
+    encrypted_secret: Vec<u8>,
+    pub filter: OutboxEventFilter,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub(super) fn secret(&self, key: &WebhookSecretKey) -> Result<String, CalaOutboxWebhookError> {
+        key.decrypt(&self.encrypted_secret)
+    }
+}
+
+pub struct WebhookDeadLetter {
+    pub id: WebhookDeadLetterId,
+    pub webhook_id: JobId,
+    pub sequence: EventSequence,
+    pub event: OutboxEvent,
+    pub last_error: String,
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub redelivered_at: Option<DateTime<Utc>>,
+}
+
+pub struct Webhooks {
+    pool: PgPool,
+}
+
+impl Webhooks {
+    pub(crate) fn new(pool: &PgPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub async fn create_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        id: JobId,
+        url: String,
+        encrypted_secret: Vec<u8>,
+        filter: OutboxEventFilter,
+    ) -> Result<Webhook, CalaOutboxWebhookError> {
+        let row = sqlx::query!(
+            r#"INSERT INTO webhooks (id, url, encrypted_secret, filter)
+            VALUES ($1, $2, $3, $4)
+            RETURNING created_at"#,
+            id as JobId,
+            url,
+            encrypted_secret,
+            serde_json::to_value(&filter)?,
+        )
+        .fetch_one(&mut **op.tx())
+        .await?;
+        Ok(Webhook {
+            id,
+            url,
+            encrypted_secret,
+            filter,
+            created_at: row.created_at,
+        })
+    }
+
+    pub async fn find_by_id(&self, id: JobId) -> Result<Webhook, CalaOutboxWebhookError> {
+        self.try_find_by_id(id)
+            .await?
+            .ok_or(CalaOutboxWebhookError::Sqlx(sqlx::Error::RowNotFound))
+    }
+
+    pub async fn try_find_by_id(
+        &self,
+        id: JobId,
+    ) -> Result<Option<Webhook>, CalaOutboxWebhookError> {
+        let row = sqlx::query!(
+            r#"SELECT url, encrypted_secret, filter, created_at FROM webhooks WHERE id = $1"#,
+            id as JobId,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        row.map(|row| {
+            Ok(Webhook {
+                id,
+                url: row.url,
+                encrypted_secret: row.encrypted_secret,
+                filter: serde_json::from_value(row.filter)?,
+                created_at: row.created_at,
+            })
+        })
+        .transpose()
+    }
+
+    /// Deletes the webhook together with its dead letters.
+    pub async fn delete_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        id: JobId,
+    ) -> Result<Webhook, CalaOutboxWebhookError> {
+        let webhook = self.find_by_id(id).await?;
+        sqlx::query!(
+            r#"DELETE FROM webhook_dead_letters WHERE webhook_id = $1"#,
+            id as JobId,
+        )
+        .execute(&mut **op.tx())
+        .await?;
+        sqlx::query!(r#"DELETE FROM webhooks WHERE id = $1"#, id as JobId)
+            .execute(&mut **op.tx())
+            .await?;
+        Ok(webhook)
+    }
+
+    /// Records an event that exhausted its delivery attempts.
+    pub async fn create_dead_letter(
+        &self,
+        webhook_id: JobId,
+        event: &OutboxEvent,
+        last_error: String,
+        attempts: u32,
+    ) -> Result<(), CalaOutboxWebhookError> {
+        sqlx::query!(
+            r#"INSERT INTO webhook_dead_letters (id, webhook_id, sequence, event, last_error, attempts)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (webhook_id, sequence) DO UPDATE
+            SET last_error = EXCLUDED.last_error, attempts = EXCLUDED.attempts"#,
+            WebhookDeadLetterId::new() as WebhookDeadLetterId,
+            webhook_id as JobId,
+            event.sequence as EventSequence,
+            serde_json::to_value(event)?,
+            last_error,
+            attempts as i32,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_dead_letters(
+        &self,
+        webhook_id: JobId,
+    ) -> Result<Vec<WebhookDeadLetter>, CalaOutboxWebhookError> {
+        let rows = sqlx::query!(
+            r#"SELECT id AS "id: WebhookDeadLetterId", sequence AS "sequence: EventSequence",
+                 event, last_error, attempts, created_at, redelivered_at
+            FROM webhook_dead_letters
+            WHERE webhook_id = $1
+            ORDER BY sequence"#,
+            webhook_id as JobId,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        let mut dead_letters = Vec::new();
+        for row in rows {
+            dead_letters.push(WebhookDeadLetter {
+                id: row.id,
+                webhook_id,
+                sequence: row.sequence,
+                event: serde_json::from_value(row.event)?,
+                last_error: row.last_error,
+                attempts: row.attempts as u32,
+                created_at: row.created_at,
+                redelivered_at: row.redelivered_at,
+            });
+        }
+        Ok(dead_letters)
+    }
+
+    pub async fn find_dead_letter(
+        &self,
+        id: WebhookDeadLetterId,
+    ) -> Result<WebhookDeadLetter, CalaOutboxWebhookError> {
+        let row = sqlx::query!(
+            r#"SELECT webhook_id AS "webhook_id: JobId", sequence AS "sequence: EventSequence",
+                 event, last_error, attempts, created_at, redelivered_at
+            FROM webhook_dead_letters
+            WHERE id = $1"#,
+            id as WebhookDeadLetterId,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(WebhookDeadLetter {
+            id,
+            webhook_id: row.webhook_id,
+            sequence: row.sequence,
+            event: serde_json::from_value(row.event)?,
+            last_error: row.last_error,
+            attempts: row.attempts as u32,
+            created_at: row.created_at,
+            redelivered_at: row.redelivered_at,
+        })
+    }
+
+    pub async fn record_redelivery(
+        &self,
+        dead_letter: &mut WebhookDeadLetter,
+        result: &Result<(), CalaOutboxWebhookError>,
+    ) -> Result<(), CalaOutboxWebhookError> {
+        dead_letter.attempts += 1;
+        match result {
+            Ok(()) => dead_letter.redelivered_at = Some(Utc::now()),
+            Err(e) => dead_letter.last_error = e.to_string(),
+        }
+        sqlx::query!(
+            r#"UPDATE webhook_dead_letters
+            SET attempts = $2, last_error = $3, redelivered_at = $4
+            WHERE id = $1"#,
+            dead_letter.id as WebhookDeadLetterId,
+            dead_letter.attempts as i32,
+            dead_letter.last_error,
+            dead_letter.redelivered_at,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
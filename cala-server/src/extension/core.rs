@@ -2,6 +2,8 @@
 pub struct CoreMutationExtension {
     #[graphql(flatten)]
     cala_outbox_import: super::cala_outbox_import::Mutation,
+    #[graphql(flatten)]
+    cala_outbox_webhook: super::cala_outbox_webhook::Mutation,
 }
 
 #[derive(async_graphql::SimpleObject, Default)]
@@ -24,9 +26,16 @@ impl CoreQueryExtension {
 pub struct QueryExtension {
     #[graphql(flatten)]
     core: CoreQueryExtension,
+    #[graphql(flatten)]
+    cala_outbox_webhook: super::cala_outbox_webhook::Query,
 }
 impl super::QueryExtensionMarker for QueryExtension {}
 
-pub fn job_registration(registry: &mut crate::job::JobRegistry) {
+pub fn job_registration(registry: &mut crate::job::JobRegistry, config: &crate::app::AppConfig) {
     registry.add_initializer::<super::cala_outbox_import::CalaOutboxImportJobInitializer>();
+    registry.add_configured_initializer(
+        super::cala_outbox_webhook::CalaOutboxWebhookJobInitializer::new(
+            config.outbox_webhook.clone(),
+        ),
+    );
 }
@@ -1,7 +1,10 @@
 use async_graphql::*;
 
 mod cala_outbox_import;
+mod cala_outbox_webhook;
 pub mod core;
 
+pub use cala_outbox_webhook::CalaOutboxWebhookConfig;
+
 pub trait MutationExtensionMarker: Default + OutputType + ContainerType + 'static {}
 pub trait QueryExtensionMarker: Default + OutputType + ContainerType + 'static {}
@@ -2,8 +2,7 @@ use async_graphql::{dataloader::*, types::connection::*, *};
 
 use cala_ledger::{
     balance::*,
-    entry::EntriesByCreatedAtCursor,
-    primitives::{AccountId, Currency, JournalId},
+    primitives::{AccountId, AccountSetId, Currency, JournalId},
 };
 
 pub use cala_ledger::account::AccountsByNameCursor;
@@ -11,7 +10,7 @@ pub use cala_ledger::account::AccountsByNameCursor;
 use crate::app::CalaApp;
 
 use super::{
-    account_set::*, balance::Balance, convert::ToGlobalId, entry::Entry, loader::LedgerDataLoader,
+    account_set::*, balance::Balance, convert::ToGlobalId, entry::*, loader::LedgerDataLoader,
     primitives::*, schema::DbOp,
 };
 
@@ -108,12 +107,14 @@ impl Account {
         .await
     }
 
-    async fn entries(
+    async fn ancestors(
         &self,
         ctx: &Context<'_>,
         first: i32,
         after: Option<String>,
-    ) -> Result<Connection<EntriesByCreatedAtCursor, Entry, EmptyFields, EmptyFields>> {
+    ) -> Result<
+        Connection<AccountSetHierarchyCursor, AccountSetHierarchyNode, EmptyFields, EmptyFields>,
+    > {
         let app = ctx.data_unchecked::<CalaApp>();
         let account_id = AccountId::from(self.account_id);
         query(
@@ -123,23 +124,48 @@ impl Account {
             None,
             |after, _, first, _| async move {
                 let first = first.expect("First always exists");
-                let result = app
-                    .ledger()
-                    .entries()
-                    .list_for_account_id(
-                        account_id,
-                        cala_ledger::es_entity::PaginatedQueryArgs { first, after },
-                        cala_ledger::es_entity::ListDirection::Descending,
-                    )
-                    .await?;
-                let mut connection = Connection::new(false, result.has_next_page);
-                connection
-                    .edges
-                    .extend(result.entities.into_iter().map(|entity| {
-                        let cursor = EntriesByCreatedAtCursor::from(&entity);
-                        Edge::new(cursor, Entry::from(entity))
-                    }));
-                Ok::<_, async_graphql::Error>(connection)
+                let query_args = cala_ledger::es_entity::PaginatedQueryArgs { first, after };
+
+                let result = match ctx.data_opt::<DbOp>() {
+                    Some(op) => {
+                        let mut op = op.try_lock().expect("Lock held concurrently");
+                        app.ledger()
+                            .account_sets()
+                            .list_ancestors_in_op(&mut op, account_id, query_args)
+                            .await?
+                    }
+                    None => {
+                        app.ledger()
+                            .account_sets()
+                            .list_ancestors(account_id, query_args)
+                            .await?
+                    }
+                };
+                hierarchy_connection(ctx, result).await
+            },
+        )
+        .await
+    }
+
+    /// Entries most recent first. Without a filter they are ordered by creation
+    /// time, with a filter by the effective date of their transaction.
+    async fn entries(
+        &self,
+        ctx: &Context<'_>,
+        first: i32,
+        after: Option<String>,
+        filter: Option<EntriesFilterInput>,
+    ) -> Result<Connection<EntriesCursor, Entry, EmptyFields, EmptyFields>> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let owner = EntriesOwner::Account(AccountId::from(self.account_id));
+        query(
+            after,
+            None,
+            Some(first),
+            None,
+            |after, _, first, _| async move {
+                let first = first.expect("First always exists");
+                list_entries(app, owner, first, after, filter).await
             },
         )
         .await
@@ -188,6 +214,29 @@ impl ToGlobalId for cala_ledger::AccountId {
     }
 }
 
+#[derive(InputObject, Default)]
+pub(super) struct AccountsFilterInput {
+    pub status: Option<Status>,
+    pub normal_balance_type: Option<DebitOrCredit>,
+    pub code_prefix: Option<String>,
+    pub is_account_set: Option<bool>,
+    pub member_of_account_set_id: Option<UUID>,
+    pub metadata: Option<JSON>,
+}
+
+impl From<AccountsFilterInput> for cala_ledger::account::AccountsFilter {
+    fn from(input: AccountsFilterInput) -> Self {
+        Self {
+            status: input.status,
+            normal_balance_type: input.normal_balance_type,
+            code_prefix: input.code_prefix,
+            is_account_set: input.is_account_set,
+            member_of: input.member_of_account_set_id.map(AccountSetId::from),
+            metadata: input.metadata.map(|json| json.into_inner()),
+        }
+    }
+}
+
 impl From<cala_ledger::account::Account> for Account {
     fn from(account: cala_ledger::account::Account) -> Self {
         let created_at = account.created_at();
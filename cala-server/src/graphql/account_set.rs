@@ -3,14 +3,15 @@ use async_graphql::{dataloader::*, types::connection::*, *};
 use cala_ledger::{
     account_set::AccountSetMemberId,
     balance::*,
-    entry::EntriesByCreatedAtCursor,
     primitives::{AccountId, AccountSetId, Currency, JournalId},
 };
 
-pub use cala_ledger::account_set::{AccountSetMembersByCreatedAtCursor, AccountSetsByNameCursor};
+pub use cala_ledger::account_set::{
+    AccountSetHierarchyCursor, AccountSetMembersByCreatedAtCursor, AccountSetsByNameCursor,
+};
 
 use super::{
-    balance::*, convert::ToGlobalId, entry::Entry, loader::LedgerDataLoader, primitives::*,
+    balance::*, convert::ToGlobalId, entry::*, loader::LedgerDataLoader, primitives::*,
     schema::DbOp,
 };
 use crate::app::CalaApp;
@@ -21,6 +22,13 @@ enum AccountSetMember {
     AccountSet(AccountSet),
 }
 
+#[derive(SimpleObject)]
+pub struct AccountSetHierarchyNode {
+    member: AccountSetMember,
+    depth: u32,
+    path: Vec<UUID>,
+}
+
 #[derive(Clone, SimpleObject)]
 #[graphql(complex)]
 pub struct AccountSet {
@@ -199,14 +207,18 @@ impl AccountSet {
         .await
     }
 
-    async fn entries(
+    async fn descendants(
         &self,
         ctx: &Context<'_>,
         first: i32,
         after: Option<String>,
-    ) -> Result<Connection<EntriesByCreatedAtCursor, Entry, EmptyFields, EmptyFields>> {
+        #[graphql(default)] leaf_accounts_only: bool,
+    ) -> Result<
+        Connection<AccountSetHierarchyCursor, AccountSetHierarchyNode, EmptyFields, EmptyFields>,
+    > {
         let app = ctx.data_unchecked::<CalaApp>();
         let account_set_id = AccountSetId::from(self.account_set_id);
+
         query(
             after,
             None,
@@ -214,29 +226,169 @@ impl AccountSet {
             None,
             |after, _, first, _| async move {
                 let first = first.expect("First always exists");
-                let result = app
-                    .ledger()
-                    .entries()
-                    .list_for_account_set_id(
-                        account_set_id,
-                        cala_ledger::es_entity::PaginatedQueryArgs { first, after },
-                        cala_ledger::es_entity::ListDirection::Descending,
-                    )
-                    .await?;
-                let mut connection = Connection::new(false, result.has_next_page);
-                connection
-                    .edges
-                    .extend(result.entities.into_iter().map(|entity| {
-                        let cursor = EntriesByCreatedAtCursor::from(&entity);
-                        Edge::new(cursor, Entry::from(entity))
-                    }));
-                Ok::<_, async_graphql::Error>(connection)
+                let query_args = cala_ledger::es_entity::PaginatedQueryArgs { first, after };
+
+                let result = match ctx.data_opt::<DbOp>() {
+                    Some(op) => {
+                        let mut op = op.try_lock().expect("Lock held concurrently");
+                        app.ledger()
+                            .account_sets()
+                            .list_descendants_in_op(
+                                &mut op,
+                                account_set_id,
+                                leaf_accounts_only,
+                                query_args,
+                            )
+                            .await?
+                    }
+                    None => {
+                        app.ledger()
+                            .account_sets()
+                            .list_descendants(account_set_id, leaf_accounts_only, query_args)
+                            .await?
+                    }
+                };
+                hierarchy_connection(ctx, result).await
+            },
+        )
+        .await
+    }
+
+    async fn ancestors(
+        &self,
+        ctx: &Context<'_>,
+        first: i32,
+        after: Option<String>,
+    ) -> Result<
+        Connection<AccountSetHierarchyCursor, AccountSetHierarchyNode, EmptyFields, EmptyFields>,
+    > {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let account_set_id = AccountSetId::from(self.account_set_id);
+
+        query(
+            after,
+            None,
+            Some(first),
+            None,
+            |after, _, first, _| async move {
+                let first = first.expect("First always exists");
+                let query_args = cala_ledger::es_entity::PaginatedQueryArgs { first, after };
+
+                let result = match ctx.data_opt::<DbOp>() {
+                    Some(op) => {
+                        let mut op = op.try_lock().expect("Lock held concurrently");
+                        app.ledger()
+                            .account_sets()
+                            .list_ancestors_in_op(&mut op, account_set_id, query_args)
+                            .await?
+                    }
+                    None => {
+                        app.ledger()
+                            .account_sets()
+                            .list_ancestors(account_set_id, query_args)
+                            .await?
+                    }
+                };
+                hierarchy_connection(ctx, result).await
+            },
+        )
+        .await
+    }
+
+    /// Entries most recent first. Without a filter they are ordered by creation
+    /// time, with a filter by the effective date of their transaction.
+    async fn entries(
+        &self,
+        ctx: &Context<'_>,
+        first: i32,
+        after: Option<String>,
+        filter: Option<EntriesFilterInput>,
+    ) -> Result<Connection<EntriesCursor, Entry, EmptyFields, EmptyFields>> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let owner = EntriesOwner::AccountSet(AccountSetId::from(self.account_set_id));
+        query(
+            after,
+            None,
+            Some(first),
+            None,
+            |after, _, first, _| async move {
+                let first = first.expect("First always exists");
+                list_entries(app, owner, first, after, filter).await
             },
         )
         .await
     }
 }
 
+pub(super) async fn hierarchy_connection(
+    ctx: &Context<'_>,
+    result: cala_ledger::es_entity::PaginatedQueryRet<
+        cala_ledger::account_set::AccountSetHierarchyNode,
+        AccountSetHierarchyCursor,
+    >,
+) -> Result<Connection<AccountSetHierarchyCursor, AccountSetHierarchyNode, EmptyFields, EmptyFields>>
+{
+    let mut account_ids = Vec::new();
+    let mut set_ids = Vec::new();
+    for node in result.entities.iter() {
+        match node.id {
+            AccountSetMemberId::Account(id) => account_ids.push(id),
+            AccountSetMemberId::AccountSet(id) => set_ids.push(id),
+        }
+    }
+    let (accounts, sets) = match ctx.data_opt::<DbOp>() {
+        Some(op) => {
+            let app = ctx.data_unchecked::<CalaApp>();
+            let mut op = op.try_lock().expect("Lock held concurrently");
+            (
+                app.ledger()
+                    .accounts()
+                    .find_all_in_op(&mut op, &account_ids)
+                    .await?,
+                app.ledger()
+                    .account_sets()
+                    .find_all_in_op(&mut op, &set_ids)
+                    .await?,
+            )
+        }
+        None => {
+            let loader = ctx.data_unchecked::<DataLoader<LedgerDataLoader>>();
+            (
+                loader.load_many(account_ids).await?,
+                loader.load_many(set_ids).await?,
+            )
+        }
+    };
+
+    let mut connection = Connection::new(false, result.has_next_page);
+    // A member reachable via several paths appears once per path
+    for node in result.entities {
+        let cursor = AccountSetHierarchyCursor::from(&node);
+        let member = match node.id {
+            AccountSetMemberId::Account(id) => AccountSetMember::Account(
+                accounts
+                    .get(&id)
+                    .cloned()
+                    .ok_or_else(|| Error::new(format!("Could not find account {id}")))?,
+            ),
+            AccountSetMemberId::AccountSet(id) => AccountSetMember::AccountSet(
+                sets.get(&id)
+                    .cloned()
+                    .ok_or_else(|| Error::new(format!("Could not find account set {id}")))?,
+            ),
+        };
+        connection.edges.push(Edge::new(
+            cursor,
+            AccountSetHierarchyNode {
+                member,
+                depth: node.depth,
+                path: node.path.into_iter().map(UUID::from).collect(),
+            },
+        ));
+    }
+    Ok(connection)
+}
+
 #[derive(InputObject)]
 pub(super) struct AccountSetCreateInput {
     pub account_set_id: UUID,
@@ -309,6 +461,59 @@ pub(super) struct RemoveFromAccountSetPayload {
     pub account_set: AccountSet,
 }
 
+#[derive(InputObject)]
+pub(super) struct AccountSetArchiveInput {
+    pub account_set_id: UUID,
+    #[graphql(default)]
+    pub detach: bool,
+}
+
+#[derive(SimpleObject)]
+pub(super) struct AccountSetArchivePayload {
+    pub account_set_id: UUID,
+}
+
+#[derive(InputObject)]
+pub(super) struct AccountSetMemberInput {
+    pub member_id: UUID,
+    pub member_type: AccountSetMemberType,
+}
+
+impl From<AccountSetMemberInput> for AccountSetMemberId {
+    fn from(input: AccountSetMemberInput) -> Self {
+        match input.member_type {
+            AccountSetMemberType::Account => {
+                AccountSetMemberId::Account(AccountId::from(input.member_id))
+            }
+            AccountSetMemberType::AccountSet => {
+                AccountSetMemberId::AccountSet(AccountSetId::from(input.member_id))
+            }
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub(super) struct AddToAccountSetBatchInput {
+    pub account_set_id: UUID,
+    pub members: Vec<AccountSetMemberInput>,
+}
+
+#[derive(SimpleObject)]
+pub(super) struct AddToAccountSetBatchPayload {
+    pub account_set: AccountSet,
+}
+
+#[derive(InputObject)]
+pub(super) struct RemoveFromAccountSetBatchInput {
+    pub account_set_id: UUID,
+    pub members: Vec<AccountSetMemberInput>,
+}
+
+#[derive(SimpleObject)]
+pub(super) struct RemoveFromAccountSetBatchPayload {
+    pub account_set: AccountSet,
+}
+
 impl ToGlobalId for cala_ledger::AccountSetId {
     fn to_global_id(&self) -> async_graphql::types::ID {
         async_graphql::types::ID::from(format!("account_set:{}", self))
@@ -359,6 +564,22 @@ impl From<cala_ledger::account_set::AccountSet> for RemoveFromAccountSetPayload
     }
 }
 
+impl From<cala_ledger::account_set::AccountSet> for AddToAccountSetBatchPayload {
+    fn from(value: cala_ledger::account_set::AccountSet) -> Self {
+        Self {
+            account_set: AccountSet::from(value),
+        }
+    }
+}
+
+impl From<cala_ledger::account_set::AccountSet> for RemoveFromAccountSetBatchPayload {
+    fn from(value: cala_ledger::account_set::AccountSet) -> Self {
+        Self {
+            account_set: AccountSet::from(value),
+        }
+    }
+}
+
 #[derive(InputObject)]
 pub(super) struct AccountSetUpdateInput {
     pub name: Option<String>,
@@ -1,7 +1,10 @@
 use async_graphql::*;
 
 use super::{convert::ToGlobalId, primitives::*};
-use cala_ledger::primitives::{AccountId, Currency, JournalId};
+use cala_ledger::{
+    balance::BalanceSnapshot,
+    primitives::{AccountId, Currency, JournalId},
+};
 
 #[derive(SimpleObject)]
 pub(super) struct Money {
@@ -39,10 +42,13 @@ pub(super) struct Balance {
     pub encumbrance: BalanceAmount,
     pub version: u32,
     #[graphql(skip)]
-    pub(super) balance: cala_ledger::balance::AccountBalance,
+    pub(super) details: BalanceSnapshot,
+    #[graphql(skip)]
+    pub(super) normal_balance_type: DebitOrCredit,
 }
 
 #[derive(SimpleObject)]
+#[allow(dead_code)]
 pub(super) struct RangedBalance {
     pub start: Balance,
     pub end: Balance,
@@ -52,12 +58,25 @@ pub(super) struct RangedBalance {
 #[ComplexObject]
 impl Balance {
     async fn available(&self, layer: Layer) -> BalanceAmount {
-        let amount = self.balance.details.available(layer);
-        let currency = self.balance.details.currency;
-        BalanceAmount {
+        let amount = self.details.available(layer);
+        BalanceAmount::new(&amount, self.normal_balance_type, self.details.currency)
+    }
+}
+
+impl BalanceAmount {
+    fn new(
+        amount: &cala_ledger::balance::BalanceAmount,
+        normal_balance_type: DebitOrCredit,
+        currency: Currency,
+    ) -> Self {
+        let normal_balance = match normal_balance_type {
+            DebitOrCredit::Credit => amount.cr_balance - amount.dr_balance,
+            DebitOrCredit::Debit => amount.dr_balance - amount.cr_balance,
+        };
+        Self {
             dr_balance: (amount.dr_balance, currency).into(),
             cr_balance: (amount.cr_balance, currency).into(),
-            normal_balance: (self.balance.available(layer), currency).into(),
+            normal_balance: (normal_balance, currency).into(),
             entry_id: amount.entry_id.into(),
         }
     }
@@ -69,44 +88,35 @@ impl ToGlobalId for (JournalId, AccountId, Currency) {
     }
 }
 
-impl From<cala_ledger::balance::AccountBalance> for Balance {
-    fn from(balance: cala_ledger::balance::AccountBalance) -> Self {
-        let currency = balance.details.currency;
+impl Balance {
+    pub(super) fn from_snapshot(
+        details: BalanceSnapshot,
+        normal_balance_type: DebitOrCredit,
+    ) -> Self {
+        let currency = details.currency;
         Self {
-            id: (
-                balance.details.journal_id,
-                balance.details.account_id,
-                balance.details.currency,
-            )
-                .to_global_id(),
-            journal_id: balance.details.journal_id.into(),
-            account_id: balance.details.account_id.into(),
-            entry_id: balance.details.entry_id.into(),
-            currency: balance.details.currency.into(),
-            version: balance.details.version,
-            settled: BalanceAmount {
-                dr_balance: (balance.details.settled.dr_balance, currency).into(),
-                cr_balance: (balance.details.settled.cr_balance, currency).into(),
-                normal_balance: (balance.settled(), currency).into(),
-                entry_id: balance.details.settled.entry_id.into(),
-            },
-            pending: BalanceAmount {
-                dr_balance: (balance.details.pending.dr_balance, currency).into(),
-                cr_balance: (balance.details.pending.cr_balance, currency).into(),
-                normal_balance: (balance.pending(), currency).into(),
-                entry_id: balance.details.pending.entry_id.into(),
-            },
-            encumbrance: BalanceAmount {
-                dr_balance: (balance.details.encumbrance.dr_balance, currency).into(),
-                cr_balance: (balance.details.encumbrance.cr_balance, currency).into(),
-                normal_balance: (balance.encumbrance(), currency).into(),
-                entry_id: balance.details.encumbrance.entry_id.into(),
-            },
-            balance,
+            id: (details.journal_id, details.account_id, details.currency).to_global_id(),
+            journal_id: details.journal_id.into(),
+            account_id: details.account_id.into(),
+            entry_id: details.entry_id.into(),
+            currency: details.currency.into(),
+            version: details.version,
+            settled: BalanceAmount::new(&details.settled, normal_balance_type, currency),
+            pending: BalanceAmount::new(&details.pending, normal_balance_type, currency),
+            encumbrance: BalanceAmount::new(&details.encumbrance, normal_balance_type, currency),
+            details,
+            normal_balance_type,
         }
     }
 }
 
+impl From<cala_ledger::balance::AccountBalance> for Balance {
+    fn from(balance: cala_ledger::balance::AccountBalance) -> Self {
+        let normal_balance_type = balance.normal_balance_type();
+        Self::from_snapshot(balance.details, normal_balance_type)
+    }
+}
+
 impl From<cala_ledger::balance::BalanceRange> for RangedBalance {
     fn from(ranged_balance: cala_ledger::balance::BalanceRange) -> Self {
         Self {
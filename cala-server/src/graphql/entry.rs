@@ -1,11 +1,21 @@
-use async_graphql::{dataloader::*, *};
+use async_graphql::{
+    connection::{Connection, CursorType, Edge, EmptyFields},
+    dataloader::*,
+    *,
+};
+use serde::{Deserialize, Serialize};
 
-use cala_ledger::primitives::{AccountId, DebitOrCredit, Layer, TransactionId};
+use cala_ledger::{
+    entry::{EntriesByCreatedAtCursor, EntriesByEffectiveCursor},
+    es_entity::{ListDirection, PaginatedQueryArgs},
+    primitives::{AccountId, AccountSetId, DebitOrCredit, Layer, TransactionId},
+};
 
 use super::{
     account::Account, convert::ToGlobalId, loader::LedgerDataLoader, primitives::*,
     transaction::Transaction,
 };
+use crate::app::CalaApp;
 
 #[derive(Clone, SimpleObject)]
 #[graphql(complex)]
@@ -44,6 +54,156 @@ impl Entry {
     }
 }
 
+/// Unfiltered entries are paginated by creation time, filtered entries by the
+/// effective date of their transaction.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub(super) enum EntriesCursor {
+    ByCreatedAt(EntriesByCreatedAtCursor),
+    ByEffective(EntriesByEffectiveCursor),
+}
+
+impl CursorType for EntriesCursor {
+    type Error = String;
+
+    fn encode_cursor(&self) -> String {
+        use base64::{engine::general_purpose, Engine as _};
+        let json = serde_json::to_string(&self).expect("could not serialize token");
+        general_purpose::STANDARD_NO_PAD.encode(json.as_bytes())
+    }
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        use base64::{engine::general_purpose, Engine as _};
+        let bytes = general_purpose::STANDARD_NO_PAD
+            .decode(s.as_bytes())
+            .map_err(|e| e.to_string())?;
+        let json = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(|e| e.to_string())
+    }
+}
+
+pub(super) enum EntriesOwner {
+    Account(AccountId),
+    AccountSet(AccountSetId),
+}
+
+/// Lists the entries of `owner`, most recent first.
+pub(super) async fn list_entries(
+    app: &CalaApp,
+    owner: EntriesOwner,
+    first: usize,
+    after: Option<EntriesCursor>,
+    filter: Option<EntriesFilterInput>,
+) -> Result<Connection<EntriesCursor, Entry, EmptyFields, EmptyFields>> {
+    let entries = app.ledger().entries();
+    let (entities, has_next_page) = match filter {
+        None => {
+            let after = match after {
+                Some(EntriesCursor::ByCreatedAt(cursor)) => Some(cursor),
+                Some(EntriesCursor::ByEffective(_)) => {
+                    return Err(Error::new("cursor requires an entries filter"))
+                }
+                None => None,
+            };
+            let args = PaginatedQueryArgs { first, after };
+            let result = match owner {
+                EntriesOwner::Account(id) => {
+                    entries
+                        .list_for_account_id(id, args, ListDirection::Descending)
+                        .await?
+                }
+                EntriesOwner::AccountSet(id) => {
+                    entries
+                        .list_for_account_set_id(id, args, ListDirection::Descending)
+                        .await?
+                }
+            };
+            let entities: Vec<_> = result
+                .entities
+                .into_iter()
+                .map(|entity| {
+                    let cursor =
+                        EntriesCursor::ByCreatedAt(EntriesByCreatedAtCursor::from(&entity));
+                    (cursor, entity)
+                })
+                .collect();
+            (entities, result.has_next_page)
+        }
+        Some(filter) => {
+            let after = match after {
+                Some(EntriesCursor::ByEffective(cursor)) => Some(cursor),
+                Some(EntriesCursor::ByCreatedAt(_)) => {
+                    return Err(Error::new("cursor can't be used with an entries filter"))
+                }
+                None => None,
+            };
+            let args = PaginatedQueryArgs { first, after };
+            let result = match owner {
+                EntriesOwner::Account(id) => {
+                    entries
+                        .list_filtered_for_account_id(
+                            id,
+                            filter.into(),
+                            args,
+                            ListDirection::Descending,
+                        )
+                        .await?
+                }
+                EntriesOwner::AccountSet(id) => {
+                    entries
+                        .list_filtered_for_account_set_id(
+                            id,
+                            filter.into(),
+                            args,
+                            ListDirection::Descending,
+                        )
+                        .await?
+                }
+            };
+            let entities: Vec<_> = result
+                .entities
+                .into_iter()
+                .map(|entity| {
+                    let cursor =
+                        EntriesCursor::ByEffective(EntriesByEffectiveCursor::from(&entity));
+                    (cursor, entity)
+                })
+                .collect();
+            (entities, result.has_next_page)
+        }
+    };
+    let mut connection = Connection::new(false, has_next_page);
+    connection.edges.extend(
+        entities
+            .into_iter()
+            .map(|(cursor, entity)| Edge::new(cursor, Entry::from(entity))),
+    );
+    Ok(connection)
+}
+
+#[derive(InputObject, Default)]
+pub(super) struct EntriesFilterInput {
+    pub effective_from: Option<Date>,
+    pub effective_until: Option<Date>,
+    pub entry_type: Option<String>,
+    pub layer: Option<Layer>,
+    pub currency: Option<CurrencyCode>,
+    pub direction: Option<DebitOrCredit>,
+}
+
+impl From<EntriesFilterInput> for cala_ledger::entry::EntriesFilter {
+    fn from(input: EntriesFilterInput) -> Self {
+        Self {
+            effective_from: input.effective_from.map(Into::into),
+            effective_until: input.effective_until.map(Into::into),
+            entry_type: input.entry_type,
+            layer: input.layer,
+            currency: input.currency.map(Into::into),
+            direction: input.direction,
+        }
+    }
+}
+
 impl ToGlobalId for cala_ledger::EntryId {
     fn to_global_id(&self) -> async_graphql::types::ID {
         use base64::{engine::general_purpose, Engine as _};
@@ -0,0 +1,81 @@
+use async_graphql::{Error, ErrorExtensions};
+use cel_interpreter::CelError;
+
+use cala_ledger::{
+    error::LedgerError,
+    tx_template::{error::TxTemplateError, ParamError},
+    velocity::error::VelocityError,
+};
+
+/// Errors that may have been caused by a CEL expression.
+pub(super) trait CelErrorSource: std::fmt::Display {
+    /// The CEL error and, if known, the template field holding the expression.
+    fn cel_error(&self) -> Option<(Option<&str>, &CelError)>;
+}
+
+impl CelErrorSource for TxTemplateError {
+    fn cel_error(&self) -> Option<(Option<&str>, &CelError)> {
+        match self {
+            TxTemplateError::TypeCheck(field, _, e) => Some((Some(field), e)),
+            TxTemplateError::CelError(e) => Some((None, e)),
+            TxTemplateError::ParamError(e) => e.cel_error(),
+            _ => None,
+        }
+    }
+}
+
+impl CelErrorSource for VelocityError {
+    fn cel_error(&self) -> Option<(Option<&str>, &CelError)> {
+        match self {
+            VelocityError::CelError(e) => Some((None, e)),
+            VelocityError::ParamError(e) => e.cel_error(),
+            _ => None,
+        }
+    }
+}
+
+impl CelErrorSource for ParamError {
+    fn cel_error(&self) -> Option<(Option<&str>, &CelError)> {
+        match self {
+            ParamError::CelError(e) => Some((None, e)),
+            _ => None,
+        }
+    }
+}
+
+impl CelErrorSource for LedgerError {
+    fn cel_error(&self) -> Option<(Option<&str>, &CelError)> {
+        match self {
+            LedgerError::TxTemplateError(e) => e.cel_error(),
+            LedgerError::VelocityError(e) => e.cel_error(),
+            _ => None,
+        }
+    }
+}
+
+/// Converts the error into a GraphQL error. Errors caused by a CEL expression
+/// expose the failing field, sub-expression and its line / column as extensions.
+pub(super) fn into_graphql_error(err: impl CelErrorSource) -> Error {
+    let error = Error::new(err.to_string());
+    let Some((field, cel_error)) = err.cel_error() else {
+        return error;
+    };
+    let code = match cel_error {
+        CelError::CelParseError(..) => "CEL_PARSE_ERROR",
+        CelError::TypeError(..) => "CEL_TYPE_ERROR",
+        _ => "CEL_EVALUATION_ERROR",
+    };
+    error.extend_with(|_, ext| {
+        ext.set("code", code);
+        if let Some(field) = field {
+            ext.set("field", field);
+        }
+        if let Some(location) = cel_error.location() {
+            ext.set("line", location.line as u64);
+            ext.set("column", location.column as u64);
+        }
+        if let Some(sub_expression) = cel_error.sub_expression() {
+            ext.set("subExpression", sub_expression);
+        }
+    })
+}
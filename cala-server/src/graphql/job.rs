@@ -12,6 +12,7 @@ pub struct Job {
 }
 
 #[derive(SimpleObject)]
+#[allow(dead_code)]
 pub struct JobCreatePayload {
     pub job: Job,
 }
@@ -2,6 +2,8 @@ use async_graphql::*;
 
 use super::{convert::ToGlobalId, primitives::*};
 
+pub use cala_ledger::journal::{JournalsCursor, JournalsSortBy};
+
 #[derive(InputObject)]
 pub struct JournalCreateInput {
     pub(super) journal_id: UUID,
@@ -28,6 +30,18 @@ pub struct JournalCreatePayload {
     pub journal: Journal,
 }
 
+pub(super) fn journals_cursor(
+    sort_by: JournalsSortBy,
+    journal: &cala_ledger::journal::Journal,
+) -> JournalsCursor {
+    use cala_ledger::journal::*;
+    match sort_by {
+        JournalsSortBy::Name => JournalsByNameCursor::from(journal).into(),
+        JournalsSortBy::CreatedAt => JournalsByCreatedAtCursor::from(journal).into(),
+        JournalsSortBy::Id => JournalsByIdCursor::from(journal).into(),
+    }
+}
+
 impl ToGlobalId for cala_ledger::JournalId {
     fn to_global_id(&self) -> async_graphql::types::ID {
         async_graphql::types::ID::from(format!("journal:{}", self))
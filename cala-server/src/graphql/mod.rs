@@ -3,11 +3,13 @@ pub mod account_set;
 pub mod balance;
 mod convert;
 pub mod entry;
+mod error;
 mod job;
 pub mod journal;
 pub mod loader;
 pub mod primitives;
 mod schema;
+mod subscription;
 mod timestamp;
 pub mod transaction;
 pub mod tx_template;
@@ -17,17 +19,18 @@ use async_graphql::{dataloader::*, *};
 
 pub use job::Job;
 pub use schema::*;
+pub use subscription::*;
 
 use crate::{app::CalaApp, extension::*};
 use loader::LedgerDataLoader;
 
 pub fn schema<Q: QueryExtensionMarker, M: MutationExtensionMarker>(
     app: Option<CalaApp>,
-) -> Schema<CoreQuery<Q>, CoreMutation<M>, EmptySubscription> {
+) -> Schema<CoreQuery<Q>, CoreMutation<M>, CoreSubscription> {
     let schema = Schema::build(
         CoreQuery::<Q>::default(),
         CoreMutation::<M>::default(),
-        EmptySubscription,
+        CoreSubscription,
     );
     if let Some(app) = app {
         schema
@@ -47,6 +47,7 @@ pub enum ParamDataType {
     Date,
     Timestamp,
     Json,
+    List,
 }
 
 #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
@@ -108,6 +109,12 @@ impl From<UUID> for crate::primitives::JobId {
     }
 }
 
+impl From<UUID> for crate::primitives::WebhookDeadLetterId {
+    fn from(uuid: UUID) -> Self {
+        crate::primitives::WebhookDeadLetterId::from(uuid.0)
+    }
+}
+
 impl From<UUID> for crate::integration::IntegrationId {
     fn from(uuid: UUID) -> Self {
         crate::integration::IntegrationId::from(uuid.0)
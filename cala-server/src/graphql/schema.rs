@@ -6,8 +6,8 @@ use tokio::sync::Mutex;
 use crate::{app::CalaApp, extension::*};
 
 use super::{
-    account::*, account_set::*, balance::*, journal::*, loader::*, primitives::*, transaction::*,
-    tx_template::*, velocity::*,
+    account::*, account_set::*, balance::*, error::*, journal::*, loader::*, primitives::*,
+    transaction::*, tx_template::*, velocity::*,
 };
 
 pub type DbOp<'a> = Arc<Mutex<cala_ledger::LedgerOperation<'a>>>;
@@ -65,8 +65,10 @@ impl<E: QueryExtensionMarker> CoreQuery<E> {
         ctx: &Context<'_>,
         first: i32,
         after: Option<String>,
+        filter: Option<AccountsFilterInput>,
     ) -> Result<Connection<AccountsByNameCursor, Account, EmptyFields, EmptyFields>> {
         let app = ctx.data_unchecked::<CalaApp>();
+        let filter = cala_ledger::account::AccountsFilter::from(filter.unwrap_or_default());
         query(
             after,
             None,
@@ -77,7 +79,10 @@ impl<E: QueryExtensionMarker> CoreQuery<E> {
                 let result = app
                     .ledger()
                     .accounts()
-                    .list(cala_ledger::es_entity::PaginatedQueryArgs { first, after })
+                    .list_filtered(
+                        filter,
+                        cala_ledger::es_entity::PaginatedQueryArgs { first, after },
+                    )
                     .await?;
                 let mut connection = Connection::new(false, result.has_next_page);
                 connection
@@ -106,6 +111,47 @@ impl<E: QueryExtensionMarker> CoreQuery<E> {
         Ok(loader.load_one(JournalId::from(id)).await?)
     }
 
+    async fn journals(
+        &self,
+        ctx: &Context<'_>,
+        first: i32,
+        after: Option<String>,
+        status: Option<Status>,
+        #[graphql(default)] sort_by: JournalsSortBy,
+    ) -> Result<Connection<JournalsCursor, Journal, EmptyFields, EmptyFields>> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        query(
+            after,
+            None,
+            Some(first),
+            None,
+            |after, _, first, _| async move {
+                let first = first.expect("First always exists");
+                let result = app
+                    .ledger()
+                    .journals()
+                    .list(
+                        status,
+                        cala_ledger::es_entity::Sort {
+                            by: sort_by,
+                            direction: Default::default(),
+                        },
+                        cala_ledger::es_entity::PaginatedQueryArgs { first, after },
+                    )
+                    .await?;
+                let mut connection = Connection::new(false, result.has_next_page);
+                connection
+                    .edges
+                    .extend(result.entities.into_iter().map(|entity| {
+                        let cursor = journals_cursor(sort_by, &entity);
+                        Edge::new(cursor, Journal::from(entity))
+                    }));
+                Ok::<_, async_graphql::Error>(connection)
+            },
+        )
+        .await
+    }
+
     async fn balance(
         &self,
         ctx: &Context<'_>,
@@ -151,6 +197,70 @@ impl<E: QueryExtensionMarker> CoreQuery<E> {
         }
     }
 
+    async fn transactions_by_correlation_id(
+        &self,
+        ctx: &Context<'_>,
+        correlation_id: String,
+        first: i32,
+        after: Option<String>,
+    ) -> Result<Connection<TransactionsByCreatedAtCursor, Transaction, EmptyFields, EmptyFields>>
+    {
+        let app = ctx.data_unchecked::<CalaApp>();
+        query(
+            after,
+            None,
+            Some(first),
+            None,
+            |after, _, first, _| async move {
+                let first = first.expect("First always exists");
+                let result = app
+                    .ledger()
+                    .transactions()
+                    .list_for_correlation_id(
+                        correlation_id,
+                        cala_ledger::es_entity::PaginatedQueryArgs { first, after },
+                        cala_ledger::es_entity::ListDirection::Ascending,
+                    )
+                    .await?;
+                Ok::<_, async_graphql::Error>(transactions_connection(result))
+            },
+        )
+        .await
+    }
+
+    async fn transactions_for_journal(
+        &self,
+        ctx: &Context<'_>,
+        journal_id: UUID,
+        first: i32,
+        after: Option<String>,
+        filter: Option<TransactionsFilterInput>,
+    ) -> Result<Connection<TransactionsByCreatedAtCursor, Transaction, EmptyFields, EmptyFields>>
+    {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let journal_id = JournalId::from(journal_id);
+        query(
+            after,
+            None,
+            Some(first),
+            None,
+            |after, _, first, _| async move {
+                let first = first.expect("First always exists");
+                let result = app
+                    .ledger()
+                    .transactions()
+                    .list_for_journal_id(
+                        journal_id,
+                        filter.unwrap_or_default().into(),
+                        cala_ledger::es_entity::PaginatedQueryArgs { first, after },
+                    )
+                    .await?;
+                Ok::<_, async_graphql::Error>(transactions_connection(result))
+            },
+        )
+        .await
+    }
+
     async fn tx_template(
         &self,
         ctx: &Context<'_>,
@@ -369,6 +479,31 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
         Ok(account_set.into())
     }
 
+    async fn account_set_archive(
+        &self,
+        ctx: &Context<'_>,
+        input: AccountSetArchiveInput,
+    ) -> Result<AccountSetArchivePayload> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let mut op = ctx
+            .data_unchecked::<DbOp>()
+            .try_lock()
+            .expect("Lock held concurrently");
+
+        app.ledger()
+            .account_sets()
+            .archive_in_op(
+                &mut op,
+                AccountSetId::from(input.account_set_id),
+                input.detach,
+            )
+            .await?;
+
+        Ok(AccountSetArchivePayload {
+            account_set_id: input.account_set_id,
+        })
+    }
+
     async fn add_to_account_set(
         &self,
         ctx: &Context<'_>,
@@ -409,6 +544,56 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
         Ok(account_set.into())
     }
 
+    async fn add_to_account_set_batch(
+        &self,
+        ctx: &Context<'_>,
+        input: AddToAccountSetBatchInput,
+    ) -> Result<AddToAccountSetBatchPayload> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let mut op = ctx
+            .data_unchecked::<DbOp>()
+            .try_lock()
+            .expect("Lock held concurrently");
+
+        let members = input
+            .members
+            .into_iter()
+            .map(cala_ledger::account_set::AccountSetMemberId::from)
+            .collect();
+        let account_set = app
+            .ledger()
+            .account_sets()
+            .add_members_in_op(&mut op, AccountSetId::from(input.account_set_id), members)
+            .await?;
+
+        Ok(account_set.into())
+    }
+
+    async fn remove_from_account_set_batch(
+        &self,
+        ctx: &Context<'_>,
+        input: RemoveFromAccountSetBatchInput,
+    ) -> Result<RemoveFromAccountSetBatchPayload> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let mut op = ctx
+            .data_unchecked::<DbOp>()
+            .try_lock()
+            .expect("Lock held concurrently");
+
+        let members = input
+            .members
+            .into_iter()
+            .map(cala_ledger::account_set::AccountSetMemberId::from)
+            .collect();
+        let account_set = app
+            .ledger()
+            .account_sets()
+            .remove_members_in_op(&mut op, AccountSetId::from(input.account_set_id), members)
+            .await?;
+
+        Ok(account_set.into())
+    }
+
     async fn journal_create(
         &self,
         ctx: &Context<'_>,
@@ -518,6 +703,9 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
                 if let Some(desc) = param.description {
                     param_builder.description(desc);
                 }
+                if let Some(constraints) = param.constraints {
+                    constraints.apply(&mut param_builder);
+                }
                 let new_param = param_builder.build()?;
                 new_params.push(new_param);
             }
@@ -533,6 +721,8 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
                 units,
                 currency,
                 description,
+                condition,
+                for_each,
             } = entry;
             let mut new_entry_input_builder =
                 cala_ledger::tx_template::NewTxTemplateEntry::builder();
@@ -546,6 +736,12 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
             if let Some(desc) = description {
                 new_entry_input_builder.description(desc);
             }
+            if let Some(condition) = condition {
+                new_entry_input_builder.condition(condition);
+            }
+            if let Some(TxTemplateEntryForEachInput { list, var }) = for_each {
+                new_entry_input_builder.for_each(list, var);
+            }
             let new_entry_input = new_entry_input_builder.build()?;
             new_entries.push(new_entry_input);
         }
@@ -569,7 +765,8 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
             .ledger()
             .tx_templates()
             .create_in_op(&mut op, new_tx_template)
-            .await?;
+            .await
+            .map_err(into_graphql_error)?;
 
         Ok(tx_template.into())
     }
@@ -593,10 +790,40 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
                 &input.tx_template_code,
                 params.unwrap_or_default(),
             )
-            .await?;
+            .await
+            .map_err(into_graphql_error)?;
         Ok(transaction.into())
     }
 
+    async fn transaction_annotate(
+        &self,
+        ctx: &Context<'_>,
+        input: TransactionAnnotateInput,
+    ) -> Result<TransactionAnnotatePayload> {
+        let app = ctx.data_unchecked::<CalaApp>();
+        let mut op = ctx
+            .data_unchecked::<DbOp>()
+            .try_lock()
+            .expect("Lock held concurrently");
+        let mut builder = cala_ledger::transaction::NewTransactionAnnotation::builder();
+        builder.actor(input.actor).tags(input.tags);
+        if let Some(metadata) = input.metadata {
+            builder.metadata(metadata)?;
+        }
+        let transaction = app
+            .ledger()
+            .transactions()
+            .annotate_in_op(
+                &mut op,
+                TransactionId::from(input.transaction_id),
+                builder.build()?,
+            )
+            .await?;
+        Ok(TransactionAnnotatePayload {
+            transaction: transaction.into(),
+        })
+    }
+
     async fn velocity_limit_create(
         &self,
         ctx: &Context<'_>,
@@ -676,6 +903,9 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
                 if let Some(description) = param.description {
                     param_builder.description(description);
                 }
+                if let Some(constraints) = param.constraints {
+                    constraints.apply(&mut param_builder);
+                }
                 let new_param = param_builder.build()?;
                 new_params.push(new_param);
             }
@@ -688,7 +918,8 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
             .ledger()
             .velocities()
             .create_limit_in_op(&mut op, new_velocity_limit)
-            .await?;
+            .await
+            .map_err(into_graphql_error)?;
 
         Ok(velocity_limit.into())
     }
@@ -726,7 +957,8 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
             .ledger()
             .velocities()
             .create_control_in_op(&mut op, new_velocity_control)
-            .await?;
+            .await
+            .map_err(into_graphql_error)?;
 
         Ok(velocity_control.into())
     }
@@ -750,7 +982,8 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
                 input.velocity_control_id.into(),
                 input.velocity_limit_id.into(),
             )
-            .await?;
+            .await
+            .map_err(into_graphql_error)?;
 
         Ok(velocity_limit.into())
     }
@@ -776,7 +1009,8 @@ impl<E: MutationExtensionMarker> CoreMutation<E> {
                 input.account_id.into(),
                 params,
             )
-            .await?;
+            .await
+            .map_err(into_graphql_error)?;
 
         Ok(velocity_control.into())
     }
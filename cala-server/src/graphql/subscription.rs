@@ -0,0 +1,184 @@
+use async_graphql::*;
+use futures::{Stream, StreamExt};
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use cala_ledger::{
+    outbox::{
+        EventSequence, OutboxEvent, OutboxEventFilter, OutboxEventPayload, OutboxEventPayloadType,
+    },
+    primitives::*,
+    CalaLedger,
+};
+
+use super::{balance::*, primitives::*, transaction::*};
+use crate::app::CalaApp;
+
+#[derive(SimpleObject)]
+pub struct TransactionCreatedEvent {
+    sequence: u64,
+    recorded_at: Timestamp,
+    transaction: Transaction,
+}
+
+#[derive(SimpleObject)]
+pub struct BalanceUpdatedEvent {
+    sequence: u64,
+    recorded_at: Timestamp,
+    balance: Balance,
+}
+
+#[derive(Default)]
+pub struct CoreSubscription;
+
+#[Subscription(name = "Subscription")]
+impl CoreSubscription {
+    /// Transactions posted to the journal, optionally only those involving the account.
+    /// Filtering by account loads the entries of every transaction in the journal.
+    /// Pass the `sequence` of the last received event as `startAfter` to resume.
+    async fn transaction_created(
+        &self,
+        ctx: &Context<'_>,
+        journal_id: UUID,
+        account_id: Option<UUID>,
+        start_after: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<TransactionCreatedEvent>>> {
+        let ledger = ctx.data_unchecked::<CalaApp>().ledger().clone();
+        let filter = OutboxEventFilter {
+            payload_types: vec![OutboxEventPayloadType::TransactionCreated],
+            journal_ids: vec![JournalId::from(journal_id)],
+            ..Default::default()
+        };
+        let account_id = account_id.map(AccountId::from);
+        let listener = ledger
+            .register_filtered_outbox_listener(start_after.map(EventSequence::from), filter)
+            .await?;
+        Ok(listener.filter_map(move |event| {
+            let ledger = ledger.clone();
+            async move {
+                match event {
+                    Ok(event) => transaction_created(&ledger, event, account_id)
+                        .await
+                        .transpose(),
+                    Err(e) => Some(Err(e.into())),
+                }
+            }
+        }))
+    }
+
+    /// Balance changes of the accounts and account sets in any currency.
+    /// Pass the `sequence` of the last received event as `startAfter` to resume.
+    async fn balance_updated(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] account_ids: Vec<UUID>,
+        #[graphql(default)] account_set_ids: Vec<UUID>,
+        journal_id: Option<UUID>,
+        start_after: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<BalanceUpdatedEvent>>> {
+        if account_ids.is_empty() && account_set_ids.is_empty() {
+            return Err(Error::new(
+                "At least one of accountIds or accountSetIds must be provided",
+            ));
+        }
+        let ledger = ctx.data_unchecked::<CalaApp>().ledger().clone();
+        let filter = OutboxEventFilter {
+            payload_types: vec![
+                OutboxEventPayloadType::BalanceCreated,
+                OutboxEventPayloadType::BalanceUpdated,
+            ],
+            journal_ids: journal_id.into_iter().map(JournalId::from).collect(),
+            account_ids: account_ids
+                .into_iter()
+                .map(AccountId::from)
+                .chain(
+                    account_set_ids
+                        .into_iter()
+                        .map(|id| AccountId::from(AccountSetId::from(id))),
+                )
+                .collect(),
+            ..Default::default()
+        };
+        let listener = ledger
+            .register_filtered_outbox_listener(start_after.map(EventSequence::from), filter)
+            .await?;
+        let normal_balance_types = Arc::new(Mutex::new(HashMap::new()));
+        Ok(listener.filter_map(move |event| {
+            let ledger = ledger.clone();
+            let normal_balance_types = Arc::clone(&normal_balance_types);
+            async move {
+                match event {
+                    Ok(event) => balance_updated(&ledger, &normal_balance_types, event)
+                        .await
+                        .transpose(),
+                    Err(e) => Some(Err(e.into())),
+                }
+            }
+        }))
+    }
+}
+
+async fn transaction_created(
+    ledger: &CalaLedger,
+    event: OutboxEvent,
+    account_id: Option<AccountId>,
+) -> Result<Option<TransactionCreatedEvent>> {
+    let OutboxEventPayload::TransactionCreated { transaction, .. } = event.payload else {
+        return Ok(None);
+    };
+    // Transactions only reference accounts via their entries
+    if let Some(account_id) = account_id {
+        let entries = ledger.entries().find_all(&transaction.entry_ids).await?;
+        if !entries
+            .values()
+            .any(|entry| entry.values().account_id == account_id)
+        {
+            return Ok(None);
+        }
+    }
+    Ok(Some(TransactionCreatedEvent {
+        sequence: u64::from(event.sequence),
+        recorded_at: Timestamp::from(event.recorded_at),
+        transaction: Transaction::from(transaction),
+    }))
+}
+
+/// The normal balance type of the subscribed accounts looked up once per stream.
+type NormalBalanceTypes = Mutex<HashMap<AccountId, DebitOrCredit>>;
+
+async fn balance_updated(
+    ledger: &CalaLedger,
+    normal_balance_types: &NormalBalanceTypes,
+    event: OutboxEvent,
+) -> Result<Option<BalanceUpdatedEvent>> {
+    let balance = match event.payload {
+        OutboxEventPayload::BalanceCreated { balance, .. }
+        | OutboxEventPayload::BalanceUpdated { balance, .. } => balance,
+        _ => return Ok(None),
+    };
+    let cached = normal_balance_types
+        .lock()
+        .expect("poisoned lock")
+        .get(&balance.account_id)
+        .copied();
+    let normal_balance_type = match cached {
+        Some(normal_balance_type) => normal_balance_type,
+        None => {
+            let account = ledger.accounts().find(balance.account_id).await?;
+            let normal_balance_type = account.values().normal_balance_type;
+            normal_balance_types
+                .lock()
+                .expect("poisoned lock")
+                .insert(balance.account_id, normal_balance_type);
+            normal_balance_type
+        }
+    };
+    Ok(Some(BalanceUpdatedEvent {
+        sequence: u64::from(event.sequence),
+        recorded_at: Timestamp::from(event.recorded_at),
+        balance: Balance::from_snapshot(balance, normal_balance_type),
+    }))
+}
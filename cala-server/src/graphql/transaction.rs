@@ -1,4 +1,7 @@
-use async_graphql::*;
+use async_graphql::{types::connection::*, *};
+
+pub use cala_ledger::transaction::TransactionsByCreatedAtCursor;
+use cala_ledger::{es_entity::PaginatedQueryRet, primitives::TxTemplateId};
 
 use super::{convert::ToGlobalId, primitives::*};
 
@@ -21,10 +24,46 @@ pub struct Transaction {
     external_id: Option<String>,
     description: Option<String>,
     metadata: Option<JSON>,
+    tags: Vec<String>,
     created_at: Timestamp,
     modified_at: Timestamp,
 }
 
+#[derive(InputObject)]
+pub struct TransactionAnnotateInput {
+    pub transaction_id: UUID,
+    pub actor: String,
+    pub metadata: Option<JSON>,
+    #[graphql(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct TransactionAnnotatePayload {
+    pub transaction: Transaction,
+}
+
+#[derive(InputObject, Default)]
+pub struct TransactionsFilterInput {
+    pub tx_template_id: Option<UUID>,
+    pub created_from: Option<Timestamp>,
+    pub created_until: Option<Timestamp>,
+    pub effective_from: Option<Date>,
+    pub effective_until: Option<Date>,
+}
+
+impl From<TransactionsFilterInput> for cala_ledger::transaction::TransactionsFilter {
+    fn from(input: TransactionsFilterInput) -> Self {
+        Self {
+            tx_template_id: input.tx_template_id.map(TxTemplateId::from),
+            created_from: input.created_from.map(|t| t.into_inner()),
+            created_until: input.created_until.map(|t| t.into_inner()),
+            effective_from: input.effective_from.map(Into::into),
+            effective_until: input.effective_until.map(Into::into),
+        }
+    }
+}
+
 #[derive(SimpleObject)]
 pub struct TransactionPostPayload {
     pub transaction: Transaction,
@@ -45,7 +84,16 @@ impl From<cala_ledger::transaction::Transaction> for Transaction {
     fn from(entity: cala_ledger::transaction::Transaction) -> Self {
         let created_at = entity.created_at();
         let modified_at = entity.modified_at();
-        let values = entity.into_values();
+        Self {
+            created_at: Timestamp::from(created_at),
+            modified_at: Timestamp::from(modified_at),
+            ..Self::from(entity.into_values())
+        }
+    }
+}
+
+impl From<cala_ledger::transaction::TransactionValues> for Transaction {
+    fn from(values: cala_ledger::transaction::TransactionValues) -> Self {
         Self {
             id: values.id.to_global_id(),
             transaction_id: UUID::from(values.id),
@@ -57,8 +105,9 @@ impl From<cala_ledger::transaction::Transaction> for Transaction {
             external_id: values.external_id,
             description: values.description,
             metadata: values.metadata.map(JSON::from),
-            created_at: Timestamp::from(created_at),
-            modified_at: Timestamp::from(modified_at),
+            tags: values.tags,
+            created_at: Timestamp::from(values.created_at),
+            modified_at: Timestamp::from(values.modified_at),
         }
     }
 }
@@ -70,3 +119,16 @@ impl From<cala_ledger::transaction::Transaction> for TransactionPostPayload {
         }
     }
 }
+
+pub(super) fn transactions_connection(
+    result: PaginatedQueryRet<cala_ledger::transaction::Transaction, TransactionsByCreatedAtCursor>,
+) -> Connection<TransactionsByCreatedAtCursor, Transaction, EmptyFields, EmptyFields> {
+    let mut connection = Connection::new(false, result.has_next_page);
+    connection
+        .edges
+        .extend(result.entities.into_iter().map(|entity| {
+            let cursor = TransactionsByCreatedAtCursor::from(&entity);
+            Edge::new(cursor, Transaction::from(entity))
+        }));
+    connection
+}
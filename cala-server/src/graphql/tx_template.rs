@@ -23,6 +23,18 @@ pub(super) struct ParamDefinition {
     r#type: ParamDataType,
     default: Option<Expression>,
     description: Option<String>,
+    constraints: ParamConstraints,
+}
+
+#[derive(Clone, SimpleObject)]
+pub(super) struct ParamConstraints {
+    required: bool,
+    allowed_values: Vec<Expression>,
+    min: Option<Decimal>,
+    max: Option<Decimal>,
+    scale: Option<u32>,
+    regex: Option<String>,
+    predicate: Option<Expression>,
 }
 
 #[derive(Clone, SimpleObject)]
@@ -35,6 +47,14 @@ pub(super) struct TxTemplateEntry {
     currency: Expression,
     description: Option<Expression>,
     metadata: Option<Expression>,
+    condition: Option<Expression>,
+    for_each: Option<TxTemplateEntryForEach>,
+}
+
+#[derive(Clone, SimpleObject)]
+pub(super) struct TxTemplateEntryForEach {
+    list: Expression,
+    var: String,
 }
 
 #[derive(Clone, SimpleObject)]
@@ -77,6 +97,14 @@ pub(super) struct TxTemplateEntryInput {
     pub units: Expression,
     pub currency: Expression,
     pub description: Option<Expression>,
+    pub condition: Option<Expression>,
+    pub for_each: Option<TxTemplateEntryForEachInput>,
+}
+
+#[derive(InputObject)]
+pub(super) struct TxTemplateEntryForEachInput {
+    pub list: Expression,
+    pub var: String,
 }
 
 #[derive(InputObject)]
@@ -85,6 +113,44 @@ pub(super) struct ParamDefinitionInput {
     pub r#type: ParamDataType,
     pub default: Option<Expression>,
     pub description: Option<String>,
+    pub constraints: Option<ParamConstraintsInput>,
+}
+
+#[derive(InputObject)]
+pub(super) struct ParamConstraintsInput {
+    #[graphql(default)]
+    pub required: bool,
+    #[graphql(default)]
+    pub allowed_values: Vec<Expression>,
+    pub min: Option<Decimal>,
+    pub max: Option<Decimal>,
+    pub scale: Option<u32>,
+    pub regex: Option<String>,
+    pub predicate: Option<Expression>,
+}
+
+impl ParamConstraintsInput {
+    pub(super) fn apply(self, builder: &mut cala_ledger::tx_template::NewParamDefinitionBuilder) {
+        builder.required(self.required);
+        for value in self.allowed_values {
+            builder.allowed_value(value);
+        }
+        if let Some(min) = self.min {
+            builder.min(min.into());
+        }
+        if let Some(max) = self.max {
+            builder.max(max.into());
+        }
+        if let Some(scale) = self.scale {
+            builder.scale(scale);
+        }
+        if let Some(regex) = self.regex {
+            builder.regex(regex);
+        }
+        if let Some(predicate) = self.predicate {
+            builder.predicate(predicate);
+        }
+    }
 }
 
 #[derive(SimpleObject)]
@@ -161,6 +227,8 @@ impl From<cala_ledger::tx_template::TxTemplateEntry> for TxTemplateEntry {
             currency,
             description,
             metadata,
+            condition,
+            for_each,
         }: cala_ledger::tx_template::TxTemplateEntry,
     ) -> Self {
         Self {
@@ -172,6 +240,11 @@ impl From<cala_ledger::tx_template::TxTemplateEntry> for TxTemplateEntry {
             currency: Expression::from(currency),
             description: description.map(Expression::from),
             metadata: metadata.map(Expression::from),
+            condition: condition.map(Expression::from),
+            for_each: for_each.map(|for_each| TxTemplateEntryForEach {
+                list: Expression::from(for_each.list),
+                var: for_each.var,
+            }),
         }
     }
 }
@@ -184,6 +257,31 @@ impl From<cala_ledger::tx_template::ParamDefinition> for ParamDefinition {
             r#type: ParamDataType::from(value.r#type),
             default,
             description: value.description,
+            constraints: ParamConstraints::from(value.constraints),
+        }
+    }
+}
+
+impl From<cala_ledger::tx_template::ParamConstraints> for ParamConstraints {
+    fn from(
+        cala_ledger::tx_template::ParamConstraints {
+            required,
+            allowed_values,
+            min,
+            max,
+            scale,
+            regex,
+            predicate,
+        }: cala_ledger::tx_template::ParamConstraints,
+    ) -> Self {
+        Self {
+            required,
+            allowed_values: allowed_values.into_iter().map(Expression::from).collect(),
+            min: min.map(Decimal::from),
+            max: max.map(Decimal::from),
+            scale,
+            regex: regex.map(String::from),
+            predicate: predicate.map(Expression::from),
         }
     }
 }
@@ -64,6 +64,23 @@ impl JobExecutor {
         Ok(())
     }
 
+    pub async fn cancel_job(
+        &self,
+        db: &mut Transaction<'_, Postgres>,
+        id: JobId,
+    ) -> Result<(), JobError> {
+        sqlx::query!(
+            r#"
+          DELETE FROM job_executions
+          WHERE id = $1
+        "#,
+            id as JobId,
+        )
+        .execute(&mut **db)
+        .await?;
+        Ok(())
+    }
+
     pub async fn start_poll(&mut self) -> Result<(), JobError> {
         let pool = self.pool.clone();
         let poll_interval = self.config.poll_interval;
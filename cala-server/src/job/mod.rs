@@ -75,6 +75,17 @@ impl Jobs {
         Ok(job)
     }
 
+    /// Stops the job from being scheduled again. A currently running
+    /// execution is not interrupted.
+    #[instrument(name = "cala_server.jobs.cancel", skip(self, op))]
+    pub async fn cancel_in_op(
+        &self,
+        op: &mut LedgerOperation<'_>,
+        id: JobId,
+    ) -> Result<(), JobError> {
+        self.executor.cancel_job(op.tx(), id).await
+    }
+
     #[instrument(name = "cala_server.jobs.find", skip(self))]
     pub async fn find(&self, id: JobId) -> Result<Job, JobError> {
         self.repo.find_by_id(id).await
@@ -20,6 +20,11 @@ impl JobRegistry {
     }
 
     pub fn add_initializer<I: JobInitializer + Default>(&mut self) {
+        self.add_configured_initializer(I::default());
+    }
+
+    /// Like [add_initializer](Self::add_initializer) for initializers that need configuration.
+    pub fn add_configured_initializer<I: JobInitializer>(&mut self, initializer: I) {
         let job_type = <I as JobInitializer>::job_type();
         assert!(
             !self.initializers.contains_key(&job_type),
@@ -28,7 +33,7 @@ impl JobRegistry {
         );
 
         self.initializers
-            .insert(<I as JobInitializer>::job_type(), Box::<I>::default());
+            .insert(<I as JobInitializer>::job_type(), Box::new(initializer));
         self.retry_settings.insert(
             <I as JobInitializer>::job_type(),
             <I as JobInitializer>::retry_on_error_settings(),
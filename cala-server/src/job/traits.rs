@@ -34,6 +34,7 @@ pub enum JobCompletion {
 }
 
 #[async_trait]
+#[allow(clippy::double_must_use)]
 pub trait JobRunner: Send + Sync + 'static {
     async fn run(
         &self,
@@ -4,3 +4,4 @@ pub struct ServerId(String);
 
 es_entity::entity_id! { ImportJobId }
 es_entity::entity_id! { JobId }
+es_entity::entity_id! { WebhookDeadLetterId }
@@ -1,7 +1,7 @@
 mod config;
 
 use async_graphql::*;
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use axum::{routing::get, Extension, Router};
 use axum_extra::headers::HeaderMap;
 use cala_ledger::{CalaLedger, LedgerOperation};
@@ -25,6 +25,7 @@ pub async fn run<Q: QueryExtensionMarker, M: MutationExtensionMarker>(
             "/graphql",
             get(playground).post(axum::routing::post(graphql_handler::<Q, M>)),
         )
+        .route_service("/graphql/ws", GraphQLSubscription::new(schema.clone()))
         .layer(Extension(schema))
         .layer(Extension(ledger));
 
@@ -39,7 +40,9 @@ pub async fn run<Q: QueryExtensionMarker, M: MutationExtensionMarker>(
 #[instrument(name = "cala_server.graphql", skip_all, fields(error, error.level, error.message))]
 pub async fn graphql_handler<Q: QueryExtensionMarker, M: MutationExtensionMarker>(
     headers: HeaderMap,
-    schema: Extension<Schema<graphql::CoreQuery<Q>, graphql::CoreMutation<M>, EmptySubscription>>,
+    schema: Extension<
+        Schema<graphql::CoreQuery<Q>, graphql::CoreMutation<M>, graphql::CoreSubscription>,
+    >,
     Extension(ledger): Extension<CalaLedger>,
     req: GraphQLRequest,
 ) -> GraphQLResponse {
@@ -77,7 +80,8 @@ pub async fn graphql_handler<Q: QueryExtensionMarker, M: MutationExtensionMarker
 
 async fn playground() -> impl axum::response::IntoResponse {
     axum::response::Html(async_graphql::http::playground_source(
-        async_graphql::http::GraphQLPlaygroundConfig::new("/graphql"),
+        async_graphql::http::GraphQLPlaygroundConfig::new("/graphql")
+            .subscription_endpoint("/graphql/ws"),
     ))
 }
 
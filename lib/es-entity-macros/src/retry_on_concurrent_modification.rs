@@ -16,7 +16,7 @@ pub fn make(
 
     let mut inner_fn = input.clone();
     let inner_ident = syn::Ident::new(
-        &format!("{}_exec_one", &input.sig.ident),
+        &format!("{}_exec_one", input.sig.ident),
         input.sig.ident.span(),
     );
     inner_fn.sig.ident = inner_ident.clone();
@@ -117,7 +117,7 @@ where
         self.persisted_events.iter()
     }
 
-    pub fn last_persisted(&self, n: usize) -> LastPersisted<T> {
+    pub fn last_persisted(&self, n: usize) -> LastPersisted<'_, T> {
         let start = self.persisted_events.len() - n;
         self.persisted_events[start..].iter()
     }
@@ -28,7 +28,7 @@ pub trait EsEntity: TryFromEvents<Self::Event> {
     type New: IntoEvents<Self::Event>;
 
     fn events(&self) -> &EntityEvents<Self::Event>;
-    fn last_persisted(&self, n: usize) -> crate::events::LastPersisted<Self::Event> {
+    fn last_persisted(&self, n: usize) -> crate::events::LastPersisted<'_, Self::Event> {
         self.events().last_persisted(n)
     }
 
@@ -46,6 +46,7 @@ pub trait EsRepo {
 }
 
 #[async_trait]
+#[allow(clippy::double_must_use)]
 pub trait PopulateNested<C>: EsRepo {
     async fn populate(
         &self,